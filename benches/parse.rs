@@ -0,0 +1,146 @@
+//! Time the parsing of the bundled GIR files, reporting the fastest of a few
+//! runs of each mode along with the allocations made by a run.
+//!
+//! Run with `cargo bench --bench parse`, optionally followed by `--` and the
+//! name of the modes to run.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fs,
+    hint::black_box,
+    path::Path,
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use gir_parser::{borrowed, GirStream, ParseOptions, Repository};
+
+const RUNS: usize = 5;
+
+/// Count the allocations, and the bytes they asked for.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED.fetch_add(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+fn sources() -> Vec<String> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("gir-files");
+    let mut paths = fs::read_dir(dir)
+        .expect("gir-files directory")
+        .map(|entry| entry.expect("directory entry").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "gir"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| fs::read_to_string(path).expect("readable GIR file"))
+        .collect()
+}
+
+fn bench(name: &str, sources: &[String], parse: impl Fn(&str)) {
+    let mut fastest = Duration::MAX;
+    let (mut allocations, mut allocated) = (0, 0);
+    for _ in 0..RUNS {
+        allocations = ALLOCATIONS.load(Ordering::Relaxed);
+        allocated = ALLOCATED.load(Ordering::Relaxed);
+        let start = Instant::now();
+        for source in sources {
+            parse(source);
+        }
+        fastest = fastest.min(start.elapsed());
+        allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+        allocated = ALLOCATED.load(Ordering::Relaxed) - allocated;
+    }
+    let mib = |bytes: f64| bytes / (1024.0 * 1024.0);
+    let size = sources.iter().map(String::len).sum::<usize>() as f64;
+    let throughput = mib(size) / fastest.as_secs_f64();
+    println!(
+        "{name:<12} {fastest:>10.2?} {throughput:>8.1} MiB/s {:>10} allocations {:>8.1} MiB",
+        allocations,
+        mib(allocated as f64),
+    );
+}
+
+fn main() {
+    let filter = std::env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with('-'))
+        .collect::<Vec<_>>();
+    let enabled = |name: &str| filter.is_empty() || filter.iter().any(|f| name.contains(f));
+    let sources = sources();
+
+    if enabled("default") {
+        bench("default", &sources, |source| {
+            black_box(Repository::from_str(source).expect("valid GIR file"));
+        });
+    }
+    if enabled("borrowed") {
+        bench("borrowed", &sources, |source| {
+            black_box(borrowed::Repository::parse(source).expect("valid GIR file"));
+        });
+    }
+    let modes = [
+        (
+            "skip_docs",
+            ParseOptions {
+                skip_docs: true,
+                ..Default::default()
+            },
+        ),
+        (
+            "no_spans",
+            ParseOptions {
+                spans: false,
+                ..Default::default()
+            },
+        ),
+    ];
+    for (name, options) in modes {
+        if enabled(name) {
+            bench(name, &sources, |source| {
+                black_box(
+                    Repository::from_str_with_options(source, &options).expect("valid GIR file"),
+                );
+            });
+        }
+    }
+
+    let stream = |source: &str| {
+        for event in GirStream::from_reader(source.as_bytes()).expect("readable GIR file") {
+            black_box(event.expect("valid GIR file"));
+        }
+    };
+    if enabled("stream") {
+        bench("stream", &sources, stream);
+    }
+    // Documents written on a single line, like minified ones.
+    if enabled("stream_line") {
+        let lines = sources
+            .iter()
+            .map(|source| source.replace('\n', " "))
+            .collect::<Vec<_>>();
+        bench("stream_line", &lines, stream);
+    }
+}
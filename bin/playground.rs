@@ -1,19 +1,14 @@
-use gir_parser::Repository;
+use gir_parser::{GirSearchPath, IncludeGraph};
 
 fn main() {
     let paths = std::fs::read_dir("./gir-files").unwrap();
-    let mut namespaces = std::collections::HashMap::new();
+    let gir_files = paths
+        .map(|path| path.unwrap().file_name().into_string().unwrap())
+        .filter(|file_name| file_name.ends_with(".gir"));
+    let repositories = IncludeGraph::load(gir_files, &GirSearchPath::new(["./gir-files"])).unwrap();
     let mut total_namespaces = 0;
 
-    for path in paths {
-        let path = path.unwrap().path();
-        let gir_file = path.file_name().unwrap().to_str().unwrap();
-
-        Repository::from_path_follow_namespaces_and_cache(&mut namespaces, gir_file, "./gir-files")
-            .unwrap();
-    }
-
-    for (name, repo) in &namespaces {
+    for (name, repo) in repositories.iter() {
         println!("Library: {}", repo.namespace().name());
         println!("GIR File: {}", name);
         println!("Version: {}", repo.namespace().version());
@@ -1,49 +1,40 @@
-use xmlserde_derives::XmlDeserialize;
-
 use crate::{
     attribute::Attribute,
     documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition},
+    extra::Extra,
     prelude::*,
     r#type::AnyType,
+    span::NodeSpan,
+    tree::{FromNode, Node},
     version::Version,
+    writer::{Element, ToXml},
     Stability,
 };
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
-#[xmlserde(root = b"alias")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Alias {
-    #[xmlserde(name = b"name", ty = "attr")]
     name: String,
-    #[xmlserde(name = b"c:type", ty = "attr")]
     c_type: String,
     // Common attributes
-    #[xmlserde(name = b"introspectable", ty = "attr")]
     introspectable: Option<bool>,
-    #[xmlserde(name = b"deprecated", ty = "attr")]
     deprecated: Option<bool>,
-    #[xmlserde(name = b"version", ty = "attr")]
     version: Option<Version>,
-    #[xmlserde(name = b"deprecated-version", ty = "attr")]
     deprecated_version: Option<Version>,
-    #[xmlserde(name = b"stability", ty = "attr")]
     stability: Option<Stability>,
     // Documentation
-    #[xmlserde(name = b"doc", ty = "child")]
     doc: Option<Documentation>,
-    #[xmlserde(name = b"doc-deprecated", ty = "child")]
     doc_deprecated: Option<DocDeprecated>,
-    #[xmlserde(name = b"doc-stability", ty = "child")]
     doc_stability: Option<DocStability>,
-    #[xmlserde(name = b"doc-version", ty = "child")]
     doc_version: Option<DocVersion>,
-    #[xmlserde(name = b"source-position", ty = "child")]
     source_position: Option<SourcePosition>,
     // Attributes: 0 or more
-    #[xmlserde(name = b"attribute", ty = "child")]
     attributes: Vec<Attribute>,
-    #[xmlserde(ty = "untag")]
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
     type_: AnyType,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Alias {
@@ -63,3 +54,66 @@ impl Alias {
 impl_info!(Alias);
 impl_attributable!(Alias);
 impl_documentable!(Alias);
+
+impl_extensible!(Alias);
+impl_spanned!(Alias);
+impl_builder!(Alias => AliasBuilder {
+    new {
+        name: String => name_mut;
+        c_type: String => c_type_mut;
+        type_: AnyType => ty_mut;
+    }
+    optional {
+        introspectable: bool => introspectable, introspectable_mut;
+        deprecated: bool => deprecated, deprecated_mut;
+        version: Version => version, version_mut;
+        deprecated_version: Version => deprecated_version, deprecated_version_mut;
+        stability: Stability => stability, stability_mut;
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+    }
+    list {
+        attributes: Attribute => attribute, attributes_mut;
+    }
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for Alias {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            c_type: node.attr("c:type"),
+            introspectable: node.opt_attr("introspectable"),
+            deprecated: node.opt_attr("deprecated"),
+            version: node.opt_attr("version"),
+            deprecated_version: node.opt_attr("deprecated-version"),
+            stability: node.opt_attr("stability"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            attributes: node.children("attribute"),
+            type_: node.one_of(),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Alias {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .attr("name", &self.name)
+            .attr("c:type", &self.c_type)
+            .info(self)
+            .documentation(self)
+            .attributes(self)
+            .untagged([&self.type_])
+            .extra(self)
+    }
+}
@@ -1,25 +1,28 @@
-use xmlserde_derives::XmlDeserialize;
+use crate::{
+    extra::Extra,
+    interner::Atom,
+    prelude::*,
+    r#type::{AnyType, Type},
+    span::NodeSpan,
+    tree::{FromNode, Node},
+    writer::{Element, ToXml},
+};
 
-use crate::r#type::Type;
-
-#[derive(Clone, Debug, PartialEq, Eq, Hash, XmlDeserialize)]
-#[xmlserde(root = b"array")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Array {
-    #[xmlserde(name = b"name", ty = "attr")]
-    name: Option<String>,
-    #[xmlserde(name = b"zero-terminated", ty = "attr")]
+    name: Option<Atom>,
     zero_terminated: Option<bool>,
-    #[xmlserde(name = b"fixed-size", ty = "attr")]
     fixed_size: Option<u16>,
-    #[xmlserde(name = b"introspectable", ty = "attr")]
     introspectable: Option<bool>,
-    #[xmlserde(name = b"length", ty = "attr")]
     length: Option<u32>,
-    #[xmlserde(name = b"c:type", ty = "attr")]
-    c_type: Option<String>,
-    #[xmlserde(name = b"type", ty = "child")]
-    type_: Type, // TODO: does this really has to be AnyType?
+    c_type: Option<Atom>,
+    /// The type of the elements, which is an array for the arrays of arrays
+    /// found in some GIR files.
+    element: Box<AnyType>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Array {
@@ -47,7 +50,81 @@ impl Array {
         self.c_type.as_deref()
     }
 
+    /// The type of the elements, which is either a type or an array for the
+    /// arrays of arrays.
+    pub fn element(&self) -> &AnyType {
+        &self.element
+    }
+
+    /// The type of the elements, flattening the arrays of arrays: those
+    /// return the type of the elements of their innermost array. Use
+    /// [`Array::element`] to tell them apart.
     pub fn ty(&self) -> &Type {
-        &self.type_
+        match &*self.element {
+            AnyType::Type(ty) => ty,
+            AnyType::Array(array) => array.ty(),
+        }
+    }
+
+    pub fn ty_mut(&mut self) -> &mut Type {
+        match &mut *self.element {
+            AnyType::Type(ty) => ty,
+            AnyType::Array(array) => array.ty_mut(),
+        }
+    }
+
+    /// The type of the elements of an array of arrays.
+    pub(crate) fn array(&self) -> Option<&Array> {
+        match &*self.element {
+            AnyType::Array(array) => Some(array),
+            AnyType::Type(_) => None,
+        }
+    }
+}
+
+impl_extensible!(Array);
+impl_spanned!(Array);
+impl_builder!(Array => ArrayBuilder {
+    new { element: AnyType => element_mut; }
+    optional {
+        name: Atom => name, name_mut;
+        zero_terminated: bool => zero_terminated, zero_terminated_mut;
+        fixed_size: u16 => fixed_size, fixed_size_mut;
+        introspectable: bool => introspectable, introspectable_mut;
+        length: u32 => length, length_mut;
+        c_type: Atom => c_type, c_type_mut;
+    }
+    list {}
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for Array {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.opt_attr("name"),
+            zero_terminated: node.opt_attr("zero-terminated"),
+            fixed_size: node.opt_attr("fixed-size"),
+            introspectable: node.opt_attr("introspectable"),
+            length: node.opt_attr("length"),
+            c_type: node.opt_attr("c:type"),
+            element: Box::new(node.one_of()),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Array {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .opt_attr("name", &self.name)
+            .opt_attr("zero-terminated", &self.zero_terminated)
+            .opt_attr("fixed-size", &self.fixed_size)
+            .opt_attr("introspectable", &self.introspectable)
+            .opt_attr("length", &self.length)
+            .opt_attr("c:type", &self.c_type)
+            .untagged([&*self.element])
+            .extra(self)
     }
 }
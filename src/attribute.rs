@@ -1,13 +1,20 @@
-use xmlserde_derives::XmlDeserialize;
+use crate::{
+    extra::Extra,
+    interner::Atom,
+    prelude::*,
+    span::NodeSpan,
+    tree::{FromNode, Node},
+    writer::{Element, ToXml},
+};
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, XmlDeserialize)]
-#[xmlserde(root = b"attribute")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Attribute {
-    #[xmlserde(name = b"name", ty = "attr")]
-    name: String,
-    #[xmlserde(name = b"value", ty = "attr")]
+    name: Atom,
     value: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Attribute {
@@ -19,3 +26,36 @@ impl Attribute {
         &self.value
     }
 }
+
+impl_extensible!(Attribute);
+impl_spanned!(Attribute);
+impl_builder!(Attribute => AttributeBuilder {
+    new {
+        name: Atom => name_mut;
+        value: String => value_mut;
+    }
+    optional {}
+    list {}
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for Attribute {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            value: node.attr("value"),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Attribute {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .attr("name", &self.name)
+            .attr("value", &self.value)
+            .extra(self)
+    }
+}
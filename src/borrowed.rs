@@ -0,0 +1,1572 @@
+//! A read-only model of GIR documents borrowing its strings from the parsed
+//! text, for tools which only read large files and don't want to pay for an
+//! allocation per name, C type and documentation string.
+//!
+//! The types mirror the ones at the root of the crate and expose the same
+//! accessors, including the traits of the [prelude](crate::prelude) over the
+//! [`Borrowed`] model. Strings are only copied when the XML escapes them,
+//! such as documentation containing `&lt;`.
+//!
+//! ```no_run
+//! use gir_parser::prelude::*;
+//!
+//! let source = std::fs::read_to_string("./gir-files/Gtk-4.0.gir").unwrap();
+//! let repository = gir_parser::borrowed::Repository::parse(&source).unwrap();
+//! for class in repository.namespace().classes() {
+//!     for method in class.methods() {
+//!         println!("{}", method.name());
+//!     }
+//! }
+//! ```
+
+use std::borrow::Cow;
+
+use crate::{
+    error::ParseWarning,
+    parameter::Direction,
+    repository::DocFormat,
+    schema::Kind,
+    traits::{self, Attributable, Callable as _, Documentable, FunctionLike, Model},
+    tree::{Node, Origin, Tree},
+    version::Version,
+    FunctionScope, ParseOptions, ParserError, SignalEmission, Stability, TransferOwnership,
+};
+
+const REQUIRED: &str = "required children are checked against the schema";
+
+/// The model of this module, whose nodes borrow their strings from the parsed
+/// text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Borrowed;
+
+impl Model for Borrowed {
+    type Documentation<'a> = Documentation<'a>;
+    type DocDeprecated<'a> = DocDeprecated<'a>;
+    type DocStability<'a> = DocStability<'a>;
+    type DocVersion<'a> = DocVersion<'a>;
+    type SourcePosition<'a> = SourcePosition<'a>;
+    type Attribute<'a> = Attribute<'a>;
+    type ReturnValue<'a> = ReturnValue<'a>;
+    type Parameters<'a> = Parameters<'a>;
+
+    fn name_value<'a>(attribute: &'a Attribute<'_>) -> (&'a str, &'a str) {
+        (attribute.name(), attribute.value())
+    }
+}
+
+/// The content of a node that isn't part of the schema.
+#[derive(Debug, Clone, Default)]
+struct ExtraContent<'a> {
+    attributes: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+    children: Vec<&'a str>,
+}
+
+impl<'a> ExtraContent<'a> {
+    fn build(node: Node<'_, 'a>) -> Self {
+        Self {
+            attributes: node.extra_attributes(),
+            children: node.extra_children(),
+        }
+    }
+}
+
+/// Declare the type of an element, with a field per attribute or child and
+/// their accessors.
+macro_rules! node {
+    (
+        $(#[$meta:meta])*
+        $rust_type:ident {
+            $($field:ident: $kind:ident $(<$($arg:ident),+>)? $(($($params:tt)*))?),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        pub struct $rust_type<'a> {
+            $($field: field_type!($kind $(<$($arg),+>)?),)*
+        }
+
+        impl<'a> $rust_type<'a> {
+            fn build(node: Node<'_, 'a>) -> Self {
+                Self {
+                    $($field: field_build!(node, $kind $(<$($arg),+>)? $(($($params)*))?),)*
+                }
+            }
+
+            $(field_accessor!($field: $kind $(<$($arg),+>)?);)*
+        }
+
+        $(field_traits!($rust_type, $field: $kind);)*
+    };
+}
+
+macro_rules! field_type {
+    (str) => { Cow<'a, str> };
+    (opt_str) => { Option<Cow<'a, str>> };
+    (prefixes) => { Option<Cow<'a, str>> };
+    (text) => { Cow<'a, str> };
+    (flag) => { bool };
+    (or<$value_type:ident>) => { $value_type };
+    (value<$value_type:ident>) => { Option<$value_type> };
+    (opt_ref<$value_type:ident>) => { Option<$value_type> };
+    (by_ref<$value_type:ident>) => { $value_type };
+    (children<$node:ident>) => { Vec<$node<'a>> };
+    (child<$node:ident>) => { Option<$node<'a>> };
+    (boxed<$node:ident>) => { Box<$node<'a>> };
+    (required<$node:ident>) => { $node<'a> };
+    (one_of<$node:ident>) => { $node<'a> };
+    (opt_one_of<$node:ident>) => { Option<$node<'a>> };
+    (all_of<$node:ident>) => { Vec<$node<'a>> };
+    (docs) => { Docs<'a> };
+    (attributes) => { Vec<Attribute<'a>> };
+    (info) => { InfoFields<'a> };
+    (callable) => { CallableInfo<'a> };
+    (signature) => { Signature<'a> };
+    (parameters) => { Parameters<'a> };
+    (extra) => { ExtraContent<'a> };
+    (doc_format) => { DocFormat };
+}
+
+macro_rules! field_build {
+    ($node:ident, str($attr:literal)) => {
+        $node.string($attr).unwrap_or_default()
+    };
+    ($node:ident, opt_str($attr:literal)) => {
+        $node.string($attr)
+    };
+    ($node:ident, prefixes($($attr:literal),+)) => {
+        None$(.or_else(|| $node.string($attr)))+
+    };
+    ($node:ident, text) => {
+        $node.text()
+    };
+    ($node:ident, flag($attr:literal, $default:literal)) => {
+        $node.opt_attr($attr).unwrap_or($default)
+    };
+    ($node:ident, or<$value_type:ident>($attr:literal, $default:expr)) => {
+        $node.opt_attr($attr).unwrap_or($default)
+    };
+    ($node:ident, value<$value_type:ident>($attr:literal)) => {
+        $node.opt_attr($attr)
+    };
+    ($node:ident, opt_ref<$value_type:ident>($attr:literal)) => {
+        $node.opt_attr($attr)
+    };
+    ($node:ident, by_ref<$value_type:ident>($attr:literal)) => {
+        $node.opt_attr($attr).unwrap_or_default()
+    };
+    ($node:ident, children<$child:ident>($tag:literal)) => {
+        $node.elements_with_tag($tag).map($child::build).collect()
+    };
+    ($node:ident, child<$child:ident>($tag:literal)) => {
+        $node.element($tag).map($child::build)
+    };
+    ($node:ident, required<$child:ident>($tag:literal)) => {
+        $child::build($node.element($tag).expect(REQUIRED))
+    };
+    ($node:ident, one_of<$child:ident>) => {
+        $node.elements().find_map($child::from_node).expect(REQUIRED)
+    };
+    ($node:ident, boxed<$child:ident>) => {
+        Box::new(field_build!($node, one_of<$child>))
+    };
+    ($node:ident, opt_one_of<$child:ident>) => {
+        $node.elements().find_map($child::from_node)
+    };
+    ($node:ident, all_of<$child:ident>) => {
+        $node.elements().filter_map($child::from_node).collect()
+    };
+    ($node:ident, docs) => {
+        Docs::build($node)
+    };
+    ($node:ident, attributes) => {
+        $node
+            .elements_with_tag("attribute")
+            .map(Attribute::build)
+            .collect()
+    };
+    ($node:ident, info) => {
+        InfoFields::build($node)
+    };
+    ($node:ident, callable) => {
+        CallableInfo::build($node)
+    };
+    ($node:ident, signature) => {
+        Signature::build($node)
+    };
+    ($node:ident, parameters) => {
+        Parameters::build($node.element("parameters"))
+    };
+    ($node:ident, extra) => {
+        ExtraContent::build($node)
+    };
+    ($node:ident, doc_format) => {
+        $node
+            .element("doc:format")
+            .and_then(|format| format.opt_attr("name"))
+            .unwrap_or_default()
+    };
+}
+
+macro_rules! field_accessor {
+    ($field:ident: str) => {
+        pub fn $field(&self) -> &str {
+            &self.$field
+        }
+    };
+    ($field:ident: opt_str) => {
+        pub fn $field(&self) -> Option<&str> {
+            self.$field.as_deref()
+        }
+    };
+    ($field:ident: prefixes) => {
+        pub fn $field(&self) -> impl Iterator<Item = &str> {
+            split_prefixes(self.$field.as_deref())
+        }
+    };
+    ($field:ident: text) => {
+        pub fn $field(&self) -> &str {
+            &self.$field
+        }
+    };
+    ($field:ident: flag) => {
+        pub fn $field(&self) -> bool {
+            self.$field
+        }
+    };
+    ($field:ident: or<$value_type:ident>) => {
+        pub fn $field(&self) -> $value_type {
+            self.$field
+        }
+    };
+    ($field:ident: value<$value_type:ident>) => {
+        pub fn $field(&self) -> Option<$value_type> {
+            self.$field
+        }
+    };
+    ($field:ident: opt_ref<$value_type:ident>) => {
+        pub fn $field(&self) -> Option<&$value_type> {
+            self.$field.as_ref()
+        }
+    };
+    ($field:ident: by_ref<$value_type:ident>) => {
+        pub fn $field(&self) -> &$value_type {
+            &self.$field
+        }
+    };
+    ($field:ident: children<$node:ident>) => {
+        pub fn $field(&self) -> &[$node<'a>] {
+            &self.$field
+        }
+    };
+    ($field:ident: child<$node:ident>) => {
+        pub fn $field(&self) -> Option<&$node<'a>> {
+            self.$field.as_ref()
+        }
+    };
+    // Only used by the accessors of the node.
+    ($field:ident: boxed<$node:ident>) => {
+        fn $field(&self) -> &$node<'a> {
+            &self.$field
+        }
+    };
+    ($field:ident: required<$node:ident>) => {
+        pub fn $field(&self) -> &$node<'a> {
+            &self.$field
+        }
+    };
+    ($field:ident: one_of<$node:ident>) => {
+        pub fn $field(&self) -> &$node<'a> {
+            &self.$field
+        }
+    };
+    ($field:ident: opt_one_of<$node:ident>) => {
+        pub fn $field(&self) -> Option<&$node<'a>> {
+            self.$field.as_ref()
+        }
+    };
+    ($field:ident: all_of<$node:ident>) => {
+        pub fn $field(&self) -> &[$node<'a>] {
+            &self.$field
+        }
+    };
+    // Exposed through the traits of the prelude instead.
+    ($field:ident: docs) => {};
+    ($field:ident: attributes) => {};
+    ($field:ident: info) => {};
+    ($field:ident: callable) => {};
+    ($field:ident: signature) => {};
+    ($field:ident: parameters) => {
+        pub fn $field(&self) -> &Parameters<'a> {
+            &self.$field
+        }
+    };
+    ($field:ident: extra) => {
+        /// The unknown attributes, as name/value pairs. Those are only kept
+        /// when parsing in lenient mode, as they fail the parsing otherwise.
+        pub fn extra_attributes(&self) -> &[(Cow<'a, str>, Cow<'a, str>)] {
+            &self.$field.attributes
+        }
+
+        /// The unknown child elements, as raw XML fragments.
+        pub fn extra_children(&self) -> &[&'a str] {
+            &self.$field.children
+        }
+    };
+    ($field:ident: doc_format) => {
+        pub fn $field(&self) -> DocFormat {
+            self.$field
+        }
+    };
+}
+
+/// The children of [`Documentable`](crate::prelude::Documentable) nodes,
+/// boxed as most nodes, such as types and parameters, have none of them.
+#[derive(Debug, Clone)]
+struct Docs<'a>(Option<Box<DocElements<'a>>>);
+
+#[derive(Debug, Clone)]
+struct DocElements<'a> {
+    doc: Option<Documentation<'a>>,
+    doc_deprecated: Option<DocDeprecated<'a>>,
+    doc_stability: Option<DocStability<'a>>,
+    doc_version: Option<DocVersion<'a>>,
+    source_position: Option<SourcePosition<'a>>,
+}
+
+impl<'a> Docs<'a> {
+    fn build(node: Node<'_, 'a>) -> Self {
+        let elements = DocElements {
+            doc: node.element("doc").map(Documentation::build),
+            doc_deprecated: node.element("doc-deprecated").map(DocDeprecated::build),
+            doc_stability: node.element("doc-stability").map(DocStability::build),
+            doc_version: node.element("doc-version").map(DocVersion::build),
+            source_position: node.element("source-position").map(SourcePosition::build),
+        };
+        let is_empty = elements.doc.is_none()
+            && elements.doc_deprecated.is_none()
+            && elements.doc_stability.is_none()
+            && elements.doc_version.is_none()
+            && elements.source_position.is_none();
+        Self((!is_empty).then(|| Box::new(elements)))
+    }
+
+    fn get(&self) -> Option<&DocElements<'a>> {
+        self.0.as_deref()
+    }
+}
+
+/// Implement the traits of the prelude for the fields holding their content.
+macro_rules! field_traits {
+    ($rust_type:ident, $field:ident: docs) => {
+        impl_documentable!($rust_type, $field);
+    };
+    ($rust_type:ident, $field:ident: attributes) => {
+        impl_attributable!($rust_type, $field);
+    };
+    ($rust_type:ident, $field:ident: info) => {
+        impl_info!($rust_type, $field);
+    };
+    ($rust_type:ident, $field:ident: callable) => {
+        impl_info!($rust_type, $field.info);
+        impl_callable!($rust_type, $field);
+    };
+    ($rust_type:ident, $field:ident: signature) => {
+        impl_function_like!($rust_type, $field);
+    };
+    ($rust_type:ident, $field:ident: $kind:ident) => {};
+}
+
+macro_rules! impl_documentable {
+    ($rust_type:ident, $($path:ident).+) => {
+        impl<'a> Documentable<Borrowed> for $rust_type<'a> {
+            fn doc(&self) -> Option<&Documentation<'_>> {
+                self.$($path).+.get()?.doc.as_ref()
+            }
+            fn doc_deprecated(&self) -> Option<&DocDeprecated<'_>> {
+                self.$($path).+.get()?.doc_deprecated.as_ref()
+            }
+            fn doc_stability(&self) -> Option<&DocStability<'_>> {
+                self.$($path).+.get()?.doc_stability.as_ref()
+            }
+            fn doc_version(&self) -> Option<&DocVersion<'_>> {
+                self.$($path).+.get()?.doc_version.as_ref()
+            }
+            fn source_position(&self) -> Option<&SourcePosition<'_>> {
+                self.$($path).+.get()?.source_position.as_ref()
+            }
+        }
+    };
+}
+
+macro_rules! impl_attributable {
+    ($rust_type:ident, $($path:ident).+) => {
+        impl<'a> Attributable<Borrowed> for $rust_type<'a> {
+            fn attributes(&self) -> &[Attribute<'_>] {
+                &self.$($path).+
+            }
+        }
+    };
+}
+
+/// The content of [`Info`](crate::prelude::Info) nodes.
+#[derive(Debug, Clone)]
+struct InfoFields<'a> {
+    docs: Docs<'a>,
+    attributes: Vec<Attribute<'a>>,
+    introspectable: bool,
+    deprecated: bool,
+    version: Option<Version>,
+    deprecated_version: Option<Version>,
+    stability: Option<Stability>,
+}
+
+impl<'a> InfoFields<'a> {
+    fn build(node: Node<'_, 'a>) -> Self {
+        Self {
+            docs: field_build!(node, docs),
+            attributes: field_build!(node, attributes),
+            introspectable: field_build!(node, flag("introspectable", true)),
+            deprecated: field_build!(node, flag("deprecated", false)),
+            version: node.opt_attr("version"),
+            deprecated_version: node.opt_attr("deprecated-version"),
+            stability: node.opt_attr("stability"),
+        }
+    }
+}
+
+macro_rules! impl_info {
+    ($rust_type:ident, $($path:ident).+) => {
+        impl_documentable!($rust_type, $($path).+.docs);
+        impl_attributable!($rust_type, $($path).+.attributes);
+
+        impl<'a> traits::Info<Borrowed> for $rust_type<'a> {
+            fn is_introspectable(&self) -> bool {
+                self.$($path).+.introspectable
+            }
+
+            fn is_deprecated(&self) -> bool {
+                self.$($path).+.deprecated
+            }
+
+            fn version(&self) -> Option<&Version> {
+                self.$($path).+.version.as_ref()
+            }
+
+            fn deprecated_version(&self) -> Option<&Version> {
+                self.$($path).+.deprecated_version.as_ref()
+            }
+
+            fn stability(&self) -> Option<Stability> {
+                self.$($path).+.stability
+            }
+        }
+    };
+}
+
+/// The content of [`Callable`](crate::prelude::Callable) nodes.
+#[derive(Debug, Clone)]
+struct CallableInfo<'a> {
+    info: InfoFields<'a>,
+    name: Cow<'a, str>,
+    c_identifier: Option<Cow<'a, str>>,
+    shadows: Option<Cow<'a, str>>,
+    shadowed_by: Option<Cow<'a, str>>,
+    moved_to: Option<Cow<'a, str>>,
+    async_func: Option<Cow<'a, str>>,
+    finish_func: Option<Cow<'a, str>>,
+    sync_func: Option<Cow<'a, str>>,
+}
+
+impl<'a> CallableInfo<'a> {
+    fn build(node: Node<'_, 'a>) -> Self {
+        Self {
+            info: InfoFields::build(node),
+            name: field_build!(node, str("name")),
+            c_identifier: node.string("c:identifier"),
+            shadows: node.string("shadows"),
+            shadowed_by: node.string("shadowed-by"),
+            moved_to: node.string("moved-to"),
+            async_func: node.string("glib:async-func"),
+            finish_func: node.string("glib:finish-func"),
+            sync_func: node.string("glib:sync-func"),
+        }
+    }
+}
+
+macro_rules! impl_callable {
+    ($rust_type:ident, $field:ident) => {
+        impl<'a> traits::Callable<Borrowed> for $rust_type<'a> {
+            fn name(&self) -> &str {
+                &self.$field.name
+            }
+
+            fn c_identifier(&self) -> Option<&str> {
+                self.$field.c_identifier.as_deref()
+            }
+
+            fn shadows(&self) -> Option<&str> {
+                self.$field.shadows.as_deref()
+            }
+
+            fn shadowed_by(&self) -> Option<&str> {
+                self.$field.shadowed_by.as_deref()
+            }
+
+            fn moved_to(&self) -> Option<&str> {
+                self.$field.moved_to.as_deref()
+            }
+
+            fn async_func(&self) -> Option<&str> {
+                self.$field.async_func.as_deref()
+            }
+
+            fn finish_func(&self) -> Option<&str> {
+                self.$field.finish_func.as_deref()
+            }
+
+            fn sync_func(&self) -> Option<&str> {
+                self.$field.sync_func.as_deref()
+            }
+        }
+    };
+}
+
+/// The content of [`FunctionLike`](crate::prelude::FunctionLike) nodes.
+#[derive(Debug, Clone)]
+struct Signature<'a> {
+    throws: bool,
+    return_value: ReturnValue<'a>,
+    parameters: Parameters<'a>,
+}
+
+impl<'a> Signature<'a> {
+    fn build(node: Node<'_, 'a>) -> Self {
+        Self {
+            throws: field_build!(node, flag("throws", false)),
+            return_value: field_build!(node, required<ReturnValue>("return-value")),
+            parameters: field_build!(node, parameters),
+        }
+    }
+}
+
+macro_rules! impl_function_like {
+    ($rust_type:ident, $field:ident) => {
+        impl<'a> FunctionLike<Borrowed> for $rust_type<'a> {
+            fn throws(&self) -> bool {
+                self.$field.throws
+            }
+
+            fn return_value(&self) -> &ReturnValue<'_> {
+                &self.$field.return_value
+            }
+
+            fn parameters(&self) -> &Parameters<'_> {
+                &self.$field.parameters
+            }
+        }
+    };
+}
+
+/// Iterators over the callables of a type.
+macro_rules! callables {
+    ($($rust_type:ident),*) => {
+        $(impl<'a> $rust_type<'a> {
+            pub fn constructors(&self) -> impl Iterator<Item = &Function<'a>> {
+                self.callables.iter().filter_map(|c| match c {
+                    Callable::Constructor(f) => Some(f),
+                    _ => None,
+                })
+            }
+
+            pub fn methods(&self) -> impl Iterator<Item = &Method<'a>> {
+                self.callables.iter().filter_map(|c| match c {
+                    Callable::Method(m) => Some(m),
+                    _ => None,
+                })
+            }
+
+            pub fn functions(&self) -> impl Iterator<Item = &Function<'a>> {
+                self.callables.iter().filter_map(|c| match c {
+                    Callable::Function(f) => Some(f),
+                    _ => None,
+                })
+            }
+        })*
+    };
+}
+
+/// Declare the enum of the fields of a compound type.
+macro_rules! compound_field {
+    ($(#[$meta:meta])* $rust_type:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        #[allow(clippy::large_enum_variant)]
+        pub enum $rust_type<'a> {
+            Field(Field<'a>),
+            Union(Union<'a>),
+            Record(Record<'a>),
+            Callback(Callback<'a>),
+        }
+
+        impl<'a> $rust_type<'a> {
+            fn from_node(node: Node<'_, 'a>) -> Option<Self> {
+                match node.tag() {
+                    "field" => Some(Self::Field(Field::build(node))),
+                    "union" => Some(Self::Union(Union::build(node))),
+                    "record" => Some(Self::Record(Record::build(node))),
+                    "callback" => Some(Self::Callback(Callback::build(node))),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+node! {
+    /// A GIR document, parsed without copying its strings.
+    Repository {
+        version: opt_ref<Version>("version"),
+        c_identifier_prefixes: prefixes("c:identifier-prefixes"),
+        c_symbol_prefixes: prefixes("c:symbol-prefixes"),
+        namespace_includes: children<NamespaceInclude>("include"),
+        header_includes: children<HeaderInclude>("c:include"),
+        packages: children<Package>("package"),
+        namespace: required<Namespace>("namespace"),
+        doc_format: doc_format,
+        extra: extra,
+    }
+}
+
+impl<'a> Repository<'a> {
+    pub fn parse(source: &'a str) -> Result<Self, ParserError> {
+        Self::parse_with_options(source, &ParseOptions::default()).map(|(repository, _)| repository)
+    }
+
+    /// Parse a GIR document, returning the warnings collected in lenient mode.
+    pub fn parse_with_options(
+        source: &'a str,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        // The borrowed nodes have no span.
+        let options = ParseOptions {
+            spans: false,
+            ..options.clone()
+        };
+        let (tree, warnings) = Tree::parse(source, Kind::ROOT, None, &options, Origin::START)?;
+        Ok((Self::build(tree.root()), warnings))
+    }
+}
+
+fn split_prefixes(prefixes: Option<&str>) -> impl Iterator<Item = &str> {
+    prefixes
+        .filter(|prefixes| !prefixes.is_empty())
+        .map(|prefixes| prefixes.split(','))
+        .into_iter()
+        .flatten()
+}
+
+node! {
+    NamespaceInclude {
+        name: str("name"),
+        version: by_ref<Version>("version"),
+        extra: extra,
+    }
+}
+
+impl NamespaceInclude<'_> {
+    pub fn as_package(&self) -> String {
+        format!("{}-{}", self.name(), self.version())
+    }
+
+    pub fn as_package_file(&self) -> String {
+        format!("{}-{}.gir", self.name(), self.version())
+    }
+}
+
+node! {
+    HeaderInclude {
+        name: str("name"),
+        extra: extra,
+    }
+}
+
+node! {
+    Package {
+        name: str("name"),
+        extra: extra,
+    }
+}
+
+node! {
+    Namespace {
+        attributes: attributes,
+        name: str("name"),
+        version: by_ref<Version>("version"),
+        c_identifier_prefixes: prefixes("c:identifier-prefixes", "c:prefix"),
+        c_symbol_prefixes: prefixes("c:symbol-prefixes"),
+        shared_library: opt_str("shared-library"),
+        aliases: children<Alias>("alias"),
+        constants: children<Constant>("constant"),
+        functions: children<Function>("function"),
+        inlined_functions: children<FunctionInline>("function-inline"),
+        macros: children<FunctionMacro>("function-macro"),
+        enums: children<Enumeration>("enumeration"),
+        flags: children<BitField>("bitfield"),
+        unions: children<Union>("union"),
+        boxed: children<Boxed>("glib:boxed"),
+        doc_sections: children<DocSection>("docsection"),
+        records: children<Record>("record"),
+        classes: children<Class>("class"),
+        callbacks: children<Callback>("callback"),
+        interfaces: children<Interface>("interface"),
+        extra: extra,
+    }
+}
+
+impl Namespace<'_> {
+    /// Copied from the old gir
+    pub fn link_name(&self) -> Option<&str> {
+        let mut s = self.shared_library()?;
+
+        if s.starts_with("lib") {
+            s = &s[3..];
+        }
+
+        if let Some(offset) = s.rfind(".so") {
+            s = &s[..offset];
+        } else if let Some(offset) = s.rfind(".dll") {
+            s = &s[..offset];
+            if let Some(offset) = s.rfind('-') {
+                s = &s[..offset];
+            }
+        }
+
+        Some(s)
+    }
+}
+
+node! {
+    Documentation {
+        space: opt_str("xml:space"),
+        whitespace: opt_str("xml:whitespace"),
+        filename: opt_str("filename"),
+        line: opt_str("line"),
+        column: opt_str("column"),
+        text: text,
+    }
+}
+
+node! {
+    DocDeprecated {
+        space: opt_str("xml:space"),
+        whitespace: opt_str("xml:whitespace"),
+        text: text,
+    }
+}
+
+node! {
+    DocStability {
+        space: opt_str("xml:space"),
+        whitespace: opt_str("xml:whitespace"),
+        text: text,
+    }
+}
+
+node! {
+    DocVersion {
+        space: opt_str("xml:space"),
+        whitespace: opt_str("xml:whitespace"),
+        text: text,
+    }
+}
+
+node! {
+    SourcePosition {
+        filename: str("filename"),
+        line: str("line"),
+        column: opt_str("column"),
+        extra: extra,
+    }
+}
+
+node! {
+    Attribute {
+        name: str("name"),
+        value: str("value"),
+        extra: extra,
+    }
+}
+
+node! {
+    DocSection {
+        docs: docs,
+        name: str("name"),
+        extra: extra,
+    }
+}
+
+node! {
+    Alias {
+        info: info,
+        name: str("name"),
+        c_type: str("c:type"),
+        ty: one_of<AnyType>,
+        extra: extra,
+    }
+}
+
+node! {
+    Type {
+        docs: docs,
+        name: opt_str("name"),
+        c_type: opt_str("c:type"),
+        is_introspectable: flag("introspectable", true),
+        parameters: all_of<AnyType>,
+        extra: extra,
+    }
+}
+
+impl<'a> Type<'a> {
+    /// The type parameters which aren't arrays, in order.
+    pub fn types(&self) -> impl Iterator<Item = &Type<'a>> {
+        self.parameters()
+            .iter()
+            .filter_map(|parameter| match parameter {
+                AnyType::Type(ty) => Some(ty),
+                AnyType::Array(_) => None,
+            })
+    }
+
+    /// The type parameters which are arrays, in order.
+    pub fn arrays(&self) -> impl Iterator<Item = &Array<'a>> {
+        self.parameters()
+            .iter()
+            .filter_map(|parameter| match parameter {
+                AnyType::Array(array) => Some(array),
+                AnyType::Type(_) => None,
+            })
+    }
+}
+
+node! {
+    Array {
+        name: opt_str("name"),
+        zero_terminated: value<bool>("zero-terminated"),
+        fixed_size: value<u16>("fixed-size"),
+        is_introspectable: flag("introspectable", true),
+        length: value<u32>("length"),
+        c_type: opt_str("c:type"),
+        element: boxed<AnyType>,
+        extra: extra,
+    }
+}
+
+impl<'a> Array<'a> {
+    /// The type of the elements, flattening the arrays of arrays: those
+    /// return the type of the elements of their innermost array. Use
+    /// [`Array::element`] to tell them apart.
+    pub fn ty(&self) -> &Type<'a> {
+        match self.element() {
+            AnyType::Type(ty) => ty,
+            AnyType::Array(array) => array.ty(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum AnyType<'a> {
+    Type(Type<'a>),
+    Array(Array<'a>),
+}
+
+impl<'a> AnyType<'a> {
+    fn from_node(node: Node<'_, 'a>) -> Option<Self> {
+        match node.tag() {
+            "type" => Some(Self::Type(Type::build(node))),
+            "array" => Some(Self::Array(Array::build(node))),
+            _ => None,
+        }
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(self, Self::Array(_))
+    }
+
+    pub fn as_array(&self) -> &Array<'a> {
+        match self {
+            Self::Array(array) => array,
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn is_type(&self) -> bool {
+        matches!(self, Self::Type(_))
+    }
+
+    pub fn as_type(&self) -> &Type<'a> {
+        match self {
+            Self::Type(ty) => ty,
+            _ => unreachable!(),
+        }
+    }
+}
+
+node! {
+    Constant {
+        info: info,
+        name: str("name"),
+        value: str("value"),
+        c_type: opt_str("c:type"),
+        c_identifier: opt_str("c:identifier"),
+        ty: one_of<AnyType>,
+        extra: extra,
+    }
+}
+
+node! {
+    Member {
+        info: info,
+        name: str("name"),
+        value: str("value"),
+        c_identifier: str("c:identifier"),
+        g_nick: opt_str("glib:nick"),
+        g_name: opt_str("glib:name"),
+        extra: extra,
+    }
+}
+
+node! {
+    Enumeration {
+        info: info,
+        name: str("name"),
+        c_type: str("c:type"),
+        g_type_name: opt_str("glib:type-name"),
+        g_get_type: opt_str("glib:get-type"),
+        g_error_domain: opt_str("glib:error-domain"),
+        members: children<Member>("member"),
+        functions: children<Function>("function"),
+        inlined_functions: children<FunctionInline>("function-inline"),
+        extra: extra,
+    }
+}
+
+node! {
+    BitField {
+        info: info,
+        name: str("name"),
+        c_type: str("c:type"),
+        g_get_type: opt_str("glib:get-type"),
+        g_type_name: opt_str("glib:type-name"),
+        members: children<Member>("member"),
+        functions: children<Function>("function"),
+        inlined_functions: children<FunctionInline>("function-inline"),
+        extra: extra,
+    }
+}
+
+node! {
+    Boxed {
+        info: info,
+        g_name: str("glib:name"),
+        is_foreign: flag("foreign", false),
+        g_is_gtype_struct_for: opt_str("glib:is-gtype-struct-for"),
+        g_type_name: opt_str("glib:type-name"),
+        g_get_type: opt_str("glib:get-type"),
+        symbol_prefix: opt_str("c:symbol-prefix"),
+        copy_function: opt_str("copy-function"),
+        free_function: opt_str("free-function"),
+        functions: children<Function>("function"),
+        inlined_functions: children<FunctionInline>("function-inline"),
+        extra: extra,
+    }
+}
+
+node! {
+    ReturnValue {
+        docs: docs,
+        attributes: attributes,
+        is_introspectable: flag("introspectable", true),
+        is_nullable: value<bool>("nullable"),
+        closure: value<usize>("closure"),
+        scope: value<FunctionScope>("scope"),
+        destroy: value<usize>("destroy"),
+        is_skip: value<bool>("skip"),
+        is_allow_none: value<bool>("allow-none"),
+        transfer_ownership: value<TransferOwnership>("transfer-ownership"),
+        ty: one_of<AnyType>,
+        extra: extra,
+    }
+}
+
+/// The parameters of a callable, which may be omitted when it has none.
+#[derive(Debug, Clone)]
+pub struct Parameters<'a> {
+    instance_parameter: Option<InstanceParameter<'a>>,
+    parameter: Vec<Parameter<'a>>,
+    extra: ExtraContent<'a>,
+}
+
+impl<'a> Parameters<'a> {
+    fn build(node: Option<Node<'_, 'a>>) -> Self {
+        let Some(node) = node else {
+            return Self {
+                instance_parameter: None,
+                parameter: Vec::new(),
+                extra: ExtraContent::default(),
+            };
+        };
+        Self {
+            instance_parameter: field_build!(node, child<InstanceParameter>("instance-parameter")),
+            parameter: field_build!(node, children<Parameter>("parameter")),
+            extra: field_build!(node, extra),
+        }
+    }
+
+    field_accessor!(extra: extra);
+
+    pub fn is_empty(&self) -> bool {
+        self.instance_parameter.is_none() && self.parameter.is_empty()
+    }
+
+    pub fn instance(&self) -> Option<&InstanceParameter<'a>> {
+        self.instance_parameter.as_ref()
+    }
+
+    pub fn inner(&self) -> &[Parameter<'a>] {
+        &self.parameter
+    }
+
+    /// Returns an iterator over all parameters
+    pub fn all(&self) -> impl Iterator<Item = AnyParameter<'_, 'a>> {
+        self.instance_parameter
+            .iter()
+            .map(AnyParameter::Instance)
+            .chain(self.parameter.iter().map(AnyParameter::Regular))
+    }
+}
+
+impl<'b, 'a> IntoIterator for &'b Parameters<'a> {
+    type Item = &'b Parameter<'a>;
+    type IntoIter = std::slice::Iter<'b, Parameter<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.parameter.iter()
+    }
+}
+
+impl<'a> std::ops::Deref for Parameters<'a> {
+    type Target = [Parameter<'a>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.parameter
+    }
+}
+
+/// Represents either an instance parameter or a regular parameter
+#[derive(Debug, Clone, Copy)]
+pub enum AnyParameter<'b, 'a> {
+    Instance(&'b InstanceParameter<'a>),
+    Regular(&'b Parameter<'a>),
+}
+
+impl<'b, 'a> AnyParameter<'b, 'a> {
+    pub fn name(&self) -> &'b str {
+        match self {
+            Self::Instance(p) => p.name(),
+            Self::Regular(p) => p.name(),
+        }
+    }
+
+    pub fn is_instance(&self) -> bool {
+        matches!(self, Self::Instance(_))
+    }
+
+    pub fn is_regular(&self) -> bool {
+        matches!(self, Self::Regular(_))
+    }
+
+    pub fn as_instance(&self) -> &'b InstanceParameter<'a> {
+        match self {
+            Self::Instance(p) => p,
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn as_regular(&self) -> &'b Parameter<'a> {
+        match self {
+            Self::Regular(p) => p,
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn is_nullable(&self) -> Option<bool> {
+        match self {
+            Self::Instance(p) => p.is_nullable(),
+            Self::Regular(p) => p.is_nullable(),
+        }
+    }
+
+    pub fn is_allow_none(&self) -> Option<bool> {
+        match self {
+            Self::Instance(p) => p.is_allow_none(),
+            Self::Regular(p) => p.is_allow_none(),
+        }
+    }
+
+    pub fn direction(&self) -> Option<Direction> {
+        match self {
+            Self::Instance(p) => p.direction(),
+            Self::Regular(p) => p.direction(),
+        }
+    }
+
+    pub fn is_caller_allocates(&self) -> Option<bool> {
+        match self {
+            Self::Instance(p) => p.is_caller_allocates(),
+            Self::Regular(p) => p.is_caller_allocates(),
+        }
+    }
+
+    pub fn transfer_ownership(&self) -> Option<TransferOwnership> {
+        match self {
+            Self::Instance(p) => p.transfer_ownership(),
+            Self::Regular(p) => p.transfer_ownership(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum ParameterType<'a> {
+    Type(Type<'a>),
+    Array(Array<'a>),
+    VarArgs,
+}
+
+impl<'a> ParameterType<'a> {
+    fn from_node(node: Node<'_, 'a>) -> Option<Self> {
+        match node.tag() {
+            "type" => Some(Self::Type(Type::build(node))),
+            "array" => Some(Self::Array(Array::build(node))),
+            "varargs" => Some(Self::VarArgs),
+            _ => None,
+        }
+    }
+}
+
+node! {
+    Parameter {
+        docs: docs,
+        attributes: attributes,
+        name: str("name"),
+        is_nullable: value<bool>("nullable"),
+        is_allow_none: value<bool>("allow-none"),
+        is_introspectable: flag("introspectable", true),
+        scope: value<FunctionScope>("scope"),
+        closure: value<usize>("closure"),
+        destroy: value<usize>("destroy"),
+        direction: value<Direction>("direction"),
+        is_caller_allocates: value<bool>("caller-allocates"),
+        is_optional: value<bool>("optional"),
+        is_skip: value<bool>("skip"),
+        transfer_ownership: value<TransferOwnership>("transfer-ownership"),
+        ty: opt_one_of<ParameterType>,
+        extra: extra,
+    }
+}
+
+impl Parameter<'_> {
+    pub fn is_length(&self) -> bool {
+        if !self.direction().is_some_and(|d| d.is_in()) {
+            return false;
+        }
+        let len = self.name().len();
+        if len >= 3 && &self.name()[len - 3..len] == "len" {
+            return true;
+        }
+
+        self.name().contains("length")
+    }
+}
+
+node! {
+    InstanceParameter {
+        docs: docs,
+        name: str("name"),
+        is_nullable: value<bool>("nullable"),
+        is_allow_none: value<bool>("allow-none"),
+        direction: value<Direction>("direction"),
+        is_caller_allocates: value<bool>("caller-allocates"),
+        transfer_ownership: value<TransferOwnership>("transfer-ownership"),
+        ty: child<Type>("type"),
+        extra: extra,
+    }
+}
+
+node! {
+    Function {
+        callable: callable,
+        signature: signature,
+        extra: extra,
+    }
+}
+
+node! {
+    FunctionInline {
+        callable: callable,
+        signature: signature,
+        extra: extra,
+    }
+}
+
+node! {
+    Method {
+        callable: callable,
+        signature: signature,
+        get_property: opt_str("glib:get-property"),
+        set_property: opt_str("glib:set-property"),
+        extra: extra,
+    }
+}
+
+node! {
+    MethodInline {
+        callable: callable,
+        signature: signature,
+        extra: extra,
+    }
+}
+
+node! {
+    VirtualMethod {
+        callable: callable,
+        signature: signature,
+        invoker: opt_str("invoker"),
+        extra: extra,
+    }
+}
+
+node! {
+    FunctionMacro {
+        callable: callable,
+        parameters: parameters,
+        throws: value<bool>("throws"),
+        extra: extra,
+    }
+}
+
+node! {
+    Callback {
+        info: info,
+        signature: signature,
+        name: str("name"),
+        c_type: opt_str("c:type"),
+        extra: extra,
+    }
+}
+
+node! {
+    Signal {
+        info: info,
+        return_value: required<ReturnValue>("return-value"),
+        parameters: parameters,
+        name: str("name"),
+        is_detailed: flag("detailed", false),
+        when: value<SignalEmission>("when"),
+        is_action: flag("action", false),
+        is_no_hooks: flag("no-hooks", false),
+        is_no_recurse: flag("no-recurse", false),
+        emitter: opt_str("emitter"),
+        extra: extra,
+    }
+}
+
+node! {
+    Property {
+        info: info,
+        name: str("name"),
+        is_readable: flag("readable", true),
+        is_writable: flag("writable", false),
+        is_construct: flag("construct", false),
+        is_construct_only: flag("construct-only", false),
+        getter: opt_str("getter"),
+        setter: opt_str("setter"),
+        default_value: opt_str("default-value"),
+        transfer_ownership: or<TransferOwnership>("transfer-ownership", TransferOwnership::None),
+        ty: one_of<AnyType>,
+        extra: extra,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Callable<'a> {
+    Constructor(Function<'a>),
+    Method(Method<'a>),
+    Function(Function<'a>),
+}
+
+impl<'a> Callable<'a> {
+    fn from_node(node: Node<'_, 'a>) -> Option<Self> {
+        match node.tag() {
+            "constructor" => Some(Self::Constructor(Function::build(node))),
+            "method" => Some(Self::Method(Method::build(node))),
+            "function" => Some(Self::Function(Function::build(node))),
+            _ => None,
+        }
+    }
+
+    pub fn is_constructor(&self) -> bool {
+        matches!(self, Self::Constructor(_))
+    }
+
+    pub fn is_method(&self) -> bool {
+        matches!(self, Self::Method(_))
+    }
+
+    pub fn is_function(&self) -> bool {
+        matches!(self, Self::Function(_))
+    }
+
+    pub fn as_function(&self) -> Option<&Function<'a>> {
+        match self {
+            Self::Constructor(f) | Self::Function(f) => Some(f),
+            _ => None,
+        }
+    }
+
+    pub fn as_method(&self) -> Option<&Method<'a>> {
+        match self {
+            Self::Method(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Constructor(f) | Self::Function(f) => f.name(),
+            Self::Method(m) => m.name(),
+        }
+    }
+
+    pub fn c_identifier(&self) -> Option<&str> {
+        match self {
+            Self::Constructor(f) | Self::Function(f) => f.c_identifier(),
+            Self::Method(m) => m.c_identifier(),
+        }
+    }
+
+    pub fn moved_to(&self) -> Option<&str> {
+        match self {
+            Self::Constructor(f) | Self::Function(f) => f.moved_to(),
+            Self::Method(m) => m.moved_to(),
+        }
+    }
+
+    pub fn throws(&self) -> bool {
+        match self {
+            Self::Constructor(f) | Self::Function(f) => f.throws(),
+            Self::Method(m) => m.throws(),
+        }
+    }
+
+    pub fn return_value(&self) -> &ReturnValue<'_> {
+        match self {
+            Self::Constructor(f) | Self::Function(f) => f.return_value(),
+            Self::Method(m) => m.return_value(),
+        }
+    }
+
+    pub fn parameters(&self) -> &Parameters<'_> {
+        match self {
+            Self::Constructor(f) | Self::Function(f) => f.parameters(),
+            Self::Method(m) => m.parameters(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum FieldType<'a> {
+    Type(Type<'a>),
+    Callback(Callback<'a>),
+    Array(Array<'a>),
+}
+
+impl<'a> FieldType<'a> {
+    fn from_node(node: Node<'_, 'a>) -> Option<Self> {
+        match node.tag() {
+            "type" => Some(Self::Type(Type::build(node))),
+            "callback" => Some(Self::Callback(Callback::build(node))),
+            "array" => Some(Self::Array(Array::build(node))),
+            _ => None,
+        }
+    }
+
+    pub fn is_type(&self) -> bool {
+        matches!(self, Self::Type(_))
+    }
+
+    pub fn as_type(&self) -> &Type<'a> {
+        if let Self::Type(t) = &self {
+            t
+        } else {
+            unreachable!()
+        }
+    }
+
+    pub fn is_callback(&self) -> bool {
+        matches!(self, Self::Callback(_))
+    }
+
+    pub fn as_callback(&self) -> &Callback<'a> {
+        if let Self::Callback(c) = &self {
+            c
+        } else {
+            unreachable!()
+        }
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(self, Self::Array(_))
+    }
+
+    pub fn as_array(&self) -> &Array<'a> {
+        if let Self::Array(a) = &self {
+            a
+        } else {
+            unreachable!()
+        }
+    }
+}
+
+node! {
+    Field {
+        info: info,
+        name: str("name"),
+        is_readable: flag("readable", false),
+        is_writable: flag("writable", false),
+        is_private: flag("private", false),
+        bits: value<u8>("bits"),
+        ty: one_of<FieldType>,
+        extra: extra,
+    }
+}
+
+compound_field!(RecordField);
+
+node! {
+    Record {
+        info: info,
+        name: opt_str("name"),
+        c_type: opt_str("c:type"),
+        is_disguised: flag("disguised", false),
+        is_opaque: flag("opaque", false),
+        is_pointer: flag("pointer", false),
+        is_foreign: flag("foreign", false),
+        g_is_gtype_struct_for: opt_str("glib:is-gtype-struct-for"),
+        g_type_name: opt_str("glib:type-name"),
+        g_get_type: opt_str("glib:get-type"),
+        symbol_prefix: opt_str("c:symbol-prefix"),
+        copy_function: opt_str("copy-function"),
+        free_function: opt_str("free-function"),
+        fields: all_of<RecordField>,
+        callables: all_of<Callable>,
+        inlined_methods: children<MethodInline>("method-inline"),
+        inlined_functions: children<FunctionInline>("function-inline"),
+        extra: extra,
+    }
+}
+
+compound_field!(UnionField);
+
+node! {
+    Union {
+        info: info,
+        name: opt_str("name"),
+        c_type: opt_str("c:type"),
+        c_symbol_prefix: opt_str("c:symbol-prefix"),
+        g_type_name: opt_str("glib:type-name"),
+        g_get_type: opt_str("glib:get-type"),
+        copy_function: opt_str("copy-function"),
+        free_function: opt_str("free-function"),
+        fields: all_of<UnionField>,
+        callables: all_of<Callable>,
+        inlined_methods: children<MethodInline>("method-inline"),
+        inlined_functions: children<FunctionInline>("function-inline"),
+        extra: extra,
+    }
+}
+
+node! {
+    Implements {
+        name: str("name"),
+        extra: extra,
+    }
+}
+
+node! {
+    Prerequisite {
+        name: str("name"),
+        extra: extra,
+    }
+}
+
+compound_field!(ClassField);
+
+node! {
+    Class {
+        info: info,
+        name: str("name"),
+        is_fundamental: flag("glib:fundamental", false),
+        is_final: flag("final", false),
+        is_abstract: flag("abstract", false),
+        symbol_prefix: opt_str("c:symbol-prefix"),
+        c_type: opt_str("c:type"),
+        parent: opt_str("parent"),
+        g_type_name: str("glib:type-name"),
+        g_get_type: str("glib:get-type"),
+        g_type_struct: opt_str("glib:type-struct"),
+        g_ref_func: opt_str("glib:ref-func"),
+        g_unref_func: opt_str("glib:unref-func"),
+        g_set_value_func: opt_str("glib:set-value-func"),
+        g_get_value_func: opt_str("glib:get-value-func"),
+        implements: children<Implements>("implements"),
+        callables: all_of<Callable>,
+        inlined_methods: children<MethodInline>("method-inline"),
+        inlined_functions: children<FunctionInline>("function-inline"),
+        virtual_methods: children<VirtualMethod>("virtual-method"),
+        fields: all_of<ClassField>,
+        properties: children<Property>("property"),
+        signals: children<Signal>("glib:signal"),
+        constants: children<Constant>("constant"),
+        extra: extra,
+    }
+}
+
+compound_field!(InterfaceField);
+
+node! {
+    Interface {
+        info: info,
+        name: str("name"),
+        symbol_prefix: opt_str("c:symbol-prefix"),
+        c_type: opt_str("c:type"),
+        g_type_name: str("glib:type-name"),
+        g_get_type: str("glib:get-type"),
+        g_type_struct: opt_str("glib:type-struct"),
+        prerequisites: children<Prerequisite>("prerequisite"),
+        implements: children<Implements>("implements"),
+        callables: all_of<Callable>,
+        inlined_methods: children<MethodInline>("method-inline"),
+        inlined_functions: children<FunctionInline>("function-inline"),
+        virtual_methods: children<VirtualMethod>("virtual-method"),
+        fields: all_of<InterfaceField>,
+        properties: children<Property>("property"),
+        signals: children<Signal>("glib:signal"),
+        constants: children<Constant>("constant"),
+        extra: extra,
+    }
+}
+
+callables!(Record, Union, Class, Interface);
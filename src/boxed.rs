@@ -1,65 +1,49 @@
-use xmlserde_derives::XmlDeserialize;
-
 use crate::{
     attribute::Attribute,
     documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition},
+    extra::Extra,
     function::{Function, FunctionInline},
     prelude::*,
+    span::NodeSpan,
+    tree::{FromNode, Node},
     version::Version,
+    writer::{Element, ToXml},
     Stability,
 };
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
-#[xmlserde(root = b"glib:boxed")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Boxed {
-    #[xmlserde(name = b"glib:name", ty = "attr")]
     g_name: String,
-    #[xmlserde(name = b"glib:type-name", ty = "attr")]
     g_type_name: Option<String>,
-    #[xmlserde(name = b"glib:get-type", ty = "attr")]
     g_get_type: Option<String>,
-    #[xmlserde(name = b"c:symbol-prefix", ty = "attr")]
     symbol_prefix: Option<String>,
-    #[xmlserde(name = b"foreign", ty = "attr")]
     foreign: Option<bool>,
-    #[xmlserde(name = b"glib:is-gtype-struct-for", ty = "attr")]
     g_is_gtype_struct_for: Option<String>,
-    #[xmlserde(name = b"copy-function", ty = "attr")]
     copy_function: Option<String>,
-    #[xmlserde(name = b"free-function", ty = "attr")]
     free_function: Option<String>,
 
     // Common attributes
-    #[xmlserde(name = b"introspectable", ty = "attr")]
     introspectable: Option<bool>,
-    #[xmlserde(name = b"deprecated", ty = "attr")]
     deprecated: Option<bool>,
-    #[xmlserde(name = b"version", ty = "attr")]
     version: Option<Version>,
-    #[xmlserde(name = b"deprecated-version", ty = "attr")]
     deprecated_version: Option<Version>,
-    #[xmlserde(name = b"stability", ty = "attr")]
     stability: Option<Stability>,
     // Documentation
-    #[xmlserde(name = b"doc", ty = "child")]
     doc: Option<Documentation>,
-    #[xmlserde(name = b"doc-deprecated", ty = "child")]
     doc_deprecated: Option<DocDeprecated>,
-    #[xmlserde(name = b"doc-stability", ty = "child")]
     doc_stability: Option<DocStability>,
-    #[xmlserde(name = b"doc-version", ty = "child")]
     doc_version: Option<DocVersion>,
-    #[xmlserde(name = b"source-position", ty = "child")]
     source_position: Option<SourcePosition>,
     // Attributes: 0 or more
-    #[xmlserde(name = b"attribute", ty = "child")]
     attributes: Vec<Attribute>,
 
-    #[xmlserde(name = b"function", ty = "child")]
     functions: Vec<Function>,
-    #[xmlserde(name = b"function-inline", ty = "child")]
+    #[cfg_attr(feature = "serde", serde(rename = "inlined_functions"))]
     inline_functions: Vec<FunctionInline>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Boxed {
@@ -108,3 +92,87 @@ impl Boxed {
 impl_info!(Boxed);
 impl_attributable!(Boxed);
 impl_documentable!(Boxed);
+
+impl_extensible!(Boxed);
+impl_spanned!(Boxed);
+impl_builder!(Boxed => BoxedBuilder {
+    new {
+        g_name: String => g_name_mut;
+    }
+    optional {
+        g_type_name: String => g_type_name, g_type_name_mut;
+        g_get_type: String => g_get_type, g_get_type_mut;
+        symbol_prefix: String => symbol_prefix, symbol_prefix_mut;
+        foreign: bool => foreign, foreign_mut;
+        g_is_gtype_struct_for: String => g_is_gtype_struct_for, g_is_gtype_struct_for_mut;
+        copy_function: String => copy_function, copy_function_mut;
+        free_function: String => free_function, free_function_mut;
+        introspectable: bool => introspectable, introspectable_mut;
+        deprecated: bool => deprecated, deprecated_mut;
+        version: Version => version, version_mut;
+        deprecated_version: Version => deprecated_version, deprecated_version_mut;
+        stability: Stability => stability, stability_mut;
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+    }
+    list {
+        attributes: Attribute => attribute, attributes_mut;
+        functions: Function => function, functions_mut;
+        inline_functions: FunctionInline => inlined_function, inlined_functions_mut;
+    }
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for Boxed {
+    fn from_node(node: Node) -> Self {
+        Self {
+            g_name: node.attr("glib:name"),
+            g_type_name: node.opt_attr("glib:type-name"),
+            g_get_type: node.opt_attr("glib:get-type"),
+            symbol_prefix: node.opt_attr("c:symbol-prefix"),
+            foreign: node.opt_attr("foreign"),
+            g_is_gtype_struct_for: node.opt_attr("glib:is-gtype-struct-for"),
+            copy_function: node.opt_attr("copy-function"),
+            free_function: node.opt_attr("free-function"),
+            introspectable: node.opt_attr("introspectable"),
+            deprecated: node.opt_attr("deprecated"),
+            version: node.opt_attr("version"),
+            deprecated_version: node.opt_attr("deprecated-version"),
+            stability: node.opt_attr("stability"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            attributes: node.children("attribute"),
+            functions: node.children("function"),
+            inline_functions: node.children("function-inline"),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Boxed {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .attr("glib:name", &self.g_name)
+            .opt_attr("glib:type-name", &self.g_type_name)
+            .opt_attr("glib:get-type", &self.g_get_type)
+            .opt_attr("c:symbol-prefix", &self.symbol_prefix)
+            .opt_attr("foreign", &self.foreign)
+            .opt_attr("glib:is-gtype-struct-for", &self.g_is_gtype_struct_for)
+            .opt_attr("copy-function", &self.copy_function)
+            .opt_attr("free-function", &self.free_function)
+            .info(self)
+            .documentation(self)
+            .attributes(self)
+            .children("function", &self.functions)
+            .children("function-inline", &self.inline_functions)
+            .extra(self)
+    }
+}
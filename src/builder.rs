@@ -0,0 +1,102 @@
+/// Generate a builder for a node, along with `&mut` accessors to its fields so
+/// a parsed tree can be patched.
+///
+/// The `new` fields are passed to `builder()`, the `optional` fields, `list`
+/// items and `value`s are set through the builder, the latter starting from
+/// their default, while the `hidden` fields are left to their default or to
+/// the value following them. Each field is followed by the name of its
+/// builder method, if any, and of its `&mut` accessor. The accessor of a `new`
+/// field can be left out to write it by hand, in which case the field may also
+/// be an `Option` of its type.
+macro_rules! impl_builder {
+    (@hidden) => {
+        Default::default()
+    };
+    (@hidden $value:expr) => {
+        $value
+    };
+    (
+        $rust_type:ident => $builder:ident {
+            new { $($new:ident: $new_ty:ty $(=> $new_mut:ident)?;)* }
+            optional { $($opt:ident: $opt_ty:ty => $opt_set:ident, $opt_mut:ident;)* }
+            list { $($list:ident: $list_ty:ty => $list_push:ident, $list_mut:ident;)* }
+            value { $($value:ident: $value_ty:ty => $value_set:ident, $value_mut:ident;)* }
+            hidden { $($hidden:ident $(= $hidden_value:expr)?),* }
+        }
+    ) => {
+        #[doc = concat!("A builder for [`", stringify!($rust_type), "`].")]
+        #[derive(Clone, Debug)]
+        #[must_use]
+        pub struct $builder($rust_type);
+
+        impl $rust_type {
+            pub fn builder($($new: impl Into<$new_ty>),*) -> $builder {
+                $builder(Self {
+                    $($new: Into::<$new_ty>::into($new).into(),)*
+                    $($opt: None,)*
+                    $($list: Vec::new(),)*
+                    $($value: Default::default(),)*
+                    $($hidden: impl_builder!(@hidden $($hidden_value)?),)*
+                })
+            }
+
+            $($(pub fn $new_mut(&mut self) -> &mut $new_ty {
+                &mut self.$new
+            })?)*
+
+            $(pub fn $opt_mut(&mut self) -> &mut Option<$opt_ty> {
+                &mut self.$opt
+            })*
+
+            $(pub fn $list_mut(&mut self) -> &mut Vec<$list_ty> {
+                &mut self.$list
+            })*
+
+            $(pub fn $value_mut(&mut self) -> &mut $value_ty {
+                &mut self.$value
+            })*
+        }
+
+        impl $builder {
+            $(pub fn $opt_set(mut self, $opt: impl Into<$opt_ty>) -> Self {
+                self.0.$opt = Some($opt.into());
+                self
+            })*
+
+            $(pub fn $list_push(mut self, $list: $list_ty) -> Self {
+                self.0.$list.push($list);
+                self
+            })*
+
+            $(pub fn $value_set(mut self, $value: $value_ty) -> Self {
+                self.0.$value = $value;
+                self
+            })*
+
+            pub fn build(self) -> $rust_type {
+                self.0
+            }
+        }
+    };
+}
+
+/// Add the builder methods filling the [`Parameters`](crate::Parameters) of a
+/// callable, one parameter at a time.
+macro_rules! impl_parameters_builder {
+    ($builder:ident) => {
+        impl $builder {
+            pub fn instance_parameter(
+                mut self,
+                parameter: crate::parameter::InstanceParameter,
+            ) -> Self {
+                *self.0.parameters.instance_mut() = Some(parameter);
+                self
+            }
+
+            pub fn parameter(mut self, parameter: crate::parameter::Parameter) -> Self {
+                self.0.parameters.inner_mut().push(parameter);
+                self
+            }
+        }
+    };
+}
@@ -0,0 +1,252 @@
+//! Binary snapshots of parsed repositories, stored next to each other in a
+//! cache directory so reloading a file that didn't change skips the parsing.
+//!
+//! Snapshots are the `serde` representation of the model and of the
+//! warnings found while parsing it, encoded with `postcard`. They depend on
+//! the parse options changing the model, so a snapshot is only reused with
+//! the options it was built with.
+
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    compression, include_graph::IncludeGraph, repository::Repository, search_path::GirSearchPath,
+    ParseOptions, ParseWarning, ParserError,
+};
+
+const MAGIC: &[u8; 8] = b"GIRCACHE";
+/// Bumped whenever the snapshot encoding changes. Snapshots written by other
+/// versions of the crate are discarded as well, as the model itself may have
+/// changed.
+const FORMAT_VERSION: u32 = 9;
+/// The coarsest modification time resolution of common file systems, FAT's.
+/// A file modified within that long before its snapshot was written may have
+/// changed again since without its modification time changing.
+const MTIME_GRANULARITY: Duration = Duration::from_secs(2);
+
+/// The parse options changing the model built from a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct ModelOptions {
+    strict: bool,
+    skip_docs: bool,
+    spans: bool,
+}
+
+impl From<&ParseOptions> for ModelOptions {
+    fn from(options: &ParseOptions) -> Self {
+        Self {
+            strict: options.strict,
+            skip_docs: options.skip_docs,
+            spans: options.spans,
+        }
+    }
+}
+
+/// The repository of a snapshot, along with the warnings found while
+/// parsing it.
+type Payload = (Repository, Vec<ParseWarning>);
+
+/// What a snapshot was built from.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Header {
+    format_version: u32,
+    crate_version: String,
+    options: ModelOptions,
+    len: u64,
+    mtime: Option<(u64, u32)>,
+    /// When the snapshot was written.
+    written: Option<(u64, u32)>,
+    content_hash: u64,
+}
+
+impl Header {
+    /// Whether the size and modification time of a file are enough to tell
+    /// it didn't change since the snapshot was written.
+    fn is_fresh(&self, len: u64, mtime: Option<(u64, u32)>) -> bool {
+        let (Some(mtime), Some(written)) = (mtime, self.written) else {
+            return false;
+        };
+        let as_duration = |(secs, nanos)| Duration::new(secs, nanos);
+        self.len == len
+            && self.mtime == Some(mtime)
+            && as_duration(mtime) + MTIME_GRANULARITY < as_duration(written)
+    }
+}
+
+/// Encode a value with its `serde` representation, using `postcard`. It
+/// fails when a value refuses to be serialized, as paths which aren't valid
+/// UTF-8 do.
+pub(crate) fn to_bytes(value: &impl Serialize) -> Result<Vec<u8>, postcard::Error> {
+    postcard::to_stdvec(value)
+}
+
+/// Decode a value, which must use all of `input`. Malformed input makes the
+/// snapshot unusable.
+pub(crate) fn from_bytes<T: DeserializeOwned>(input: &[u8]) -> Option<T> {
+    let (value, rest) = postcard::take_from_bytes(input).ok()?;
+    rest.is_empty().then_some(value)
+}
+
+/// FNV-1a, stable across platforms and Rust versions unlike the hashers of
+/// the standard library.
+fn content_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn timestamp(time: SystemTime) -> Option<(u64, u32)> {
+    let time = time.duration_since(UNIX_EPOCH).ok()?;
+    Some((time.as_secs(), time.subsec_nanos()))
+}
+
+fn mtime(metadata: &std::fs::Metadata) -> Option<(u64, u32)> {
+    timestamp(metadata.modified().ok()?)
+}
+
+/// The snapshot of a GIR file, named after the file and a hash of its
+/// absolute path and of the options, so files with the same name don't
+/// collide and loading with other options doesn't replace it.
+fn cache_path(path: &Path, cache_dir: &Path, options: ModelOptions) -> PathBuf {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_owned());
+    let file_name = path
+        .file_name()
+        .map_or_else(Default::default, |name| name.to_string_lossy());
+    let mut key = path.as_os_str().as_encoded_bytes().to_vec();
+    key.extend(to_bytes(&options).expect("the options are serializable"));
+    let key_hash = content_hash(&key);
+    cache_dir.join(format!("{file_name}-{key_hash:016x}.cache"))
+}
+
+/// Read a snapshot, returning its header and payload if it was written by
+/// this version of the crate with the same options.
+fn read_snapshot(cache_path: &Path, options: ModelOptions) -> Option<(Header, Vec<u8>)> {
+    let bytes = std::fs::read(cache_path).ok()?;
+    let input = bytes.strip_prefix(MAGIC)?;
+    let (header, payload) = postcard::take_from_bytes::<Header>(input).ok()?;
+    if header.format_version != FORMAT_VERSION
+        || header.crate_version != env!("CARGO_PKG_VERSION")
+        || header.options != options
+    {
+        return None;
+    }
+    Some((header, payload.to_vec()))
+}
+
+/// Store a snapshot, recording when it was written. The cache is best
+/// effort: a snapshot that can't be written, for example because the cache
+/// directory is read-only, is skipped.
+fn write_snapshot(cache_path: &Path, header: &mut Header, payload: &[u8]) {
+    header.written = timestamp(SystemTime::now());
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend(to_bytes(&*header).expect("the header is serializable"));
+    bytes.extend_from_slice(payload);
+    // Write to a temporary file first so concurrent readers never see a
+    // partially written snapshot.
+    let tmp_path = cache_path.with_extension(format!("tmp{}", std::process::id()));
+    let written = cache_path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(&tmp_path, bytes))
+        .and_then(|()| std::fs::rename(&tmp_path, cache_path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(tmp_path);
+    }
+}
+
+impl Repository {
+    /// Parse a GIR file, reusing the snapshot stored in `cache_dir` by a
+    /// previous call if the file didn't change since.
+    ///
+    /// A snapshot is reused when the size and modification time of the file
+    /// are unchanged, or when its content hashes to the same value. The
+    /// content is always hashed when the file was modified too close to the
+    /// snapshot being written for its modification time to tell whether it
+    /// changed since. Snapshots that are stale, corrupted or written by
+    /// another version of the crate are replaced. Failing to write a snapshot
+    /// doesn't fail the call, the parsed repository is returned uncached.
+    pub fn load_cached(
+        path: impl AsRef<Path>,
+        cache_dir: impl AsRef<Path>,
+    ) -> Result<Self, ParserError> {
+        Self::load_cached_with_options(path, cache_dir, &ParseOptions::default())
+            .map(|(repository, _)| repository)
+    }
+
+    /// Like [`Repository::load_cached`], parsing with `options`. Snapshots
+    /// are kept apart for each combination of the options changing the
+    /// model, and keep the warnings of lenient mode. Cached repositories go
+    /// through the interner of the options when there is one.
+    pub fn load_cached_with_options(
+        path: impl AsRef<Path>,
+        cache_dir: impl AsRef<Path>,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        let path = path.as_ref();
+        let model_options = ModelOptions::from(options);
+        let metadata = std::fs::metadata(path)?;
+        let cache_path = cache_path(path, cache_dir.as_ref(), model_options);
+        let snapshot = read_snapshot(&cache_path, model_options);
+        let mtime = mtime(&metadata);
+        let load = |payload: &[u8]| match &options.interner {
+            Some(interner) => interner.scope(|| from_bytes::<Payload>(payload)),
+            None => from_bytes::<Payload>(payload),
+        };
+
+        if let Some((header, payload)) = &snapshot {
+            if header.is_fresh(metadata.len(), mtime) {
+                if let Some(loaded) = load(payload) {
+                    return Ok(loaded);
+                }
+            }
+        }
+
+        let content = compression::read_path(path)?;
+        let mut header = Header {
+            format_version: FORMAT_VERSION,
+            crate_version: env!("CARGO_PKG_VERSION").to_owned(),
+            options: model_options,
+            len: metadata.len(),
+            mtime,
+            written: None,
+            content_hash: content_hash(content.as_bytes()),
+        };
+        if let Some((cached, payload)) = snapshot {
+            if cached.content_hash == header.content_hash && cached.len == header.len {
+                if let Some(loaded) = load(&payload) {
+                    // The content didn't change, refresh the header so the
+                    // next load can trust the modification time.
+                    write_snapshot(&cache_path, &mut header, &payload);
+                    return Ok(loaded);
+                }
+            }
+        }
+
+        let (repository, warnings) = Self::parse(&content, Some(path), options)?;
+        // A repository that can't be serialized is returned without being
+        // stored, like when the snapshot can't be written.
+        if let Ok(payload) = to_bytes(&(&repository, &warnings)) {
+            write_snapshot(&cache_path, &mut header, &payload);
+        }
+        Ok((repository, warnings))
+    }
+}
+
+impl IncludeGraph {
+    /// Like [`IncludeGraph::load`], reusing the snapshots stored in
+    /// `cache_dir` as [`Repository::load_cached`] does.
+    pub fn load_cached(
+        package_files: impl IntoIterator<Item = impl AsRef<str>>,
+        search_path: &GirSearchPath,
+        cache_dir: impl AsRef<Path>,
+    ) -> Result<Self, ParserError> {
+        let cache_dir = cache_dir.as_ref();
+        Self::load_with(package_files, search_path, &|path| {
+            Repository::load_cached(path, cache_dir)
+        })
+    }
+}
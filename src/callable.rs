@@ -1,20 +1,22 @@
-use xmlserde_derives::XmlDeserialize;
-
 use crate::{
     function::Function,
     method::Method,
     parameter::Parameters,
     return_value::ReturnValue,
     traits::{Callable as CallableTrait, FunctionLike},
+    tree::{FromNode, FromTag, Node},
+    writer::{Element, ToXml},
 };
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum Callable {
-    #[xmlserde(name = b"constructor")]
     Constructor(Function),
-    #[xmlserde(name = b"method")]
     Method(Method),
-    #[xmlserde(name = b"function")]
     Function(Function),
 }
 
@@ -87,3 +89,24 @@ impl Callable {
         }
     }
 }
+
+impl FromTag for Callable {
+    fn from_tag(node: Node) -> Option<Self> {
+        match node.tag() {
+            "constructor" => Some(Self::Constructor(Function::from_node(node))),
+            "method" => Some(Self::Method(Method::from_node(node))),
+            "function" => Some(Self::Function(Function::from_node(node))),
+            _ => None,
+        }
+    }
+}
+
+impl ToXml for Callable {
+    fn to_xml(&self, _tag: &str) -> Element {
+        match self {
+            Self::Constructor(f) => f.to_xml("constructor"),
+            Self::Method(m) => m.to_xml("method"),
+            Self::Function(f) => f.to_xml("function"),
+        }
+    }
+}
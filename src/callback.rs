@@ -1,54 +1,42 @@
-use xmlserde_derives::XmlDeserialize;
-
 use crate::{
     attribute::Attribute,
     documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition},
+    extra::Extra,
     parameter::Parameters,
     prelude::*,
     return_value::ReturnValue,
+    span::NodeSpan,
+    tree::{FromNode, Node},
     version::Version,
+    writer::{Element, ToXml},
     Stability,
 };
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
-#[xmlserde(root = b"callback")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Callback {
-    #[xmlserde(name = b"name", ty = "attr")]
     name: String,
-    #[xmlserde(name = b"c:type", ty = "attr")]
     c_type: Option<String>,
-    #[xmlserde(name = b"throws", ty = "attr")]
     throws: Option<bool>,
     // Common attributes
-    #[xmlserde(name = b"introspectable", ty = "attr")]
     introspectable: Option<bool>,
-    #[xmlserde(name = b"deprecated", ty = "attr")]
     deprecated: Option<bool>,
-    #[xmlserde(name = b"version", ty = "attr")]
     version: Option<Version>,
-    #[xmlserde(name = b"deprecated-version", ty = "attr")]
     deprecated_version: Option<Version>,
-    #[xmlserde(name = b"stability", ty = "attr")]
     stability: Option<Stability>,
     // Documentation
-    #[xmlserde(name = b"doc", ty = "child")]
     doc: Option<Documentation>,
-    #[xmlserde(name = b"doc-deprecated", ty = "child")]
     doc_deprecated: Option<DocDeprecated>,
-    #[xmlserde(name = b"doc-stability", ty = "child")]
     doc_stability: Option<DocStability>,
-    #[xmlserde(name = b"doc-version", ty = "child")]
     doc_version: Option<DocVersion>,
-    #[xmlserde(name = b"source-position", ty = "child")]
     source_position: Option<SourcePosition>,
     // Attributes: 0 or more
-    #[xmlserde(name = b"attribute", ty = "child")]
     attributes: Vec<Attribute>,
-    #[xmlserde(name = b"return-value", ty = "child")]
     return_value: ReturnValue,
-    #[xmlserde(name = b"parameters", ty = "child", default = "Parameters::default")]
     parameters: Parameters,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Callback {
@@ -65,3 +53,76 @@ impl_info!(Callback);
 impl_attributable!(Callback);
 impl_documentable!(Callback);
 impl_function_like!(Callback);
+
+impl_extensible!(Callback);
+impl_spanned!(Callback);
+impl_builder!(Callback => CallbackBuilder {
+    new {
+        name: String => name_mut;
+    }
+    optional {
+        c_type: String => c_type, c_type_mut;
+        throws: bool => throws, throws_mut;
+        introspectable: bool => introspectable, introspectable_mut;
+        deprecated: bool => deprecated, deprecated_mut;
+        version: Version => version, version_mut;
+        deprecated_version: Version => deprecated_version, deprecated_version_mut;
+        stability: Stability => stability, stability_mut;
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+    }
+    list {
+        attributes: Attribute => attribute, attributes_mut;
+    }
+    value {
+        return_value: ReturnValue => return_value, return_value_mut;
+        parameters: Parameters => parameters, parameters_mut;
+    }
+    hidden { span, extra }
+});
+impl_parameters_builder!(CallbackBuilder);
+impl FromNode for Callback {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            c_type: node.opt_attr("c:type"),
+            throws: node.opt_attr("throws"),
+            introspectable: node.opt_attr("introspectable"),
+            deprecated: node.opt_attr("deprecated"),
+            version: node.opt_attr("version"),
+            deprecated_version: node.opt_attr("deprecated-version"),
+            stability: node.opt_attr("stability"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            attributes: node.children("attribute"),
+            return_value: node.required("return-value"),
+            parameters: node.child("parameters").unwrap_or_default(),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Callback {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .attr("name", &self.name)
+            .opt_attr("c:type", &self.c_type)
+            .opt_attr("throws", &self.throws)
+            .info(self)
+            .documentation(self)
+            .attributes(self)
+            .child("return-value", &self.return_value)
+            .opt_child(
+                "parameters",
+                (!self.parameters.is_empty()).then_some(&self.parameters),
+            )
+            .extra(self)
+    }
+}
@@ -0,0 +1,333 @@
+//! The pass over GIR documents checking them against the [`schema`], so
+//! failures are reported with their location, while recording their
+//! elements in a [`Tree`] the models are built from.
+//!
+//! Unknown elements are kept as extra content of their node. Unknown
+//! attributes are either rejected or kept as well, depending on the
+//! [`ParseOptions`], which can also skip the documentation.
+//!
+//! [`schema`]: crate::schema
+
+use std::{ops::Range, path::Path};
+
+use xmlserde::quick_xml::{
+    events::{BytesStart, Event},
+    Reader, XmlVersion,
+};
+
+use crate::{
+    error::{ParseError, ParseWarning},
+    schema::Kind,
+    tree::Tree,
+    ParseOptions,
+};
+
+/// What is done with the content of an element once its start is checked.
+#[derive(PartialEq, Eq)]
+enum Content {
+    Checked,
+    /// Skipped documentation, which isn't recorded.
+    Skipped,
+    /// An element that isn't part of the schema, kept as it is without
+    /// looking at its content.
+    Raw,
+}
+
+struct Frame<'a> {
+    /// `None` for an element that isn't part of the schema, while it is
+    /// reported.
+    kind: Option<Kind>,
+    tag: &'a str,
+    offset: usize,
+    /// The position of the element among its siblings with the same tag.
+    position: usize,
+    children: Vec<(&'static str, usize)>,
+}
+
+struct Checker<'a, 't> {
+    source: &'a str,
+    root: (&'static str, Kind),
+    /// Whether the root was cut after its header, leaving children out.
+    is_cut: bool,
+    path: Option<&'t Path>,
+    strict: bool,
+    skip_docs: bool,
+    stack: Vec<Frame<'a>>,
+    tree: &'t mut Tree<'a>,
+    warnings: Vec<ParseWarning>,
+}
+
+impl<'a> Checker<'a, '_> {
+    /// Describe an element of the element path. The start tag is read again
+    /// as this is only needed when reporting errors.
+    fn segment(&self, frame: &Frame) -> String {
+        if frame.tag == "parameter" && frame.kind.is_some() {
+            return format!("{}[{}]", frame.tag, frame.position);
+        }
+        let mut reader = Reader::from_str(&self.source[frame.offset..]);
+        if let Ok(Event::Start(start) | Event::Empty(start)) = reader.read_event() {
+            if let Ok(Some(name)) = start.try_get_attribute("name") {
+                return format!("{}[@name={}]", frame.tag, decode(&name.value));
+            }
+        }
+        frame.tag.to_owned()
+    }
+
+    fn element_path(&self) -> String {
+        // The repository is left out, unlike the root of a lone element.
+        self.stack
+            .iter()
+            .skip(usize::from(self.root == Kind::ROOT))
+            .map(|frame| self.segment(frame))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    fn error(&self, offset: usize, message: String) -> ParseError {
+        ParseError::new(self.source, self.path, offset, self.element_path(), message)
+    }
+
+    fn warn(&mut self, offset: usize, message: String) {
+        let warning = ParseWarning::new(self.error(offset, message));
+        self.warnings.push(warning);
+    }
+
+    /// Check the start of an element, returning what to do with its content.
+    fn start(&mut self, start: &BytesStart, offset: usize) -> Result<Content, ParseError> {
+        let tag = &self.source[offset + 1..][..start.name().into_inner().len()];
+        let parent = self.stack.last_mut();
+        let mut frame = Frame {
+            kind: None,
+            tag,
+            offset,
+            position: 0,
+            children: Vec::new(),
+        };
+        match parent {
+            None if tag == self.root.0 => {
+                frame.kind = Some(self.root.1);
+                frame.position = 1;
+            }
+            None => {
+                return Err(self.error(
+                    offset,
+                    format!("expected a `{}` element, found `{tag}`", self.root.0),
+                ))
+            }
+            Some(parent) => match parent.kind.and_then(|kind| kind.child(tag)) {
+                Some((tag, kind)) => {
+                    frame.kind = Some(kind);
+                    frame.position = match parent.children.iter_mut().find(|(t, _)| *t == tag) {
+                        Some((_, count)) => {
+                            *count += 1;
+                            *count
+                        }
+                        None => {
+                            parent.children.push((tag, 1));
+                            1
+                        }
+                    };
+                }
+                // Unlike attributes, unknown elements never fail the parsing,
+                // as they didn't when deserializing with xmlserde.
+                None => {
+                    if !self.strict {
+                        self.stack.push(frame);
+                        self.warn(offset, format!("unknown element `{tag}`"));
+                        self.stack.pop();
+                    }
+                    self.tree.start(tag, None, offset);
+                    return Ok(Content::Raw);
+                }
+            },
+        };
+        let kind = frame.kind.expect("known element");
+        if self.skip_docs && kind.is_documentation() {
+            return Ok(Content::Skipped);
+        }
+        self.stack.push(frame);
+        self.tree.start(tag, Some(kind), offset);
+
+        let mut required = 0;
+        for attr in start.attributes() {
+            let attr = attr.map_err(|e| self.error(offset, format!("malformed attribute: {e}")))?;
+            let key = decode(attr.key.into_inner());
+            // Only needed to report the attribute.
+            let attr_offset =
+                || attribute_span(start, key).map_or(offset, |span| offset + 1 + span.start);
+            let value = attr
+                .normalized_value(XmlVersion::Implicit1_0)
+                .map_err(|e| self.error(attr_offset(), format!("malformed attribute: {e}")))?;
+            let Some(attr_schema) = kind.attribute(key) else {
+                self.skip_attribute(attr_offset(), format!("unknown attribute `{key}`"))?;
+                if kind.keeps_extra() {
+                    self.tree.attribute(key, value);
+                }
+                continue;
+            };
+            if let Err(e) = attr_schema.check(&value) {
+                let mut message = format!("invalid value `{value}` for attribute `{key}`");
+                if !e.is_empty() {
+                    message.push_str(&format!(": {e}"));
+                }
+                if attr_schema.required {
+                    return Err(self.error(attr_offset(), message));
+                }
+                self.skip_attribute(attr_offset(), message)?;
+                continue;
+            }
+            required += usize::from(attr_schema.required);
+            self.tree.attribute(key, value);
+        }
+        let required_attributes = kind.required_attributes();
+        if required < required_attributes.len() {
+            let missing = required_attributes
+                .iter()
+                .find(|attr| !matches!(start.try_get_attribute(attr.name), Ok(Some(_))))
+                .expect("missing required attribute");
+            let message = format!("missing required attribute `{}`", missing.name);
+            return Err(self.error(offset, message));
+        }
+        Ok(Content::Checked)
+    }
+
+    /// Fail on an attribute, or skip it in lenient mode.
+    fn skip_attribute(&mut self, offset: usize, message: String) -> Result<(), ParseError> {
+        if self.strict {
+            return Err(self.error(offset, message));
+        }
+        self.warn(offset, message);
+        Ok(())
+    }
+
+    fn end(&mut self, end: usize) -> Result<(), ParseError> {
+        let frame = self.stack.last().expect("end of an unopened element");
+        // The children of an element cut after its header aren't all there.
+        let kind = frame.kind.expect("known element");
+        let required_children = if self.is_cut && self.stack.len() == 1 {
+            &[]
+        } else {
+            kind.schema().required_children()
+        };
+        for group in required_children {
+            if !group
+                .iter()
+                .any(|tag| frame.children.iter().any(|(t, _)| t == tag))
+            {
+                let expected = group
+                    .iter()
+                    .map(|tag| format!("`{tag}`"))
+                    .collect::<Vec<_>>()
+                    .join(" or ");
+                return Err(self.error(frame.offset, format!("missing {expected} element")));
+            }
+        }
+        self.stack.pop();
+        self.tree.end(end);
+        Ok(())
+    }
+}
+
+fn decode(bytes: &[u8]) -> &str {
+    // The reader was created from a `&str`, so names are valid UTF-8.
+    std::str::from_utf8(bytes).unwrap_or_default()
+}
+
+/// The position of an attribute, from its name to its closing quote, inside
+/// its start tag.
+fn attribute_span(start: &BytesStart, key: &str) -> Option<Range<usize>> {
+    let raw = decode(start);
+    raw.match_indices(key).find_map(|(i, _)| {
+        if !raw[..i].ends_with(char::is_whitespace) {
+            return None;
+        }
+        let value = raw[i + key.len()..]
+            .trim_start()
+            .strip_prefix('=')?
+            .trim_start();
+        let quote = value.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+        let value_start = raw.len() - value.len();
+        let end = value_start + 1 + value[1..].find(quote)? + 1;
+        Some(i..end)
+    })
+}
+
+/// Check a document made of a single element with the given tag and kind,
+/// recording its content in `tree`. The root is `cut` when its children
+/// were left out past that offset. Returns the warnings about the content
+/// skipped or kept in lenient mode.
+pub(crate) fn check<'a, 't>(
+    tree: &'t mut Tree<'a>,
+    root: (&'static str, Kind),
+    path: Option<&'t Path>,
+    options: &ParseOptions,
+    cut: Option<usize>,
+) -> Result<Vec<ParseWarning>, ParseError> {
+    let source = tree.source();
+    let mut reader = Reader::from_str(source);
+    let mut checker = Checker {
+        source,
+        root,
+        is_cut: cut.is_some(),
+        path,
+        strict: options.strict,
+        skip_docs: options.skip_docs,
+        stack: Vec::new(),
+        tree,
+        warnings: Vec::new(),
+    };
+    let mut has_root = false;
+    loop {
+        let offset = reader.buffer_position() as usize;
+        let event = reader.read_event();
+        let end = reader.buffer_position() as usize;
+        match event {
+            Ok(Event::Start(_) | Event::Empty(_)) if has_root && checker.stack.is_empty() => {
+                let message = "unexpected element after the document root".to_owned();
+                return Err(checker.error(offset, message));
+            }
+            Ok(Event::Start(start)) => {
+                // Skipped documentation isn't recorded, nor checked, and the
+                // content of unknown elements is recorded as it is.
+                let content = checker.start(&start, offset)?;
+                if content != Content::Checked {
+                    if let Err(e) = reader.read_to_end(start.name()) {
+                        let offset = reader.error_position() as usize;
+                        return Err(checker.error(offset, format!("malformed XML: {e}")));
+                    }
+                }
+                if content == Content::Raw {
+                    checker.tree.end(reader.buffer_position() as usize);
+                }
+                has_root = true;
+            }
+            Ok(Event::Empty(start)) => {
+                match checker.start(&start, offset)? {
+                    Content::Checked => checker.end(end)?,
+                    Content::Skipped => {}
+                    Content::Raw => checker.tree.end(end),
+                }
+                has_root = true;
+            }
+            Ok(Event::End(_)) => checker.end(end)?,
+            Ok(event @ (Event::Text(_) | Event::GeneralRef(_) | Event::CData(_))) => {
+                checker.tree.text(event)
+            }
+            Ok(Event::Eof) => break,
+            Ok(_) => {}
+            Err(e) => {
+                let offset = reader.error_position() as usize;
+                return Err(checker.error(offset, format!("malformed XML: {e}")));
+            }
+        }
+    }
+    if !checker.stack.is_empty() {
+        let message = "unexpected end of document".to_owned();
+        return Err(checker.error(source.len(), message));
+    }
+    if !has_root {
+        let message = format!("missing `{}` element", root.0);
+        return Err(checker.error(0, message));
+    }
+    Ok(checker.warnings)
+}
@@ -1,30 +1,34 @@
-use xmlserde_derives::XmlDeserialize;
-
 use crate::{
     attribute::Attribute,
     callable::Callable,
     callback::Callback,
     constant::Constant,
     documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition},
+    extra::Extra,
     field::Field,
     function::{Function, FunctionInline},
+    interner::Atom,
     method::{Method, MethodInline},
     prelude::*,
     property::Property,
     record::Record,
     signal::Signal,
+    span::NodeSpan,
+    tree::{FromNode, FromTag, Node},
     union::Union,
     version::Version,
     virtual_method::VirtualMethod,
+    writer::{Element, ToXml},
     Stability,
 };
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, XmlDeserialize)]
-#[xmlserde(root = b"implements")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Implements {
-    #[xmlserde(name = b"name", ty = "attr")]
-    name: String,
+    name: Atom,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Implements {
@@ -33,101 +37,72 @@ impl Implements {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 // FIXME: The `Type` / `AnyType` fields are quite huge and some boxing would
-// probably be useful here but `xmlserde` does not seem to support that.
+// probably be useful here.
 #[allow(clippy::large_enum_variant)]
 pub enum ClassField {
-    #[xmlserde(name = b"field")]
     Field(Field),
-    #[xmlserde(name = b"union")]
     Union(Union),
-    #[xmlserde(name = b"record")]
     Record(Record),
-    #[xmlserde(name = b"callback")]
     Callback(Callback),
 }
 
-#[derive(Clone, Debug, XmlDeserialize)]
-#[xmlserde(root = b"class")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Class {
-    #[xmlserde(name = b"name", ty = "attr")]
     name: String,
-    #[xmlserde(name = b"c:symbol-prefix", ty = "attr")]
     symbol_prefix: Option<String>,
-    #[xmlserde(name = b"c:type", ty = "attr")]
     c_type: Option<String>,
-    #[xmlserde(name = b"parent", ty = "attr")]
-    parent: Option<String>,
+    parent: Option<Atom>,
     // Common attributes
-    #[xmlserde(name = b"introspectable", ty = "attr")]
     introspectable: Option<bool>,
-    #[xmlserde(name = b"deprecated", ty = "attr")]
     deprecated: Option<bool>,
-    #[xmlserde(name = b"version", ty = "attr")]
     version: Option<Version>,
-    #[xmlserde(name = b"deprecated-version", ty = "attr")]
     deprecated_version: Option<Version>,
-    #[xmlserde(name = b"stability", ty = "attr")]
     stability: Option<Stability>,
     // Documentation
-    #[xmlserde(name = b"doc", ty = "child")]
     doc: Option<Documentation>,
-    #[xmlserde(name = b"doc-deprecated", ty = "child")]
     doc_deprecated: Option<DocDeprecated>,
-    #[xmlserde(name = b"doc-stability", ty = "child")]
     doc_stability: Option<DocStability>,
-    #[xmlserde(name = b"doc-version", ty = "child")]
     doc_version: Option<DocVersion>,
-    #[xmlserde(name = b"source-position", ty = "child")]
     source_position: Option<SourcePosition>,
     // Attributes: 0 or more
-    #[xmlserde(name = b"attribute", ty = "child")]
     attributes: Vec<Attribute>,
 
-    #[xmlserde(name = b"glib:type-name", ty = "attr")]
     g_type_name: String,
-    #[xmlserde(name = b"glib:get-type", ty = "attr")]
     g_get_type: String,
-    #[xmlserde(name = b"glib:type-struct", ty = "attr")]
     g_type_struct: Option<String>,
-    #[xmlserde(name = b"glib:fundamental", ty = "attr")]
     g_fundamental: Option<bool>,
-    #[xmlserde(name = b"final", ty = "attr")]
     r#final: Option<bool>,
-    #[xmlserde(name = b"abstract", ty = "attr")]
     r#abstract: Option<bool>,
-    #[xmlserde(name = b"glib:ref-func", ty = "attr")]
     g_ref_func: Option<String>,
-    #[xmlserde(name = b"glib:unref-func", ty = "attr")]
     g_unref_func: Option<String>,
-    #[xmlserde(name = b"glib:set-value-func", ty = "attr")]
     g_set_value_func: Option<String>,
-    #[xmlserde(name = b"glib:get-value-func", ty = "attr")]
     g_get_value_func: Option<String>,
-    #[xmlserde(name = b"implements", ty = "child")]
     implements: Vec<Implements>,
 
-    #[xmlserde(name = b"function-inline", ty = "child")]
+    #[cfg_attr(feature = "serde", serde(rename = "inlined_functions"))]
     inline_functions: Vec<FunctionInline>,
 
-    #[xmlserde(name = b"inline-methods", ty = "child")]
+    #[cfg_attr(feature = "serde", serde(rename = "inlined_methods"))]
     inline_methods: Vec<MethodInline>,
 
-    #[xmlserde(name = b"property", ty = "child")]
     properties: Vec<Property>,
-    #[xmlserde(name = b"glib:signal", ty = "child")]
     signals: Vec<Signal>,
-    #[xmlserde(name = b"virtual-method", ty = "child")]
     virtual_methods: Vec<VirtualMethod>,
-    #[xmlserde(name = b"constant", ty = "child")]
     constants: Vec<Constant>,
 
-    #[xmlserde(ty = "untag")]
     fields: Vec<ClassField>,
-    #[xmlserde(ty = "untag")]
     callables: Vec<Callable>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Class {
@@ -248,3 +223,177 @@ impl Class {
 impl_documentable!(Class);
 impl_attributable!(Class);
 impl_info!(Class);
+
+impl FromTag for ClassField {
+    fn from_tag(node: Node) -> Option<Self> {
+        match node.tag() {
+            "field" => Some(Self::Field(Field::from_node(node))),
+            "union" => Some(Self::Union(Union::from_node(node))),
+            "record" => Some(Self::Record(Record::from_node(node))),
+            "callback" => Some(Self::Callback(Callback::from_node(node))),
+            _ => None,
+        }
+    }
+}
+
+impl ToXml for ClassField {
+    fn to_xml(&self, _tag: &str) -> Element {
+        match self {
+            Self::Field(f) => f.to_xml("field"),
+            Self::Union(u) => u.to_xml("union"),
+            Self::Record(r) => r.to_xml("record"),
+            Self::Callback(c) => c.to_xml("callback"),
+        }
+    }
+}
+
+impl_extensible!(Implements);
+impl_spanned!(Implements);
+impl_builder!(Implements => ImplementsBuilder {
+    new {
+        name: Atom => name_mut;
+    }
+    optional {}
+    list {}
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for Implements {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Implements {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag).attr("name", &self.name).extra(self)
+    }
+}
+
+impl_extensible!(Class);
+impl_spanned!(Class);
+impl_builder!(Class => ClassBuilder {
+    new {
+        name: String => name_mut;
+        g_type_name: String => g_type_name_mut;
+        g_get_type: String => g_get_type_mut;
+    }
+    optional {
+        symbol_prefix: String => symbol_prefix, symbol_prefix_mut;
+        c_type: String => c_type, c_type_mut;
+        parent: Atom => parent, parent_mut;
+        introspectable: bool => introspectable, introspectable_mut;
+        deprecated: bool => deprecated, deprecated_mut;
+        version: Version => version, version_mut;
+        deprecated_version: Version => deprecated_version, deprecated_version_mut;
+        stability: Stability => stability, stability_mut;
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+        g_type_struct: String => g_type_struct, g_type_struct_mut;
+        g_fundamental: bool => g_fundamental, g_fundamental_mut;
+        r#final: bool => r#final, final_mut;
+        r#abstract: bool => r#abstract, abstract_mut;
+        g_ref_func: String => g_ref_func, g_ref_func_mut;
+        g_unref_func: String => g_unref_func, g_unref_func_mut;
+        g_set_value_func: String => g_set_value_func, g_set_value_func_mut;
+        g_get_value_func: String => g_get_value_func, g_get_value_func_mut;
+    }
+    list {
+        attributes: Attribute => attribute, attributes_mut;
+        implements: Implements => implements, implements_mut;
+        inline_functions: FunctionInline => inlined_function, inlined_functions_mut;
+        inline_methods: MethodInline => inlined_method, inlined_methods_mut;
+        properties: Property => property, properties_mut;
+        signals: Signal => signal, signals_mut;
+        virtual_methods: VirtualMethod => virtual_method, virtual_methods_mut;
+        constants: Constant => constant, constants_mut;
+        fields: ClassField => field, fields_mut;
+        callables: Callable => callable, callables_mut;
+    }
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for Class {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            symbol_prefix: node.opt_attr("c:symbol-prefix"),
+            c_type: node.opt_attr("c:type"),
+            parent: node.opt_attr("parent"),
+            introspectable: node.opt_attr("introspectable"),
+            deprecated: node.opt_attr("deprecated"),
+            version: node.opt_attr("version"),
+            deprecated_version: node.opt_attr("deprecated-version"),
+            stability: node.opt_attr("stability"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            attributes: node.children("attribute"),
+            g_type_name: node.attr("glib:type-name"),
+            g_get_type: node.attr("glib:get-type"),
+            g_type_struct: node.opt_attr("glib:type-struct"),
+            g_fundamental: node.opt_attr("glib:fundamental"),
+            r#final: node.opt_attr("final"),
+            r#abstract: node.opt_attr("abstract"),
+            g_ref_func: node.opt_attr("glib:ref-func"),
+            g_unref_func: node.opt_attr("glib:unref-func"),
+            g_set_value_func: node.opt_attr("glib:set-value-func"),
+            g_get_value_func: node.opt_attr("glib:get-value-func"),
+            implements: node.children("implements"),
+            inline_functions: node.children("function-inline"),
+            inline_methods: node.children("method-inline"),
+            properties: node.children("property"),
+            signals: node.children("glib:signal"),
+            virtual_methods: node.children("virtual-method"),
+            constants: node.children("constant"),
+            fields: node.all_of(),
+            callables: node.all_of(),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Class {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .attr("name", &self.name)
+            .opt_attr("c:symbol-prefix", &self.symbol_prefix)
+            .opt_attr("c:type", &self.c_type)
+            .opt_attr("parent", &self.parent)
+            .attr("glib:type-name", &self.g_type_name)
+            .attr("glib:get-type", &self.g_get_type)
+            .opt_attr("glib:type-struct", &self.g_type_struct)
+            .opt_attr("glib:fundamental", &self.g_fundamental)
+            .opt_attr("final", &self.r#final)
+            .opt_attr("abstract", &self.r#abstract)
+            .opt_attr("glib:ref-func", &self.g_ref_func)
+            .opt_attr("glib:unref-func", &self.g_unref_func)
+            .opt_attr("glib:set-value-func", &self.g_set_value_func)
+            .opt_attr("glib:get-value-func", &self.g_get_value_func)
+            .info(self)
+            .documentation(self)
+            .attributes(self)
+            .children("implements", &self.implements)
+            .untagged(&self.fields)
+            .untagged(&self.callables)
+            .children("function-inline", &self.inline_functions)
+            .children("method-inline", &self.inline_methods)
+            .children("property", &self.properties)
+            .children("glib:signal", &self.signals)
+            .children("virtual-method", &self.virtual_methods)
+            .children("constant", &self.constants)
+            .extra(self)
+    }
+}
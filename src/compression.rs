@@ -0,0 +1,64 @@
+use std::{
+    io::{self, BufRead, Read},
+    path::{Path, PathBuf},
+};
+
+const GZ_MAGIC: &[u8] = &[0x1f, 0x8b];
+const XZ_MAGIC: &[u8] = &[0xfd, b'7', b'z', b'X', b'Z', 0x00];
+
+/// Wrap a reader in a decoder if it starts with the magic bytes of a gzip or
+/// xz stream.
+pub(crate) fn decode<'a>(mut reader: impl BufRead + 'a) -> io::Result<Box<dyn Read + 'a>> {
+    let magic = reader.fill_buf()?;
+    if magic.starts_with(GZ_MAGIC) {
+        #[cfg(feature = "gz")]
+        return Ok(Box::new(flate2::bufread::MultiGzDecoder::new(reader)));
+        #[cfg(not(feature = "gz"))]
+        return Err(unsupported("gz"));
+    }
+    if magic.starts_with(XZ_MAGIC) {
+        #[cfg(feature = "xz")]
+        return Ok(Box::new(xz2::bufread::XzDecoder::new_multi_decoder(reader)));
+        #[cfg(not(feature = "xz"))]
+        return Err(unsupported("xz"));
+    }
+    Ok(Box::new(reader))
+}
+
+#[cfg(not(all(feature = "gz", feature = "xz")))]
+fn unsupported(feature: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("reading {feature} compressed GIR files requires the `{feature}` feature"),
+    )
+}
+
+/// Read a whole document, decompressing it if needed.
+pub(crate) fn read_to_string(reader: impl BufRead) -> io::Result<String> {
+    let mut content = String::new();
+    decode(reader)?.read_to_string(&mut content)?;
+    Ok(content)
+}
+
+pub(crate) fn read_path(path: &Path) -> io::Result<String> {
+    read_to_string(io::BufReader::new(std::fs::File::open(path)?))
+}
+
+/// The paths a GIR file may be stored at: as is, then with the extensions of
+/// the compression formats enabled.
+pub(crate) fn variants(path: PathBuf) -> impl Iterator<Item = PathBuf> {
+    let extensions = [
+        cfg!(feature = "gz").then_some("gz"),
+        cfg!(feature = "xz").then_some("xz"),
+    ];
+    let compressed = extensions.into_iter().flatten().map({
+        let path = path.clone();
+        move |extension| {
+            let mut path = path.clone().into_os_string();
+            path.push(".");
+            path.push(extension);
+            PathBuf::from(path)
+        }
+    });
+    std::iter::once(path).chain(compressed)
+}
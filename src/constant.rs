@@ -1,53 +1,42 @@
-use xmlserde_derives::XmlDeserialize;
-
 use crate::{
     attribute::Attribute,
     documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition},
+    extra::Extra,
     prelude::*,
     r#type::AnyType,
+    span::NodeSpan,
+    tree::{FromNode, Node},
     version::Version,
+    writer::{Element, ToXml},
     Stability,
 };
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
-#[xmlserde(root = b"constant")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Constant {
-    #[xmlserde(name = b"name", ty = "attr")]
     name: String,
-    #[xmlserde(name = b"value", ty = "attr")]
     value: String,
-    #[xmlserde(name = b"c:type", ty = "attr")]
     c_type: Option<String>,
-    #[xmlserde(name = b"c:identifier", ty = "attr")]
     c_identifier: Option<String>,
     // Common attributes
-    #[xmlserde(name = b"introspectable", ty = "attr")]
     introspectable: Option<bool>,
-    #[xmlserde(name = b"deprecated", ty = "attr")]
     deprecated: Option<bool>,
-    #[xmlserde(name = b"version", ty = "attr")]
     version: Option<Version>,
-    #[xmlserde(name = b"deprecated-version", ty = "attr")]
     deprecated_version: Option<Version>,
-    #[xmlserde(name = b"stability", ty = "attr")]
     stability: Option<Stability>,
     // Documentation
-    #[xmlserde(name = b"doc", ty = "child")]
     doc: Option<Documentation>,
-    #[xmlserde(name = b"doc-deprecated", ty = "child")]
     doc_deprecated: Option<DocDeprecated>,
-    #[xmlserde(name = b"doc-stability", ty = "child")]
     doc_stability: Option<DocStability>,
-    #[xmlserde(name = b"doc-version", ty = "child")]
     doc_version: Option<DocVersion>,
-    #[xmlserde(name = b"source-position", ty = "child")]
     source_position: Option<SourcePosition>,
     // Attributes: 0 or more
-    #[xmlserde(name = b"attribute", ty = "child")]
     attributes: Vec<Attribute>,
-    #[xmlserde(ty = "untag")]
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
     type_: AnyType,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Constant {
@@ -75,3 +64,72 @@ impl Constant {
 impl_info!(Constant);
 impl_attributable!(Constant);
 impl_documentable!(Constant);
+
+impl_extensible!(Constant);
+impl_spanned!(Constant);
+impl_builder!(Constant => ConstantBuilder {
+    new {
+        name: String => name_mut;
+        value: String => value_mut;
+        type_: AnyType => ty_mut;
+    }
+    optional {
+        c_type: String => c_type, c_type_mut;
+        c_identifier: String => c_identifier, c_identifier_mut;
+        introspectable: bool => introspectable, introspectable_mut;
+        deprecated: bool => deprecated, deprecated_mut;
+        version: Version => version, version_mut;
+        deprecated_version: Version => deprecated_version, deprecated_version_mut;
+        stability: Stability => stability, stability_mut;
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+    }
+    list {
+        attributes: Attribute => attribute, attributes_mut;
+    }
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for Constant {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            value: node.attr("value"),
+            c_type: node.opt_attr("c:type"),
+            c_identifier: node.opt_attr("c:identifier"),
+            introspectable: node.opt_attr("introspectable"),
+            deprecated: node.opt_attr("deprecated"),
+            version: node.opt_attr("version"),
+            deprecated_version: node.opt_attr("deprecated-version"),
+            stability: node.opt_attr("stability"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            attributes: node.children("attribute"),
+            type_: node.one_of(),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Constant {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .attr("name", &self.name)
+            .attr("value", &self.value)
+            .opt_attr("c:type", &self.c_type)
+            .opt_attr("c:identifier", &self.c_identifier)
+            .info(self)
+            .documentation(self)
+            .attributes(self)
+            .untagged([&self.type_])
+            .extra(self)
+    }
+}
@@ -0,0 +1,496 @@
+//! A lossless view of GIR documents, keeping the whitespace, comments and
+//! attribute order of the text, so fixup files can be edited in place.
+//!
+//! A [`Document`] is parsed along with its [`Repository`], whose nodes lead to
+//! their element through their [`Span`]. Everything that isn't edited is
+//! written back byte for byte.
+//!
+//! ```no_run
+//! use gir_parser::{cst::Document, prelude::*};
+//!
+//! let mut document = Document::from_path("./gir-files/Gdk-4.0.gir").unwrap();
+//! let function = document
+//!     .repository()
+//!     .namespace()
+//!     .functions()
+//!     .iter()
+//!     .find(|function| function.name() == "content_deserialize_async")
+//!     .unwrap();
+//! let span = function.parameters().inner()[1].span().unwrap().clone();
+//! document
+//!     .element_mut(&span)
+//!     .unwrap()
+//!     .set_attribute("nullable", "1");
+//! std::fs::write("Gdk-4.0.gir", document.to_string()).unwrap();
+//! ```
+
+use std::{
+    borrow::Cow,
+    fmt,
+    io::Write,
+    ops::Range,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use xmlserde::quick_xml::{
+    escape::{escape, partial_escape, unescape},
+    events::Event,
+    Reader,
+};
+
+use crate::{compression, ParseError, ParseOptions, ParseWarning, ParserError, Repository, Span};
+
+/// A GIR document along with the repository it describes.
+#[derive(Debug, Clone)]
+pub struct Document {
+    nodes: Vec<Node>,
+    repository: Repository,
+    path: Option<PathBuf>,
+    options: ParseOptions,
+}
+
+impl Document {
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ParserError> {
+        let (document, _) = Self::from_path_with_options(path, &ParseOptions::default())?;
+        Ok(document)
+    }
+
+    pub fn from_path_with_options(
+        path: impl AsRef<Path>,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        let content = compression::read_path(path.as_ref())?;
+        Self::parse(&content, Some(path.as_ref()), options)
+    }
+
+    pub fn from_str_with_options(
+        content: &str,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        Self::parse(content, None, options)
+    }
+
+    fn parse(
+        content: &str,
+        path: Option<&Path>,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        // Nodes lead to their element through their span.
+        let spans = ParseOptions {
+            spans: true,
+            ..options.clone()
+        };
+        let (repository, warnings) = Repository::parse(content, path, &spans)?;
+        let document = Self {
+            nodes: build(content, path)?,
+            repository,
+            path: path.map(Path::to_path_buf),
+            options: options.clone(),
+        };
+        Ok((document, warnings))
+    }
+
+    /// The repository as it was when the document was parsed, which doesn't
+    /// follow the edits until the document is [reparsed](Self::reparse).
+    pub fn repository(&self) -> &Repository {
+        &self.repository
+    }
+
+    /// Parse the edited document again, updating its repository and the spans
+    /// of its nodes.
+    pub fn reparse(&mut self) -> Result<Vec<ParseWarning>, ParserError> {
+        let content = self.to_string();
+        let (document, warnings) = Self::parse(&content, self.path.as_deref(), &self.options)?;
+        *self = document;
+        Ok(warnings)
+    }
+
+    /// The nodes at the top of the document: the XML declaration, comments
+    /// and the `repository` element.
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    pub fn root(&self) -> &Element {
+        self.nodes
+            .iter()
+            .find_map(Node::as_element)
+            .expect("parsed document has a root")
+    }
+
+    pub fn root_mut(&mut self) -> &mut Element {
+        self.nodes
+            .iter_mut()
+            .find_map(Node::as_element_mut)
+            .expect("parsed document has a root")
+    }
+
+    /// The element a node of the [repository](Self::repository) was parsed
+    /// from, if it is still part of the document.
+    pub fn element(&self, span: &Span) -> Option<&Element> {
+        find(&self.nodes, span.range())
+    }
+
+    pub fn element_mut(&mut self, span: &Span) -> Option<&mut Element> {
+        find_mut(&mut self.nodes, span.range())
+    }
+
+    pub fn write_to(&self, mut writer: impl Write) -> Result<(), ParserError> {
+        writer.write_all(self.to_string().as_bytes())?;
+        Ok(())
+    }
+}
+
+impl FromStr for Document {
+    type Err = ParserError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (document, _) = Self::from_str_with_options(s, &ParseOptions::default())?;
+        Ok(document)
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.nodes.iter().try_for_each(|node| node.fmt(f))
+    }
+}
+
+/// A node of the document, holding its text as written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    Element(Element),
+    /// Character data, with its entity references.
+    Text(String),
+    /// The content of a comment, between `<!--` and `-->`.
+    Comment(String),
+    /// Any other markup, such as the XML declaration or CDATA sections.
+    Other(String),
+}
+
+impl Node {
+    /// A text node holding `text`, escaped as needed.
+    pub fn text(text: &str) -> Self {
+        Self::Text(partial_escape(text).into_owned())
+    }
+
+    pub fn as_element(&self) -> Option<&Element> {
+        match self {
+            Self::Element(element) => Some(element),
+            _ => None,
+        }
+    }
+
+    pub fn as_element_mut(&mut self) -> Option<&mut Element> {
+        match self {
+            Self::Element(element) => Some(element),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Element(element) => element.fmt(f),
+            Self::Text(text) | Self::Other(text) => f.write_str(text),
+            Self::Comment(comment) => write!(f, "<!--{comment}-->"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Element {
+    name: String,
+    attributes: Vec<Attribute>,
+    /// The whitespace before the closing `>` or `/>` of the start tag.
+    tag_end: String,
+    children: Vec<Node>,
+    /// `None` for elements closed by their start tag.
+    end_tag: Option<String>,
+    range: Option<Range<usize>>,
+}
+
+impl Element {
+    /// An element without attributes nor children, which can be added to the
+    /// document.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            attributes: Vec::new(),
+            tag_end: String::new(),
+            children: Vec::new(),
+            end_tag: None,
+            range: None,
+        }
+    }
+
+    /// Read a start tag, including its `<` and `>`.
+    fn read(tag: &str) -> Option<Self> {
+        let content = tag.strip_prefix('<')?.strip_suffix('>')?;
+        let content = content.strip_suffix('/').unwrap_or(content);
+        let name_end = content
+            .find(|c: char| c.is_ascii_whitespace())
+            .unwrap_or(content.len());
+        let mut element = Self::new(&content[..name_end]);
+        let mut rest = &content[name_end..];
+        loop {
+            let leading = &rest[..rest.len() - rest.trim_start().len()];
+            rest = &rest[leading.len()..];
+            if rest.is_empty() {
+                element.tag_end = leading.to_owned();
+                return Some(element);
+            }
+            let name_end = rest.find(|c: char| c == '=' || c.is_ascii_whitespace())?;
+            let (name, after) = rest.split_at(name_end);
+            let quote_start = after.find(['"', '\''])?;
+            let (equals, after) = after.split_at(quote_start);
+            let quote = after.chars().next()?;
+            let value_end = after[1..].find(quote)? + 1;
+            element.attributes.push(Attribute {
+                leading: leading.to_owned(),
+                name: name.to_owned(),
+                equals: equals.to_owned(),
+                quote,
+                value: after[1..value_end].to_owned(),
+            });
+            rest = &after[value_end + 1..];
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Where the element was in the text it was parsed from, `None` for the
+    /// elements added since.
+    pub fn range(&self) -> Option<Range<usize>> {
+        self.range.clone()
+    }
+
+    pub fn attributes(&self) -> &[Attribute] {
+        &self.attributes
+    }
+
+    /// The unescaped value of the attribute `name`.
+    pub fn attribute(&self, name: &str) -> Option<Cow<'_, str>> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name)
+            .map(Attribute::value)
+    }
+
+    /// Set the value of the attribute `name`, in place if the element has it,
+    /// or after its other attributes otherwise.
+    pub fn set_attribute(&mut self, name: &str, value: &str) {
+        let value = escape(value).into_owned();
+        match self
+            .attributes
+            .iter_mut()
+            .find(|attribute| attribute.name == name)
+        {
+            Some(attribute) => attribute.value = value,
+            None => self.attributes.push(Attribute {
+                leading: " ".to_owned(),
+                name: name.to_owned(),
+                equals: "=".to_owned(),
+                quote: '"',
+                value,
+            }),
+        }
+    }
+
+    /// Remove the attribute `name` along with the whitespace preceding it,
+    /// returning whether the element had it.
+    pub fn remove_attribute(&mut self, name: &str) -> bool {
+        let count = self.attributes.len();
+        self.attributes.retain(|attribute| attribute.name != name);
+        self.attributes.len() != count
+    }
+
+    pub fn children(&self) -> &[Node] {
+        &self.children
+    }
+
+    pub fn children_mut(&mut self) -> &mut Vec<Node> {
+        &mut self.children
+    }
+
+    /// The child elements, skipping the text and comments between them.
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(Node::as_element)
+    }
+
+    pub fn elements_mut(&mut self) -> impl Iterator<Item = &mut Element> {
+        self.children.iter_mut().filter_map(Node::as_element_mut)
+    }
+
+    /// Add `element` after the last child element, indented like it.
+    pub fn append(&mut self, element: Element) {
+        let last = self
+            .children
+            .iter()
+            .rposition(|node| matches!(node, Node::Element(_)));
+        let Some(last) = last else {
+            self.children.push(Node::Element(element));
+            return;
+        };
+        let indent = match last.checked_sub(1).map(|index| &self.children[index]) {
+            Some(Node::Text(text)) if text.trim().is_empty() => text.clone(),
+            _ => String::new(),
+        };
+        self.children.insert(last + 1, Node::Element(element));
+        if !indent.is_empty() {
+            self.children.insert(last + 1, Node::Text(indent));
+        }
+    }
+
+    /// Replace the children of the element by `text`, as for documentation
+    /// elements.
+    pub fn set_text(&mut self, text: &str) {
+        self.children = vec![Node::text(text)];
+    }
+}
+
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{}", self.name)?;
+        self.attributes
+            .iter()
+            .try_for_each(|attribute| attribute.fmt(f))?;
+        f.write_str(&self.tag_end)?;
+        if self.end_tag.is_none() && self.children.is_empty() {
+            return f.write_str("/>");
+        }
+        f.write_str(">")?;
+        self.children.iter().try_for_each(|node| node.fmt(f))?;
+        match &self.end_tag {
+            Some(end_tag) => f.write_str(end_tag),
+            None => write!(f, "</{}>", self.name),
+        }
+    }
+}
+
+/// An attribute of a start tag, along with the whitespace and quotes around
+/// it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attribute {
+    leading: String,
+    name: String,
+    /// The `=` and the whitespace around it.
+    equals: String,
+    quote: char,
+    value: String,
+}
+
+impl Attribute {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn value(&self) -> Cow<'_, str> {
+        unescape(&self.value).unwrap_or(Cow::Borrowed(&self.value))
+    }
+
+    /// The value as written, with its entity references.
+    pub fn raw_value(&self) -> &str {
+        &self.value
+    }
+}
+
+impl fmt::Display for Attribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let quote = self.quote;
+        write!(
+            f,
+            "{}{}{}{quote}{}{quote}",
+            self.leading, self.name, self.equals, self.value
+        )
+    }
+}
+
+fn build(source: &str, path: Option<&Path>) -> Result<Vec<Node>, ParseError> {
+    let error =
+        |offset, message: String| ParseError::new(source, path, offset, String::new(), message);
+    let mut reader = Reader::from_str(source);
+    let mut nodes = Vec::new();
+    let mut stack: Vec<Element> = Vec::new();
+    loop {
+        let offset = reader.buffer_position() as usize;
+        let event = reader
+            .read_event()
+            .map_err(|err| error(offset, err.to_string()))?;
+        let end = reader.buffer_position() as usize;
+        let raw = &source[offset..end];
+        let read = || Element::read(raw).ok_or_else(|| error(offset, "malformed start tag".into()));
+        let node = match event {
+            Event::Start(_) => {
+                let mut element = read()?;
+                element.range = Some(offset..offset);
+                stack.push(element);
+                continue;
+            }
+            Event::Empty(_) => {
+                let mut element = read()?;
+                element.range = Some(offset..end);
+                Node::Element(element)
+            }
+            Event::End(_) => {
+                let Some(mut element) = stack.pop() else {
+                    return Err(error(offset, "unexpected end tag".into()));
+                };
+                element.end_tag = Some(raw.to_owned());
+                element.range = element.range.map(|range| range.start..end);
+                Node::Element(element)
+            }
+            Event::Text(_) | Event::GeneralRef(_) => Node::Text(raw.to_owned()),
+            Event::Comment(_) => Node::Comment(raw[4..raw.len() - 3].to_owned()),
+            Event::Eof => break,
+            _ => Node::Other(raw.to_owned()),
+        };
+        let siblings = match stack.last_mut() {
+            Some(parent) => &mut parent.children,
+            None => &mut nodes,
+        };
+        // Entity references are read as separate events.
+        match (siblings.last_mut(), node) {
+            (Some(Node::Text(text)), Node::Text(more)) => text.push_str(&more),
+            (_, node) => siblings.push(node),
+        }
+    }
+    Ok(nodes)
+}
+
+fn contains(outer: &Range<usize>, inner: &Range<usize>) -> bool {
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+fn find(nodes: &[Node], range: Range<usize>) -> Option<&Element> {
+    let element = nodes.iter().filter_map(Node::as_element).find(|element| {
+        element
+            .range
+            .as_ref()
+            .is_some_and(|outer| contains(outer, &range))
+    })?;
+    match element.range == Some(range.clone()) {
+        true => Some(element),
+        false => find(&element.children, range),
+    }
+}
+
+fn find_mut(nodes: &mut [Node], range: Range<usize>) -> Option<&mut Element> {
+    let element = nodes
+        .iter_mut()
+        .filter_map(Node::as_element_mut)
+        .find(|element| {
+            element
+                .range
+                .as_ref()
+                .is_some_and(|outer| contains(outer, &range))
+        })?;
+    match element.range == Some(range.clone()) {
+        true => Some(element),
+        false => find_mut(&mut element.children, range),
+    }
+}
@@ -0,0 +1,1063 @@
+//! Comparison of two versions of a namespace, to track what changed between
+//! releases of a library.
+
+use xmlserde::XmlValue;
+
+use crate::{
+    hierarchy::qualified_name, prelude::*, AnyType, Array, Callback, Class, ClassField, Constant,
+    Direction, Field, FieldType, FunctionMacro, Implements, Interface, InterfaceField, Member,
+    Namespace, ParameterType, Parameters, Prerequisite, Property, Record, RecordField, Repository,
+    ReturnValue, Signal, TransferOwnership, Type, Union, UnionField, VirtualMethod,
+};
+
+/// How a change affects the users of a library, from the least to the most
+/// disruptive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Severity {
+    /// Neither existing code nor existing binaries are affected.
+    Compatible,
+    /// Code written against the old version may no longer build or behave
+    /// as expected, while binaries built against it keep working.
+    ApiBreaking,
+    /// Binaries built against the old version may no longer work.
+    AbiBreaking,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Compatible => "compatible",
+            Self::ApiBreaking => "API-breaking",
+            Self::AbiBreaking => "ABI-breaking",
+        })
+    }
+}
+
+/// The kind of node a change is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Item {
+    Alias,
+    Class,
+    Interface,
+    Record,
+    Union,
+    Enumeration,
+    BitField,
+    /// A `glib:boxed` type.
+    Boxed,
+    Callback,
+    Function,
+    /// A function only defined in the C headers, compiled in the binaries
+    /// using it.
+    FunctionInline,
+    FunctionMacro,
+    Constructor,
+    Method,
+    VirtualMethod,
+    Signal,
+    Property,
+    Member,
+    Constant,
+    Parameter,
+    ReturnValue,
+    /// An interface implemented by a class.
+    Implements,
+    /// An interface or class required by an interface.
+    Prerequisite,
+    Field,
+}
+
+impl std::fmt::Display for Item {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Alias => "alias",
+            Self::Class => "class",
+            Self::Interface => "interface",
+            Self::Record => "record",
+            Self::Union => "union",
+            Self::Enumeration => "enumeration",
+            Self::BitField => "bitfield",
+            Self::Boxed => "boxed type",
+            Self::Callback => "callback",
+            Self::Function => "function",
+            Self::FunctionInline => "inline function",
+            Self::FunctionMacro => "function macro",
+            Self::Constructor => "constructor",
+            Self::Method => "method",
+            Self::VirtualMethod => "virtual method",
+            Self::Signal => "signal",
+            Self::Property => "property",
+            Self::Member => "member",
+            Self::Constant => "constant",
+            Self::Parameter => "parameter",
+            Self::ReturnValue => "return value",
+            Self::Implements => "implemented interface",
+            Self::Prerequisite => "prerequisite",
+            Self::Field => "field",
+        })
+    }
+}
+
+/// What changed about a node present in both versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Aspect {
+    Parent,
+    Final,
+    Abstract,
+    Deprecated,
+    Type,
+    Nullable,
+    TransferOwnership,
+    Direction,
+    Throws,
+    /// The number of parameters, excluding the instance parameter.
+    Parameters,
+    Value,
+    Readable,
+    Writable,
+    /// The order of the fields present in both versions.
+    Layout,
+}
+
+impl std::fmt::Display for Aspect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Parent => "parent",
+            Self::Final => "final",
+            Self::Abstract => "abstract",
+            Self::Deprecated => "deprecated",
+            Self::Type => "type",
+            Self::Nullable => "nullable",
+            Self::TransferOwnership => "transfer ownership",
+            Self::Direction => "direction",
+            Self::Throws => "throws",
+            Self::Parameters => "number of parameters",
+            Self::Value => "value",
+            Self::Readable => "readable",
+            Self::Writable => "writable",
+            Self::Layout => "layout",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    /// An aspect of the node changed, from the `old` value to the `new` one,
+    /// as written in GIR files.
+    Changed {
+        aspect: Aspect,
+        old: String,
+        new: String,
+    },
+}
+
+/// A difference between two versions of a namespace.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Change {
+    item: Item,
+    path: String,
+    kind: ChangeKind,
+    severity: Severity,
+}
+
+impl Change {
+    pub fn item(&self) -> Item {
+        self.item
+    }
+
+    /// The path to the node, in the format of [`Metadata`](crate::Metadata)
+    /// selectors, such as `Gtk.Widget.get_parent.return`. Parameters are
+    /// named after their new name.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn kind(&self) -> &ChangeKind {
+        &self.kind
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ChangeKind::Added => write!(f, "added {} `{}`", self.item, self.path),
+            ChangeKind::Removed => write!(f, "removed {} `{}`", self.item, self.path),
+            ChangeKind::Changed { aspect, old, new } => write!(
+                f,
+                "changed {aspect} of {} `{}` from `{old}` to `{new}`",
+                self.item, self.path
+            ),
+        }
+    }
+}
+
+/// The changes between two versions of a namespace, such as the
+/// classes, methods, virtual methods, signals, properties, enumeration members
+/// and constants that were added or removed, the changes to the signatures of
+/// the callables, to the interfaces of classes, to the fields of structures
+/// and to the targets of aliases.
+///
+/// The inline functions of types, the documentation, and the attributes that
+/// don't affect the users of the library, such as C types and the
+/// `glib:get-type` functions, aren't compared.
+///
+/// Its [`Display`](std::fmt::Display) implementation renders a report of the
+/// changes, grouped by severity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ApiDiff {
+    changes: Vec<Change>,
+}
+
+impl ApiDiff {
+    pub fn new(old: &Repository, new: &Repository) -> Self {
+        let mut differ = Differ::default();
+        differ.namespace(old.namespace(), new.namespace());
+        Self {
+            changes: differ.changes,
+        }
+    }
+
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// The most disruptive severity of the changes.
+    pub fn severity(&self) -> Severity {
+        self.changes
+            .iter()
+            .map(Change::severity)
+            .max()
+            .unwrap_or(Severity::Compatible)
+    }
+
+    pub fn with_severity(&self, severity: Severity) -> impl Iterator<Item = &Change> {
+        self.changes
+            .iter()
+            .filter(move |change| change.severity == severity)
+    }
+}
+
+impl std::fmt::Display for ApiDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No changes");
+        }
+        let mut first = true;
+        for severity in [
+            Severity::AbiBreaking,
+            Severity::ApiBreaking,
+            Severity::Compatible,
+        ] {
+            let mut changes = self.with_severity(severity).peekable();
+            if changes.peek().is_none() {
+                continue;
+            }
+            if !first {
+                writeln!(f)?;
+            }
+            first = false;
+            let mut title = severity.to_string();
+            title[..1].make_ascii_uppercase();
+            writeln!(f, "{title} changes:")?;
+            for change in changes {
+                writeln!(f, "  {change}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Who calls a callable, which decides which side of a nullability change
+/// breaks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Caller {
+    /// Functions and methods, called by the users of the library.
+    User,
+    /// Callbacks and signal handlers, implemented by the users of the library
+    /// and called by it.
+    Library,
+}
+
+/// The parts of a callable its callers depend on.
+struct Signature<'a> {
+    throws: bool,
+    return_value: &'a ReturnValue,
+    parameters: &'a Parameters,
+}
+
+impl<'a> Signature<'a> {
+    fn of(callable: &'a impl FunctionLike) -> Self {
+        Self {
+            throws: callable.throws(),
+            return_value: callable.return_value(),
+            parameters: callable.parameters(),
+        }
+    }
+
+    fn of_signal(signal: &'a Signal) -> Self {
+        Self {
+            throws: false,
+            return_value: signal.return_value(),
+            parameters: signal.parameters(),
+        }
+    }
+}
+
+fn type_name(ty: &Type) -> String {
+    let name = ty.name().or(ty.c_type()).unwrap_or("?");
+    let params = ty
+        .parameters()
+        .iter()
+        .map(|parameter| match parameter {
+            AnyType::Type(ty) => type_name(ty),
+            AnyType::Array(array) => array_name(array),
+        })
+        .collect::<Vec<_>>();
+    match params.is_empty() {
+        true => name.to_owned(),
+        false => format!("{name}<{}>", params.join(", ")),
+    }
+}
+
+fn array_name(array: &Array) -> String {
+    let element = match array.array() {
+        Some(array) => array_name(array),
+        None => type_name(array.ty()),
+    };
+    match array.name() {
+        Some(name) => format!("{name}<{element}>"),
+        None => format!("[{element}]"),
+    }
+}
+
+fn any_type_name(ty: &AnyType) -> String {
+    match ty {
+        AnyType::Type(ty) => type_name(ty),
+        AnyType::Array(array) => array_name(array),
+    }
+}
+
+fn parameter_type_name(ty: Option<&ParameterType>) -> String {
+    match ty {
+        Some(ParameterType::Type(ty)) => type_name(ty),
+        Some(ParameterType::Array(array)) => array_name(array),
+        Some(ParameterType::VarArgs) => "...".to_owned(),
+        None => "?".to_owned(),
+    }
+}
+
+fn transfer(transfer: Option<TransferOwnership>) -> String {
+    transfer.unwrap_or(TransferOwnership::None).serialize()
+}
+
+fn direction(direction: Option<Direction>) -> String {
+    direction.unwrap_or(Direction::In).serialize()
+}
+
+fn callback_name(callback: &Callback) -> String {
+    let parameters = callback
+        .parameters()
+        .inner()
+        .iter()
+        .map(|parameter| parameter_type_name(parameter.ty()))
+        .collect::<Vec<_>>();
+    let return_type = any_type_name(callback.return_value().ty());
+    format!("fn({}) -> {return_type}", parameters.join(", "))
+}
+
+/// A field of a structure, with its type as it affects the layout.
+struct Slot {
+    name: String,
+    ty: String,
+    deprecated: bool,
+}
+
+impl Slot {
+    fn field(field: &Field) -> Self {
+        let ty = match field.ty() {
+            FieldType::Type(ty) => type_name(ty),
+            FieldType::Callback(callback) => callback_name(callback),
+            FieldType::Array(array) => array_name(array),
+        };
+        Self {
+            name: field.name().to_owned(),
+            ty: match field.bits() {
+                Some(bits) => format!("{ty}:{bits}"),
+                None => ty,
+            },
+            deprecated: field.is_deprecated(),
+        }
+    }
+
+    fn callback(callback: &Callback) -> Self {
+        Self {
+            name: callback.name().to_owned(),
+            ty: callback_name(callback),
+            deprecated: callback.is_deprecated(),
+        }
+    }
+
+    /// A nested union or structure, named after its position when anonymous.
+    fn compound(
+        index: usize,
+        keyword: &str,
+        name: Option<&str>,
+        compound: &(impl Layout + Info),
+    ) -> Self {
+        let slots = compound
+            .slots()
+            .into_iter()
+            .map(|slot| format!("{}: {}", slot.name, slot.ty))
+            .collect::<Vec<_>>();
+        Self {
+            name: name.map_or_else(|| format!("#{index}"), ToOwned::to_owned),
+            ty: format!("{keyword} {{ {} }}", slots.join("; ")),
+            deprecated: compound.is_deprecated(),
+        }
+    }
+}
+
+/// The nodes with fields, whose memory layout binaries depend on.
+trait Layout {
+    fn slots(&self) -> Vec<Slot>;
+}
+
+macro_rules! impl_layout {
+    ($($rust_type:ident => $field:ident),*) => {
+        $(impl Layout for $rust_type {
+            fn slots(&self) -> Vec<Slot> {
+                self.fields()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, field)| match field {
+                        $field::Field(field) => Slot::field(field),
+                        $field::Union(union) => Slot::compound(index, "union", union.name(), union),
+                        $field::Record(record) => {
+                            Slot::compound(index, "struct", record.name(), record)
+                        }
+                        $field::Callback(callback) => Slot::callback(callback),
+                    })
+                    .collect()
+            }
+        })*
+    };
+}
+
+impl_layout!(
+    Class => ClassField,
+    Interface => InterfaceField,
+    Record => RecordField,
+    Union => UnionField
+);
+
+/// Pair the nodes of both versions by name, in the order of the old version
+/// followed by the added nodes. Anonymous nodes are left out.
+fn pairs<'a, T>(
+    old: impl IntoIterator<Item = &'a T>,
+    new: impl IntoIterator<Item = &'a T>,
+    name: impl Fn(&T) -> Option<&str>,
+) -> Vec<(&'a str, Option<&'a T>, Option<&'a T>)>
+where
+    T: 'a,
+{
+    let new = new.into_iter().collect::<Vec<_>>();
+    let mut pairs = Vec::new();
+    for old in old {
+        let Some(old_name) = name(old) else {
+            continue;
+        };
+        if pairs.iter().any(|(n, _, _)| *n == old_name) {
+            continue;
+        }
+        let new = new.iter().copied().find(|new| name(new) == Some(old_name));
+        pairs.push((old_name, Some(old), new));
+    }
+    for new in new {
+        match name(new) {
+            Some(new_name) if !pairs.iter().any(|(n, _, _)| *n == new_name) => {
+                pairs.push((new_name, None, Some(new)));
+            }
+            _ => {}
+        }
+    }
+    pairs
+}
+
+#[derive(Default)]
+struct Differ {
+    changes: Vec<Change>,
+}
+
+impl Differ {
+    fn push(&mut self, item: Item, path: &str, kind: ChangeKind, severity: Severity) {
+        self.changes.push(Change {
+            item,
+            path: path.to_owned(),
+            kind,
+            severity,
+        });
+    }
+
+    fn changed(
+        &mut self,
+        item: Item,
+        path: &str,
+        aspect: Aspect,
+        (old, new): (String, String),
+        severity: Severity,
+    ) {
+        if old != new {
+            let kind = ChangeKind::Changed { aspect, old, new };
+            self.push(item, path, kind, severity);
+        }
+    }
+
+    /// A flag whose change has `severity` when it becomes `breaking` and is
+    /// compatible otherwise.
+    fn flag(
+        &mut self,
+        item: Item,
+        path: &str,
+        aspect: Aspect,
+        values: (bool, bool),
+        (breaking, severity): (bool, Severity),
+    ) {
+        let severity = match values.1 == breaking {
+            true => severity,
+            false => Severity::Compatible,
+        };
+        let values = (values.0.to_string(), values.1.to_string());
+        self.changed(item, path, aspect, values, severity);
+    }
+
+    /// Compare the nodes of both versions, reporting the nodes only present
+    /// in one of them with the `added` or `removed` severity, and calling
+    /// `compare` with the others.
+    fn items<'a, T: 'a>(
+        &mut self,
+        item: Item,
+        parent: &str,
+        (old, new): (
+            impl IntoIterator<Item = &'a T>,
+            impl IntoIterator<Item = &'a T>,
+        ),
+        name: impl Fn(&T) -> Option<&str>,
+        (added, removed): (Severity, Severity),
+        mut compare: impl FnMut(&mut Self, &str, &T, &T),
+    ) {
+        for (name, old, new) in pairs(old, new, name) {
+            let path = format!("{parent}.{name}");
+            match (old, new) {
+                (Some(old), Some(new)) => compare(self, &path, old, new),
+                (Some(_), None) => self.push(item, &path, ChangeKind::Removed, removed),
+                (None, _) => self.push(item, &path, ChangeKind::Added, added),
+            }
+        }
+    }
+
+    /// Run `diff`, downgrading the ABI-breaking changes it reports to
+    /// API-breaking ones, for the nodes that aren't part of the ABI of the
+    /// library, such as macros, which are compiled in the binaries using them.
+    fn without_abi(&mut self, diff: impl FnOnce(&mut Self)) {
+        let start = self.changes.len();
+        diff(self);
+        for change in &mut self.changes[start..] {
+            change.severity = change.severity.min(Severity::ApiBreaking);
+        }
+    }
+
+    fn info(&mut self, item: Item, path: &str, old: &impl Info, new: &impl Info) {
+        let values = (
+            old.is_deprecated().to_string(),
+            new.is_deprecated().to_string(),
+        );
+        self.changed(item, path, Aspect::Deprecated, values, Severity::Compatible);
+    }
+
+    fn namespace(&mut self, old: &Namespace, new: &Namespace) {
+        let ns = new.name();
+        let namespaces = (old.name(), new.name());
+        let compatible = Severity::Compatible;
+        let abi = Severity::AbiBreaking;
+        let api = Severity::ApiBreaking;
+        // Binaries have the sizes of the types aliases point to compiled in,
+        // while aliases themselves have no symbol.
+        self.items(
+            Item::Alias,
+            ns,
+            (old.aliases(), new.aliases()),
+            |alias| Some(alias.name()),
+            (compatible, api),
+            |this, path, old, new| {
+                this.info(Item::Alias, path, old, new);
+                let types = (any_type_name(old.ty()), any_type_name(new.ty()));
+                this.changed(Item::Alias, path, Aspect::Type, types, abi);
+            },
+        );
+        self.items(
+            Item::Class,
+            ns,
+            (old.classes(), new.classes()),
+            |class| Some(class.name()),
+            (compatible, abi),
+            |this, path, old, new| {
+                this.info(Item::Class, path, old, new);
+                // Parents are compared qualified, as they may be written
+                // either way.
+                let parent = |class: &Class, ns| {
+                    class
+                        .parent()
+                        .map(|parent| qualified_name(parent, ns))
+                        .unwrap_or_default()
+                };
+                let parents = (parent(old, namespaces.0), parent(new, namespaces.1));
+                this.changed(Item::Class, path, Aspect::Parent, parents, abi);
+                let finals = (old.is_final(), new.is_final());
+                this.flag(Item::Class, path, Aspect::Final, finals, (true, api));
+                let abstracts = (old.is_abstract(), new.is_abstract());
+                this.flag(Item::Class, path, Aspect::Abstract, abstracts, (true, api));
+                this.callables(
+                    Item::Constructor,
+                    path,
+                    old.constructors(),
+                    new.constructors(),
+                );
+                this.callables(Item::Method, path, old.methods(), new.methods());
+                this.callables(Item::Function, path, old.functions(), new.functions());
+                this.virtual_methods(path, old.virtual_methods(), new.virtual_methods());
+                this.signals(path, old.signals(), new.signals());
+                this.properties(path, old.properties(), new.properties());
+                this.constants(path, old.constants(), new.constants());
+                this.items(
+                    Item::Implements,
+                    path,
+                    (old.implements(), new.implements()),
+                    |implements: &Implements| Some(implements.name()),
+                    (compatible, abi),
+                    |_, _, _, _| {},
+                );
+                this.fields(Item::Class, path, old, new);
+            },
+        );
+        self.items(
+            Item::Interface,
+            ns,
+            (old.interfaces(), new.interfaces()),
+            |interface| Some(interface.name()),
+            (compatible, abi),
+            |this, path, old, new| {
+                this.info(Item::Interface, path, old, new);
+                this.callables(
+                    Item::Constructor,
+                    path,
+                    old.constructors(),
+                    new.constructors(),
+                );
+                this.callables(Item::Method, path, old.methods(), new.methods());
+                this.callables(Item::Function, path, old.functions(), new.functions());
+                this.virtual_methods(path, old.virtual_methods(), new.virtual_methods());
+                this.signals(path, old.signals(), new.signals());
+                this.properties(path, old.properties(), new.properties());
+                this.constants(path, old.constants(), new.constants());
+                // Existing implementations don't provide the new prerequisites.
+                this.items(
+                    Item::Prerequisite,
+                    path,
+                    (old.prerequisites(), new.prerequisites()),
+                    |prerequisite: &Prerequisite| Some(prerequisite.name()),
+                    (abi, api),
+                    |_, _, _, _| {},
+                );
+                this.fields(Item::Interface, path, old, new);
+            },
+        );
+        self.items(
+            Item::Record,
+            ns,
+            (old.records(), new.records()),
+            |record| record.name(),
+            (compatible, abi),
+            |this, path, old, new| {
+                this.info(Item::Record, path, old, new);
+                this.callables(
+                    Item::Constructor,
+                    path,
+                    old.constructors(),
+                    new.constructors(),
+                );
+                this.callables(Item::Method, path, old.methods(), new.methods());
+                this.callables(Item::Function, path, old.functions(), new.functions());
+                this.fields(Item::Record, path, old, new);
+            },
+        );
+        self.items(
+            Item::Union,
+            ns,
+            (old.unions(), new.unions()),
+            |union| union.name(),
+            (compatible, abi),
+            |this, path, old, new| {
+                this.info(Item::Union, path, old, new);
+                this.callables(
+                    Item::Constructor,
+                    path,
+                    old.constructors(),
+                    new.constructors(),
+                );
+                this.callables(Item::Method, path, old.methods(), new.methods());
+                this.callables(Item::Function, path, old.functions(), new.functions());
+                this.fields(Item::Union, path, old, new);
+            },
+        );
+        self.items(
+            Item::Enumeration,
+            ns,
+            (old.enums(), new.enums()),
+            |enumeration| Some(enumeration.name()),
+            (compatible, abi),
+            |this, path, old, new| {
+                this.info(Item::Enumeration, path, old, new);
+                this.members(path, old.members(), new.members());
+                this.callables(Item::Function, path, old.functions(), new.functions());
+            },
+        );
+        self.items(
+            Item::BitField,
+            ns,
+            (old.flags(), new.flags()),
+            |bitfield| Some(bitfield.name()),
+            (compatible, abi),
+            |this, path, old, new| {
+                this.info(Item::BitField, path, old, new);
+                this.members(path, old.members(), new.members());
+                this.callables(Item::Function, path, old.functions(), new.functions());
+            },
+        );
+        self.items(
+            Item::Callback,
+            ns,
+            (old.callbacks(), new.callbacks()),
+            |callback| Some(callback.name()),
+            (compatible, abi),
+            |this, path, old, new| {
+                this.info(Item::Callback, path, old, new);
+                let (old, new) = (Signature::of(old), Signature::of(new));
+                this.signature(Item::Callback, path, Caller::Library, old, new);
+            },
+        );
+        self.items(
+            Item::Boxed,
+            ns,
+            (old.boxed(), new.boxed()),
+            |boxed| Some(boxed.g_name()),
+            (compatible, abi),
+            |this, path, old, new| {
+                this.info(Item::Boxed, path, old, new);
+                this.callables(Item::Function, path, old.functions(), new.functions());
+            },
+        );
+        self.callables(Item::Function, ns, old.functions(), new.functions());
+        self.without_abi(|this| {
+            let (old, new) = (old.inlined_functions(), new.inlined_functions());
+            this.callables(Item::FunctionInline, ns, old, new);
+        });
+        self.macros(ns, old.macros(), new.macros());
+        self.constants(ns, old.constants(), new.constants());
+    }
+
+    fn macros(&mut self, parent: &str, old: &[FunctionMacro], new: &[FunctionMacro]) {
+        let api = Severity::ApiBreaking;
+        self.items(
+            Item::FunctionMacro,
+            parent,
+            (old, new),
+            |function_macro| Some(function_macro.name()),
+            (Severity::Compatible, api),
+            |this, path, old, new| {
+                this.info(Item::FunctionMacro, path, old, new);
+                let (old, new) = (old.parameters().inner(), new.parameters().inner());
+                let counts = (old.len().to_string(), new.len().to_string());
+                this.changed(Item::FunctionMacro, path, Aspect::Parameters, counts, api);
+            },
+        );
+    }
+
+    fn callables<'a, T: Callable + FunctionLike + 'a>(
+        &mut self,
+        item: Item,
+        parent: &str,
+        old: impl IntoIterator<Item = &'a T>,
+        new: impl IntoIterator<Item = &'a T>,
+    ) {
+        self.items(
+            item,
+            parent,
+            (old, new),
+            |callable| Some(callable.name()),
+            (Severity::Compatible, Severity::AbiBreaking),
+            |this, path, old, new| {
+                this.info(item, path, old, new);
+                let (old, new) = (Signature::of(old), Signature::of(new));
+                this.signature(item, path, Caller::User, old, new);
+            },
+        );
+    }
+
+    fn virtual_methods(&mut self, parent: &str, old: &[VirtualMethod], new: &[VirtualMethod]) {
+        self.items(
+            Item::VirtualMethod,
+            parent,
+            (old, new),
+            |method| Some(method.name()),
+            (Severity::Compatible, Severity::AbiBreaking),
+            |this, path, old, new| {
+                this.info(Item::VirtualMethod, path, old, new);
+                // Virtual methods are implemented by subclasses and called by
+                // the library, like callbacks.
+                let (old, new) = (Signature::of(old), Signature::of(new));
+                this.signature(Item::VirtualMethod, path, Caller::Library, old, new);
+            },
+        );
+    }
+
+    /// Compare the fields of the structure of an `item`. Fields describe the
+    /// memory layout binaries are built against, so adding, removing,
+    /// reordering or retyping them is ABI-breaking, unless the structure had
+    /// no fields before.
+    fn fields(&mut self, item: Item, path: &str, old: &impl Layout, new: &impl Layout) {
+        let (old, new) = (old.slots(), new.slots());
+        if old.is_empty() {
+            return;
+        }
+        let abi = Severity::AbiBreaking;
+        self.items(
+            Item::Field,
+            path,
+            (&old, &new),
+            |slot| Some(&slot.name),
+            (abi, abi),
+            |this, path, old, new| {
+                let deprecated = (old.deprecated.to_string(), new.deprecated.to_string());
+                let compatible = Severity::Compatible;
+                this.changed(
+                    Item::Field,
+                    path,
+                    Aspect::Deprecated,
+                    deprecated,
+                    compatible,
+                );
+                let types = (old.ty.clone(), new.ty.clone());
+                this.changed(Item::Field, path, Aspect::Type, types, abi);
+            },
+        );
+        let order = |slots: &[Slot], other: &[Slot]| {
+            slots
+                .iter()
+                .filter(|slot| other.iter().any(|other| other.name == slot.name))
+                .map(|slot| slot.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let layouts = (order(&old, &new), order(&new, &old));
+        self.changed(item, path, Aspect::Layout, layouts, abi);
+    }
+
+    fn signals(&mut self, parent: &str, old: &[Signal], new: &[Signal]) {
+        self.items(
+            Item::Signal,
+            parent,
+            (old, new),
+            |signal| Some(signal.name()),
+            (Severity::Compatible, Severity::ApiBreaking),
+            |this, path, old, new| {
+                this.info(Item::Signal, path, old, new);
+                let (old, new) = (Signature::of_signal(old), Signature::of_signal(new));
+                this.signature(Item::Signal, path, Caller::Library, old, new);
+            },
+        );
+    }
+
+    fn properties(&mut self, parent: &str, old: &[Property], new: &[Property]) {
+        let api = Severity::ApiBreaking;
+        self.items(
+            Item::Property,
+            parent,
+            (old, new),
+            |property| Some(property.name()),
+            (Severity::Compatible, api),
+            |this, path, old, new| {
+                this.info(Item::Property, path, old, new);
+                let types = (any_type_name(old.ty()), any_type_name(new.ty()));
+                this.changed(Item::Property, path, Aspect::Type, types, api);
+                let readable = (old.is_readable(), new.is_readable());
+                this.flag(
+                    Item::Property,
+                    path,
+                    Aspect::Readable,
+                    readable,
+                    (false, api),
+                );
+                let writable = (old.is_writable(), new.is_writable());
+                this.flag(
+                    Item::Property,
+                    path,
+                    Aspect::Writable,
+                    writable,
+                    (false, api),
+                );
+            },
+        );
+    }
+
+    fn members(&mut self, parent: &str, old: &[Member], new: &[Member]) {
+        self.items(
+            Item::Member,
+            parent,
+            (old, new),
+            |member| Some(member.name()),
+            // Binaries have the values of the removed members compiled in.
+            (Severity::Compatible, Severity::ApiBreaking),
+            |this, path, old, new| {
+                this.info(Item::Member, path, old, new);
+                let values = (old.value().to_owned(), new.value().to_owned());
+                this.changed(
+                    Item::Member,
+                    path,
+                    Aspect::Value,
+                    values,
+                    Severity::AbiBreaking,
+                );
+            },
+        );
+    }
+
+    fn constants(&mut self, parent: &str, old: &[Constant], new: &[Constant]) {
+        self.items(
+            Item::Constant,
+            parent,
+            (old, new),
+            |constant| Some(constant.name()),
+            (Severity::Compatible, Severity::ApiBreaking),
+            |this, path, old, new| {
+                this.info(Item::Constant, path, old, new);
+                let types = (any_type_name(old.ty()), any_type_name(new.ty()));
+                this.changed(
+                    Item::Constant,
+                    path,
+                    Aspect::Type,
+                    types,
+                    Severity::ApiBreaking,
+                );
+                // The value is compiled in, and commonly changes for version
+                // constants.
+                let values = (old.value().to_owned(), new.value().to_owned());
+                this.changed(
+                    Item::Constant,
+                    path,
+                    Aspect::Value,
+                    values,
+                    Severity::Compatible,
+                );
+            },
+        );
+    }
+
+    fn signature(
+        &mut self,
+        item: Item,
+        path: &str,
+        caller: Caller,
+        old: Signature,
+        new: Signature,
+    ) {
+        let abi = Severity::AbiBreaking;
+        let throws = (old.throws.to_string(), new.throws.to_string());
+        self.changed(item, path, Aspect::Throws, throws, abi);
+
+        let (old_return, new_return) = (old.return_value, new.return_value);
+        let return_path = format!("{path}.return");
+        let types = (
+            any_type_name(old_return.ty()),
+            any_type_name(new_return.ty()),
+        );
+        self.changed(Item::ReturnValue, &return_path, Aspect::Type, types, abi);
+        let nullable = (
+            old_return.is_nullable().unwrap_or(false),
+            new_return.is_nullable().unwrap_or(false),
+        );
+        // Callers have to handle the `NULL` values they are given, such as
+        // the return values of functions and the parameters of callbacks.
+        let api = Severity::ApiBreaking;
+        let breaking = caller == Caller::User;
+        self.flag(
+            Item::ReturnValue,
+            &return_path,
+            Aspect::Nullable,
+            nullable,
+            (breaking, api),
+        );
+        let transfers = (
+            transfer(old_return.transfer_ownership()),
+            transfer(new_return.transfer_ownership()),
+        );
+        let aspect = Aspect::TransferOwnership;
+        self.changed(Item::ReturnValue, &return_path, aspect, transfers, abi);
+
+        let (old, new) = (old.parameters.inner(), new.parameters.inner());
+        if old.len() != new.len() {
+            let counts = (old.len().to_string(), new.len().to_string());
+            self.changed(item, path, Aspect::Parameters, counts, abi);
+            return;
+        }
+        for (old, new) in old.iter().zip(new) {
+            let path = format!("{path}.{}", new.name());
+            let item = Item::Parameter;
+            let types = (parameter_type_name(old.ty()), parameter_type_name(new.ty()));
+            self.changed(item, &path, Aspect::Type, types, abi);
+            let nullable = (
+                old.is_nullable().unwrap_or(false),
+                new.is_nullable().unwrap_or(false),
+            );
+            self.flag(item, &path, Aspect::Nullable, nullable, (!breaking, api));
+            let transfers = (
+                transfer(old.transfer_ownership()),
+                transfer(new.transfer_ownership()),
+            );
+            self.changed(item, &path, Aspect::TransferOwnership, transfers, abi);
+            let directions = (direction(old.direction()), direction(new.direction()));
+            self.changed(item, &path, Aspect::Direction, directions, abi);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{
+    documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition},
+    extra::Extra,
+    prelude::*,
+    span::NodeSpan,
+    tree::{FromNode, Node},
+    writer::{Element, ToXml},
+};
+
+/// A standalone section of documentation, such as the overview of a library.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DocSection {
+    name: String,
+    // Documentation
+    doc: Option<Documentation>,
+    doc_deprecated: Option<DocDeprecated>,
+    doc_stability: Option<DocStability>,
+    doc_version: Option<DocVersion>,
+    source_position: Option<SourcePosition>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
+}
+
+impl DocSection {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl_documentable!(DocSection);
+
+impl_extensible!(DocSection);
+impl_spanned!(DocSection);
+impl_builder!(DocSection => DocSectionBuilder {
+    new {
+        name: String => name_mut;
+    }
+    optional {
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+    }
+    list {}
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for DocSection {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for DocSection {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .attr("name", &self.name)
+            .documentation(self)
+            .extra(self)
+    }
+}
@@ -1,15 +1,20 @@
-use xmlserde_derives::XmlDeserialize;
+use crate::{
+    extra::Extra,
+    prelude::*,
+    span::NodeSpan,
+    tree::{FromNode, Node},
+    writer::{Element, ToXml},
+};
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, XmlDeserialize)]
-#[xmlserde(root = b"source-position")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SourcePosition {
-    #[xmlserde(name = b"filename", ty = "attr")]
     filename: String,
-    #[xmlserde(name = b"line", ty = "attr")]
     line: String,
-    #[xmlserde(name = b"column", ty = "attr")]
     column: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl SourcePosition {
@@ -26,18 +31,30 @@ impl SourcePosition {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, XmlDeserialize)]
-#[xmlserde(root = b"doc-deprecated")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DocDeprecated {
-    #[xmlserde(name = b"xml:space", ty = "attr")]
     space: Option<String>,
-    #[xmlserde(name = b"xml:whitespace", ty = "attr")]
     whitespace: Option<String>,
-    #[xmlserde(ty = "text")]
     text: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
 }
 
+impl_spanned!(DocDeprecated);
+impl_builder!(DocDeprecated => DocDeprecatedBuilder {
+    new {
+        text: String => text_mut;
+    }
+    optional {
+        space: String => space, space_mut;
+        whitespace: String => whitespace, whitespace_mut;
+    }
+    list {}
+    value {}
+    hidden { span }
+});
+
 impl DocDeprecated {
     pub fn space(&self) -> Option<&str> {
         self.space.as_deref()
@@ -52,18 +69,30 @@ impl DocDeprecated {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, XmlDeserialize)]
-#[xmlserde(root = b"doc-stability")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DocStability {
-    #[xmlserde(name = b"xml:space", ty = "attr")]
     space: Option<String>,
-    #[xmlserde(name = b"xml:whitespace", ty = "attr")]
     whitespace: Option<String>,
-    #[xmlserde(ty = "text")]
     text: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
 }
 
+impl_spanned!(DocStability);
+impl_builder!(DocStability => DocStabilityBuilder {
+    new {
+        text: String => text_mut;
+    }
+    optional {
+        space: String => space, space_mut;
+        whitespace: String => whitespace, whitespace_mut;
+    }
+    list {}
+    value {}
+    hidden { span }
+});
+
 impl DocStability {
     pub fn space(&self) -> Option<&str> {
         self.space.as_deref()
@@ -78,18 +107,30 @@ impl DocStability {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, XmlDeserialize)]
-#[xmlserde(root = b"doc-version")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DocVersion {
-    #[xmlserde(name = b"xml:space", ty = "attr")]
     space: Option<String>,
-    #[xmlserde(name = b"xml:whitespace", ty = "attr")]
     whitespace: Option<String>,
-    #[xmlserde(ty = "text")]
     text: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
 }
 
+impl_spanned!(DocVersion);
+impl_builder!(DocVersion => DocVersionBuilder {
+    new {
+        text: String => text_mut;
+    }
+    optional {
+        space: String => space, space_mut;
+        whitespace: String => whitespace, whitespace_mut;
+    }
+    list {}
+    value {}
+    hidden { span }
+});
+
 impl DocVersion {
     pub fn space(&self) -> Option<&str> {
         self.space.as_deref()
@@ -104,24 +145,36 @@ impl DocVersion {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, XmlDeserialize)]
-#[xmlserde(root = b"doc")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Documentation {
-    #[xmlserde(name = b"xml:space", ty = "attr")]
     space: Option<String>,
-    #[xmlserde(name = b"xml:whitespace", ty = "attr")]
     whitespace: Option<String>,
-    #[xmlserde(name = b"filename", ty = "attr")]
     filename: Option<String>,
-    #[xmlserde(name = b"line", ty = "attr")]
     line: Option<String>,
-    #[xmlserde(name = b"column", ty = "attr")]
     column: Option<String>,
-    #[xmlserde(ty = "text")]
     text: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
 }
 
+impl_spanned!(Documentation);
+impl_builder!(Documentation => DocumentationBuilder {
+    new {
+        text: String => text_mut;
+    }
+    optional {
+        space: String => space, space_mut;
+        whitespace: String => whitespace, whitespace_mut;
+        filename: String => filename, filename_mut;
+        line: String => line, line_mut;
+        column: String => column, column_mut;
+    }
+    list {}
+    value {}
+    hidden { span }
+});
+
 impl Documentation {
     pub fn space(&self) -> Option<&str> {
         self.space.as_deref()
@@ -147,3 +200,130 @@ impl Documentation {
         &self.text
     }
 }
+
+impl_extensible!(SourcePosition);
+impl_spanned!(SourcePosition);
+impl_builder!(SourcePosition => SourcePositionBuilder {
+    new {
+        filename: String => filename_mut;
+        line: String => line_mut;
+    }
+    optional {
+        column: String => column, column_mut;
+    }
+    list {}
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for SourcePosition {
+    fn from_node(node: Node) -> Self {
+        Self {
+            filename: node.attr("filename"),
+            line: node.attr("line"),
+            column: node.opt_attr("column"),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for SourcePosition {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .attr("filename", &self.filename)
+            .attr("line", &self.line)
+            .opt_attr("column", &self.column)
+            .extra(self)
+    }
+}
+
+impl FromNode for DocDeprecated {
+    fn from_node(node: Node) -> Self {
+        Self {
+            space: node.opt_attr("xml:space"),
+            whitespace: node.opt_attr("xml:whitespace"),
+            text: node.text().into_owned(),
+            span: node.span(),
+        }
+    }
+}
+
+impl ToXml for DocDeprecated {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .opt_attr("xml:space", &self.space)
+            .opt_attr("xml:whitespace", &self.whitespace)
+            .text(&self.text)
+            .span(self)
+    }
+}
+
+impl FromNode for DocStability {
+    fn from_node(node: Node) -> Self {
+        Self {
+            space: node.opt_attr("xml:space"),
+            whitespace: node.opt_attr("xml:whitespace"),
+            text: node.text().into_owned(),
+            span: node.span(),
+        }
+    }
+}
+
+impl ToXml for DocStability {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .opt_attr("xml:space", &self.space)
+            .opt_attr("xml:whitespace", &self.whitespace)
+            .text(&self.text)
+            .span(self)
+    }
+}
+
+impl FromNode for DocVersion {
+    fn from_node(node: Node) -> Self {
+        Self {
+            space: node.opt_attr("xml:space"),
+            whitespace: node.opt_attr("xml:whitespace"),
+            text: node.text().into_owned(),
+            span: node.span(),
+        }
+    }
+}
+
+impl ToXml for DocVersion {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .opt_attr("xml:space", &self.space)
+            .opt_attr("xml:whitespace", &self.whitespace)
+            .text(&self.text)
+            .span(self)
+    }
+}
+
+impl FromNode for Documentation {
+    fn from_node(node: Node) -> Self {
+        Self {
+            space: node.opt_attr("xml:space"),
+            whitespace: node.opt_attr("xml:whitespace"),
+            filename: node.opt_attr("filename"),
+            line: node.opt_attr("line"),
+            column: node.opt_attr("column"),
+            text: node.text().into_owned(),
+            span: node.span(),
+        }
+    }
+}
+
+impl ToXml for Documentation {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .opt_attr("xml:space", &self.space)
+            .opt_attr("xml:whitespace", &self.whitespace)
+            .opt_attr("filename", &self.filename)
+            .opt_attr("line", &self.line)
+            .opt_attr("column", &self.column)
+            .text(&self.text)
+            .span(self)
+    }
+}
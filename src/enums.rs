@@ -1,62 +1,48 @@
-use xmlserde_derives::XmlDeserialize;
-
 use crate::{
     attribute::Attribute,
     documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition},
+    extra::Extra,
     function::{Function, FunctionInline},
     member::Member,
     prelude::*,
+    span::NodeSpan,
+    tree::{FromNode, Node},
     version::Version,
+    writer::{Element, ToXml},
     Stability,
 };
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
-#[xmlserde(root = b"enumeration")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Enumeration {
-    #[xmlserde(name = b"name", ty = "attr")]
     name: String,
-    #[xmlserde(name = b"c:type", ty = "attr")]
     c_type: String,
-    #[xmlserde(name = b"glib:type-name", ty = "attr")]
     g_type_name: Option<String>,
-    #[xmlserde(name = b"glib:get-type", ty = "attr")]
     g_get_type: Option<String>,
-    #[xmlserde(name = b"glib:error-domain", ty = "attr")]
     g_error_domain: Option<String>,
     // Common attributes
-    #[xmlserde(name = b"introspectable", ty = "attr")]
     introspectable: Option<bool>,
-    #[xmlserde(name = b"deprecated", ty = "attr")]
     deprecated: Option<bool>,
-    #[xmlserde(name = b"version", ty = "attr")]
     version: Option<Version>,
-    #[xmlserde(name = b"deprecated-version", ty = "attr")]
     deprecated_version: Option<Version>,
-    #[xmlserde(name = b"stability", ty = "attr")]
     stability: Option<Stability>,
     // Documentation
-    #[xmlserde(name = b"doc", ty = "child")]
     doc: Option<Documentation>,
-    #[xmlserde(name = b"doc-deprecated", ty = "child")]
     doc_deprecated: Option<DocDeprecated>,
-    #[xmlserde(name = b"doc-stability", ty = "child")]
     doc_stability: Option<DocStability>,
-    #[xmlserde(name = b"doc-version", ty = "child")]
     doc_version: Option<DocVersion>,
-    #[xmlserde(name = b"source-position", ty = "child")]
     source_position: Option<SourcePosition>,
     // Attributes: 0 or more
-    #[xmlserde(name = b"attribute", ty = "child")]
     attributes: Vec<Attribute>,
     // Members
-    #[xmlserde(name = b"member", ty = "child")]
     members: Vec<Member>,
     // Functions
-    #[xmlserde(name = b"function", ty = "child")]
     functions: Vec<Function>,
-    #[xmlserde(name = b"function-inline", ty = "child")]
+    #[cfg_attr(feature = "serde", serde(rename = "inlined_functions"))]
     inline_functions: Vec<FunctionInline>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Enumeration {
@@ -136,3 +122,81 @@ impl std::ops::Index<usize> for Enumeration {
         &self.members[index]
     }
 }
+
+impl_extensible!(Enumeration);
+impl_spanned!(Enumeration);
+impl_builder!(Enumeration => EnumerationBuilder {
+    new {
+        name: String => name_mut;
+        c_type: String => c_type_mut;
+    }
+    optional {
+        g_type_name: String => g_type_name, g_type_name_mut;
+        g_get_type: String => g_get_type, g_get_type_mut;
+        g_error_domain: String => g_error_domain, g_error_domain_mut;
+        introspectable: bool => introspectable, introspectable_mut;
+        deprecated: bool => deprecated, deprecated_mut;
+        version: Version => version, version_mut;
+        deprecated_version: Version => deprecated_version, deprecated_version_mut;
+        stability: Stability => stability, stability_mut;
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+    }
+    list {
+        attributes: Attribute => attribute, attributes_mut;
+        members: Member => member, members_mut;
+        functions: Function => function, functions_mut;
+        inline_functions: FunctionInline => inlined_function, inlined_functions_mut;
+    }
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for Enumeration {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            c_type: node.attr("c:type"),
+            g_type_name: node.opt_attr("glib:type-name"),
+            g_get_type: node.opt_attr("glib:get-type"),
+            g_error_domain: node.opt_attr("glib:error-domain"),
+            introspectable: node.opt_attr("introspectable"),
+            deprecated: node.opt_attr("deprecated"),
+            version: node.opt_attr("version"),
+            deprecated_version: node.opt_attr("deprecated-version"),
+            stability: node.opt_attr("stability"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            attributes: node.children("attribute"),
+            members: node.children("member"),
+            functions: node.children("function"),
+            inline_functions: node.children("function-inline"),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Enumeration {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .attr("name", &self.name)
+            .attr("c:type", &self.c_type)
+            .opt_attr("glib:type-name", &self.g_type_name)
+            .opt_attr("glib:get-type", &self.g_get_type)
+            .opt_attr("glib:error-domain", &self.g_error_domain)
+            .info(self)
+            .documentation(self)
+            .attributes(self)
+            .children("member", &self.members)
+            .children("function", &self.functions)
+            .children("function-inline", &self.inline_functions)
+            .extra(self)
+    }
+}
@@ -0,0 +1,294 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::OnceLock,
+};
+
+use crate::{
+    alias::Alias, array::Array, bitfield::BitField, callback::Callback, class::Class,
+    enums::Enumeration, interface::Interface, namespace::Namespace, r#type::Type, record::Record,
+    repository::Repository, union::Union,
+};
+
+/// Basic types that are not defined by any GIR file.
+const FUNDAMENTAL_TYPES: &[&str] = &[
+    "none",
+    "gboolean",
+    "gchar",
+    "guchar",
+    "gshort",
+    "gushort",
+    "gint",
+    "guint",
+    "glong",
+    "gulong",
+    "gint8",
+    "guint8",
+    "gint16",
+    "guint16",
+    "gint32",
+    "guint32",
+    "gint64",
+    "guint64",
+    "gfloat",
+    "gdouble",
+    "long double",
+    "gsize",
+    "gssize",
+    "goffset",
+    "gintptr",
+    "guintptr",
+    "gpointer",
+    "gconstpointer",
+    "gunichar",
+    "gunichar2",
+    "GType",
+    "utf8",
+    "filename",
+    "va_list",
+    "time_t",
+    "off_t",
+    "pid_t",
+    "uid_t",
+    "size_t",
+    "ssize_t",
+    "socklen_t",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveError {
+    /// The same namespace was loaded in more than one version.
+    DuplicateNamespace(String),
+    /// The namespace is not part of the environment.
+    UnknownNamespace(String),
+    /// The namespace does not define a type with that name.
+    UnknownType { namespace: String, name: String },
+    /// The type has no `name` attribute to resolve, only a C type.
+    MissingTypeName,
+    /// The name was expected to refer to a class.
+    NotAClass(String),
+    /// The name was expected to refer to an interface.
+    NotAnInterface(String),
+    /// A class inherits from itself, or an interface requires itself.
+    InheritanceCycle(String),
+}
+
+impl std::error::Error for ResolveError {}
+impl std::fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DuplicateNamespace(n) => {
+                f.write_fmt(format_args!("Namespace {n} is loaded more than once"))
+            }
+            Self::UnknownNamespace(n) => f.write_fmt(format_args!("Unknown namespace {n}")),
+            Self::UnknownType { namespace, name } => {
+                f.write_fmt(format_args!("Unknown type {name} in namespace {namespace}"))
+            }
+            Self::MissingTypeName => f.write_str("Type has no name to resolve"),
+            Self::NotAClass(n) => f.write_fmt(format_args!("{n} is not a class")),
+            Self::NotAnInterface(n) => f.write_fmt(format_args!("{n} is not an interface")),
+            Self::InheritanceCycle(n) => {
+                f.write_fmt(format_args!("Inheritance cycle involving {n}"))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolvedType<'a> {
+    Alias(&'a Alias),
+    BitField(&'a BitField),
+    Callback(&'a Callback),
+    Class(&'a Class),
+    Enumeration(&'a Enumeration),
+    Interface(&'a Interface),
+    Record(&'a Record),
+    Union(&'a Union),
+    Fundamental(&'a str),
+}
+
+impl<'a> ResolvedType<'a> {
+    pub fn name(&self) -> Option<&'a str> {
+        match self {
+            Self::Alias(a) => Some(a.name()),
+            Self::BitField(b) => Some(b.name()),
+            Self::Callback(c) => Some(c.name()),
+            Self::Class(c) => Some(c.name()),
+            Self::Enumeration(e) => Some(e.name()),
+            Self::Interface(i) => Some(i.name()),
+            Self::Record(r) => r.name(),
+            Self::Union(u) => u.name(),
+            Self::Fundamental(f) => Some(f),
+        }
+    }
+
+    pub fn is_fundamental(&self) -> bool {
+        matches!(self, Self::Fundamental(_))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TypeIndex {
+    Alias(usize),
+    BitField(usize),
+    Callback(usize),
+    Class(usize),
+    Enumeration(usize),
+    Interface(usize),
+    Record(usize),
+    Union(usize),
+}
+
+/// A set of repositories whose type references can be resolved against each
+/// other, usually built from
+/// [`Repository::from_path_follow_namespaces`].
+#[derive(Debug)]
+pub struct GirEnvironment {
+    repositories: BTreeMap<String, Repository>,
+    types: HashMap<String, HashMap<String, TypeIndex>>,
+    /// The qualified names of the children of each class, built on the first
+    /// query of the class hierarchy.
+    pub(crate) children: OnceLock<HashMap<String, Vec<String>>>,
+}
+
+impl GirEnvironment {
+    pub fn new(repositories: impl IntoIterator<Item = Repository>) -> Result<Self, ResolveError> {
+        let mut output = Self {
+            repositories: BTreeMap::new(),
+            types: HashMap::new(),
+            children: OnceLock::new(),
+        };
+        for repository in repositories {
+            let name = repository.namespace().name().to_owned();
+            if output.repositories.contains_key(&name) {
+                return Err(ResolveError::DuplicateNamespace(name));
+            }
+            output
+                .types
+                .insert(name.clone(), Self::index_namespace(repository.namespace()));
+            output.repositories.insert(name, repository);
+        }
+        Ok(output)
+    }
+
+    fn index_namespace(namespace: &Namespace) -> HashMap<String, TypeIndex> {
+        let mut types = HashMap::new();
+        let mut insert = |name: Option<&str>, index| {
+            if let Some(name) = name {
+                types.insert(name.to_owned(), index);
+            }
+        };
+        for (i, alias) in namespace.aliases().iter().enumerate() {
+            insert(Some(alias.name()), TypeIndex::Alias(i));
+        }
+        for (i, bitfield) in namespace.flags().iter().enumerate() {
+            insert(Some(bitfield.name()), TypeIndex::BitField(i));
+        }
+        for (i, callback) in namespace.callbacks().iter().enumerate() {
+            insert(Some(callback.name()), TypeIndex::Callback(i));
+        }
+        for (i, class) in namespace.classes().iter().enumerate() {
+            insert(Some(class.name()), TypeIndex::Class(i));
+        }
+        for (i, enumeration) in namespace.enums().iter().enumerate() {
+            insert(Some(enumeration.name()), TypeIndex::Enumeration(i));
+        }
+        for (i, interface) in namespace.interfaces().iter().enumerate() {
+            insert(Some(interface.name()), TypeIndex::Interface(i));
+        }
+        for (i, record) in namespace.records().iter().enumerate() {
+            insert(record.name(), TypeIndex::Record(i));
+        }
+        for (i, union) in namespace.unions().iter().enumerate() {
+            insert(union.name(), TypeIndex::Union(i));
+        }
+        types
+    }
+
+    pub fn repository(&self, namespace: &str) -> Option<&Repository> {
+        self.repositories.get(namespace)
+    }
+
+    /// The repositories of the environment, sorted by namespace name.
+    pub fn repositories(&self) -> impl Iterator<Item = &Repository> {
+        self.repositories.values()
+    }
+
+    pub fn namespace(&self, name: &str) -> Option<&Namespace> {
+        self.repository(name).map(|r| r.namespace())
+    }
+
+    /// Resolve a type name such as `Gio.File`, `Widget` or `gint`.
+    ///
+    /// Unqualified names are looked up in `namespace`, the namespace where
+    /// the reference appears.
+    pub fn resolve_name<'a>(
+        &'a self,
+        name: &str,
+        namespace: &str,
+    ) -> Result<ResolvedType<'a>, ResolveError> {
+        if !name.contains('.') {
+            if let Some(fundamental) = FUNDAMENTAL_TYPES.iter().find(|f| **f == name) {
+                return Ok(ResolvedType::Fundamental(fundamental));
+            }
+        }
+        self.lookup(name, namespace).map(|(_, resolved)| resolved)
+    }
+
+    /// Look up a type defined by one of the namespaces, along with the
+    /// namespace defining it.
+    pub(crate) fn lookup<'a>(
+        &'a self,
+        name: &str,
+        namespace: &str,
+    ) -> Result<(&'a Namespace, ResolvedType<'a>), ResolveError> {
+        let (namespace, local_name) = name.split_once('.').unwrap_or((namespace, name));
+        let ns = self
+            .namespace(namespace)
+            .ok_or_else(|| ResolveError::UnknownNamespace(namespace.to_owned()))?;
+        let index =
+            self.types[namespace]
+                .get(local_name)
+                .ok_or_else(|| ResolveError::UnknownType {
+                    namespace: namespace.to_owned(),
+                    name: local_name.to_owned(),
+                })?;
+        let resolved = match *index {
+            TypeIndex::Alias(i) => ResolvedType::Alias(&ns.aliases()[i]),
+            TypeIndex::BitField(i) => ResolvedType::BitField(&ns.flags()[i]),
+            TypeIndex::Callback(i) => ResolvedType::Callback(&ns.callbacks()[i]),
+            TypeIndex::Class(i) => ResolvedType::Class(&ns.classes()[i]),
+            TypeIndex::Enumeration(i) => ResolvedType::Enumeration(&ns.enums()[i]),
+            TypeIndex::Interface(i) => ResolvedType::Interface(&ns.interfaces()[i]),
+            TypeIndex::Record(i) => ResolvedType::Record(&ns.records()[i]),
+            TypeIndex::Union(i) => ResolvedType::Union(&ns.unions()[i]),
+        };
+        Ok((ns, resolved))
+    }
+
+    pub fn resolve_type<'a>(
+        &'a self,
+        ty: &'a Type,
+        namespace: &str,
+    ) -> Result<ResolvedType<'a>, ResolveError> {
+        let name = ty.name().ok_or(ResolveError::MissingTypeName)?;
+        self.resolve_name(name, namespace)
+    }
+
+    /// Resolve the element type of an array, or the innermost one for arrays
+    /// of arrays.
+    pub fn resolve_array<'a>(
+        &'a self,
+        array: &'a Array,
+        namespace: &str,
+    ) -> Result<ResolvedType<'a>, ResolveError> {
+        self.resolve_type(array.ty(), namespace)
+    }
+}
+
+impl TryFrom<HashMap<String, Repository>> for GirEnvironment {
+    type Error = ResolveError;
+
+    fn try_from(value: HashMap<String, Repository>) -> Result<Self, Self::Error> {
+        Self::new(value.into_values())
+    }
+}
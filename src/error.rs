@@ -0,0 +1,205 @@
+use std::path::{Path, PathBuf};
+
+/// A failure to parse a GIR document, along with where it happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParseError {
+    path: Option<PathBuf>,
+    offset: usize,
+    line: usize,
+    column: usize,
+    element_path: String,
+    message: String,
+    source_line: String,
+}
+
+impl ParseError {
+    pub(crate) fn new(
+        source: &str,
+        path: Option<&Path>,
+        offset: usize,
+        element_path: String,
+        message: String,
+    ) -> Self {
+        let offset = offset.min(source.len());
+        let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[offset..]
+            .find('\n')
+            .map_or(source.len(), |i| offset + i);
+        Self {
+            path: path.map(Path::to_path_buf),
+            offset,
+            line: source[..line_start].matches('\n').count() + 1,
+            column: source[line_start..offset].chars().count() + 1,
+            element_path,
+            message,
+            source_line: source[line_start..line_end].trim_end().to_owned(),
+        }
+    }
+
+    /// Move an error found in a fragment of a document to its place in the
+    /// document. The fragment starts at `start`, on line `first_line`, after
+    /// `column` characters of that line.
+    pub(crate) fn relocate(
+        mut self,
+        start: usize,
+        first_line: usize,
+        column: usize,
+        parent_path: &str,
+    ) -> Self {
+        self.offset += start;
+        if self.line == 1 && column > 0 {
+            self.column += column;
+            // Keep the caret under the failure, the text preceding the
+            // fragment isn't kept.
+            self.source_line.insert_str(0, &" ".repeat(column));
+        }
+        self.line += first_line - 1;
+        if !parent_path.is_empty() {
+            self.element_path = match self.element_path.is_empty() {
+                true => parent_path.to_owned(),
+                false => format!("{parent_path}/{}", self.element_path),
+            };
+        }
+        self
+    }
+
+    /// The file being parsed, if the document was read from a file.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// The byte offset of the failure in the document.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The line of the failure, starting at 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The column of the failure, in characters and starting at 1.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// The path to the element being parsed, such as:
+    ///
+    /// ```text
+    /// namespace/class[@name=Widget]/method[@name=show]/parameters/parameter[2]
+    /// ```
+    ///
+    /// Elements are identified by their `name` attribute when they have one,
+    /// except for parameters which are identified by their position.
+    pub fn element_path(&self) -> &str {
+        &self.element_path
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Render the error with the offending line of the document, in the
+    /// style of a compiler diagnostic.
+    pub fn diagnostic(&self) -> String {
+        self.render("error")
+    }
+
+    fn render(&self, level: &str) -> String {
+        let line_number = self.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        let location = match &self.path {
+            Some(path) => format!("{}:{}:{}", path.display(), self.line, self.column),
+            None => format!("{}:{}", self.line, self.column),
+        };
+        let caret_offset: String = self
+            .source_line
+            .chars()
+            .take(self.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let mut output = format!(
+            "{level}: {}\n{gutter}--> {location}\n{gutter} |\n{line_number} | {}\n{gutter} | {caret_offset}^\n",
+            self.message, self.source_line
+        );
+        if !self.element_path.is_empty() {
+            output.push_str(&format!("{gutter} = in {}\n", self.element_path));
+        }
+        output
+    }
+}
+
+impl std::error::Error for ParseError {}
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(path) = &self.path {
+            f.write_fmt(format_args!("{}:", path.display()))?;
+        }
+        f.write_fmt(format_args!(
+            "{}:{}: {}",
+            self.line, self.column, self.message
+        ))?;
+        if !self.element_path.is_empty() {
+            f.write_fmt(format_args!(" (in {})", self.element_path))?;
+        }
+        Ok(())
+    }
+}
+
+/// A problem found in a GIR document parsed in lenient mode, which was
+/// skipped instead of failing the parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParseWarning(ParseError);
+
+impl ParseWarning {
+    pub(crate) fn new(error: ParseError) -> Self {
+        Self(error)
+    }
+
+    pub(crate) fn relocate(
+        self,
+        start: usize,
+        first_line: usize,
+        column: usize,
+        parent_path: &str,
+    ) -> Self {
+        Self(self.0.relocate(start, first_line, column, parent_path))
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.0.path()
+    }
+
+    pub fn offset(&self) -> usize {
+        self.0.offset()
+    }
+
+    pub fn line(&self) -> usize {
+        self.0.line()
+    }
+
+    pub fn column(&self) -> usize {
+        self.0.column()
+    }
+
+    /// See [`ParseError::element_path`].
+    pub fn element_path(&self) -> &str {
+        self.0.element_path()
+    }
+
+    pub fn message(&self) -> &str {
+        self.0.message()
+    }
+
+    pub fn diagnostic(&self) -> String {
+        self.0.render("warning")
+    }
+}
+
+impl std::fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
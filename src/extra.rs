@@ -0,0 +1,30 @@
+/// The content of a node that isn't modelled by this crate, kept around so it
+/// can be written back.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct Extra {
+    attributes: Vec<(String, String)>,
+    children: Vec<String>,
+}
+
+impl Extra {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.attributes.is_empty() && self.children.is_empty()
+    }
+
+    pub(crate) fn attributes(&self) -> &[(String, String)] {
+        &self.attributes
+    }
+
+    pub(crate) fn children(&self) -> &[String] {
+        &self.children
+    }
+
+    pub(crate) fn push_attribute(&mut self, name: &str, value: &str) {
+        self.attributes.push((name.to_owned(), value.to_owned()));
+    }
+
+    pub(crate) fn push_child(&mut self, fragment: &str) {
+        self.children.push(fragment.to_owned());
+    }
+}
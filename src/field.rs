@@ -1,24 +1,28 @@
-use xmlserde_derives::XmlDeserialize;
-
 use crate::{
     array::Array,
     attribute::Attribute,
     callback::Callback,
     documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition},
+    extra::Extra,
     prelude::*,
     r#type::Type,
+    span::NodeSpan,
+    tree::{FromNode, FromTag, Node},
     version::Version,
+    writer::{Element, ToXml},
     Stability,
 };
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 #[allow(clippy::large_enum_variant)]
 pub enum FieldType {
-    #[xmlserde(name = b"type")]
     Type(Type),
-    #[xmlserde(name = b"callback")]
     Callback(Callback),
-    #[xmlserde(name = b"array")]
     Array(Array),
 }
 
@@ -60,51 +64,37 @@ impl FieldType {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
-#[xmlserde(root = b"field")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Field {
-    #[xmlserde(name = b"name", ty = "attr")]
     name: String,
-    #[xmlserde(name = b"readable", ty = "attr")]
     readable: Option<bool>,
-    #[xmlserde(name = b"writable", ty = "attr")]
     writable: Option<bool>,
     // Seems to be set by libgee, which is a weird use case anyways
     // Kept hidden from the external API as it is not supposed to be set per the spec
-    #[xmlserde(name = b"nullable", ty = "attr")]
+    #[cfg_attr(feature = "serde", serde(rename = "nullable"))]
     _nullable: Option<bool>,
-    #[xmlserde(name = b"private", ty = "attr")]
     private: Option<bool>,
-    #[xmlserde(name = b"bits", ty = "attr")]
     bits: Option<u8>,
     // Common attributes
-    #[xmlserde(name = b"introspectable", ty = "attr")]
     introspectable: Option<bool>,
-    #[xmlserde(name = b"deprecated", ty = "attr")]
     deprecated: Option<bool>,
-    #[xmlserde(name = b"version", ty = "attr")]
     version: Option<Version>,
-    #[xmlserde(name = b"deprecated-version", ty = "attr")]
     deprecated_version: Option<Version>,
-    #[xmlserde(name = b"stability", ty = "attr")]
     stability: Option<Stability>,
     // Documentation
-    #[xmlserde(name = b"doc", ty = "child")]
     doc: Option<Documentation>,
-    #[xmlserde(name = b"doc-deprecated", ty = "child")]
     doc_deprecated: Option<DocDeprecated>,
-    #[xmlserde(name = b"doc-stability", ty = "child")]
     doc_stability: Option<DocStability>,
-    #[xmlserde(name = b"doc-version", ty = "child")]
     doc_version: Option<DocVersion>,
-    #[xmlserde(name = b"source-position", ty = "child")]
     source_position: Option<SourcePosition>,
     // Attributes: 0 or more
-    #[xmlserde(name = b"attribute", ty = "child")]
     attributes: Vec<Attribute>,
-    #[xmlserde(ty = "untag")]
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
     type_: FieldType,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Field {
@@ -136,3 +126,98 @@ impl Field {
 impl_info!(Field);
 impl_documentable!(Field);
 impl_attributable!(Field);
+
+impl FromTag for FieldType {
+    fn from_tag(node: Node) -> Option<Self> {
+        match node.tag() {
+            "type" => Some(Self::Type(Type::from_node(node))),
+            "callback" => Some(Self::Callback(Callback::from_node(node))),
+            "array" => Some(Self::Array(Array::from_node(node))),
+            _ => None,
+        }
+    }
+}
+
+impl ToXml for FieldType {
+    fn to_xml(&self, _tag: &str) -> Element {
+        match self {
+            Self::Type(t) => t.to_xml("type"),
+            Self::Callback(c) => c.to_xml("callback"),
+            Self::Array(a) => a.to_xml("array"),
+        }
+    }
+}
+
+impl_extensible!(Field);
+impl_spanned!(Field);
+impl_builder!(Field => FieldBuilder {
+    new {
+        name: String => name_mut;
+        type_: FieldType => ty_mut;
+    }
+    optional {
+        readable: bool => readable, readable_mut;
+        writable: bool => writable, writable_mut;
+        private: bool => private, private_mut;
+        bits: u8 => bits, bits_mut;
+        introspectable: bool => introspectable, introspectable_mut;
+        deprecated: bool => deprecated, deprecated_mut;
+        version: Version => version, version_mut;
+        deprecated_version: Version => deprecated_version, deprecated_version_mut;
+        stability: Stability => stability, stability_mut;
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+    }
+    list {
+        attributes: Attribute => attribute, attributes_mut;
+    }
+    value {}
+    hidden { _nullable, span, extra }
+});
+
+impl FromNode for Field {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            readable: node.opt_attr("readable"),
+            writable: node.opt_attr("writable"),
+            _nullable: node.opt_attr("nullable"),
+            private: node.opt_attr("private"),
+            bits: node.opt_attr("bits"),
+            introspectable: node.opt_attr("introspectable"),
+            deprecated: node.opt_attr("deprecated"),
+            version: node.opt_attr("version"),
+            deprecated_version: node.opt_attr("deprecated-version"),
+            stability: node.opt_attr("stability"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            attributes: node.children("attribute"),
+            type_: node.one_of(),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Field {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .attr("name", &self.name)
+            .opt_attr("readable", &self.readable)
+            .opt_attr("writable", &self.writable)
+            .opt_attr("nullable", &self._nullable)
+            .opt_attr("private", &self.private)
+            .opt_attr("bits", &self.bits)
+            .info(self)
+            .documentation(self)
+            .attributes(self)
+            .untagged([&self.type_])
+            .extra(self)
+    }
+}
@@ -1,67 +1,49 @@
-use xmlserde_derives::XmlDeserialize;
-
 use crate::{
     attribute::Attribute,
     documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition},
+    extra::Extra,
     parameter::Parameters,
     prelude::*,
     return_value::ReturnValue,
+    span::NodeSpan,
+    tree::{FromNode, Node},
     version::Version,
+    writer::{Element, ToXml},
     Stability,
 };
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
-#[xmlserde(root = b"function")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Function {
     // Callable attributes
-    #[xmlserde(name = b"name", ty = "attr")]
     name: String,
-    #[xmlserde(name = b"c:identifier", ty = "attr")]
     c_identifier: Option<String>,
-    #[xmlserde(name = b"shadows", ty = "attr")]
     shadows: Option<String>,
-    #[xmlserde(name = b"shadowed-by", ty = "attr")]
     shadowed_by: Option<String>,
-    #[xmlserde(name = b"throws", ty = "attr")]
     throws: Option<bool>,
-    #[xmlserde(name = b"moved-to", ty = "attr")]
     moved_to: Option<String>,
-    #[xmlserde(name = b"glib:async-func", ty = "attr")]
     async_func: Option<String>,
-    #[xmlserde(name = b"glib:finish-func", ty = "attr")]
     finish_func: Option<String>,
-    #[xmlserde(name = b"glib:sync-func", ty = "attr")]
     sync_func: Option<String>,
     // Common attributes
-    #[xmlserde(name = b"introspectable", ty = "attr")]
     introspectable: Option<bool>,
-    #[xmlserde(name = b"deprecated", ty = "attr")]
     deprecated: Option<bool>,
-    #[xmlserde(name = b"version", ty = "attr")]
     version: Option<Version>,
-    #[xmlserde(name = b"deprecated-version", ty = "attr")]
     deprecated_version: Option<Version>,
-    #[xmlserde(name = b"stability", ty = "attr")]
     stability: Option<Stability>,
     // Documentation
-    #[xmlserde(name = b"doc", ty = "child")]
     doc: Option<Documentation>,
-    #[xmlserde(name = b"doc-deprecated", ty = "child")]
     doc_deprecated: Option<DocDeprecated>,
-    #[xmlserde(name = b"doc-stability", ty = "child")]
     doc_stability: Option<DocStability>,
-    #[xmlserde(name = b"doc-version", ty = "child")]
     doc_version: Option<DocVersion>,
-    #[xmlserde(name = b"source-position", ty = "child")]
     source_position: Option<SourcePosition>,
     // Attributes: 0 or more
-    #[xmlserde(name = b"attribute", ty = "child")]
     attributes: Vec<Attribute>,
-    #[xmlserde(name = b"return-value", ty = "child")]
     return_value: ReturnValue,
-    #[xmlserde(name = b"parameters", ty = "child", default = "Parameters::default")]
     parameters: Parameters,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl_info!(Function);
@@ -70,59 +52,39 @@ impl_documentable!(Function);
 impl_callable!(Function);
 impl_function_like!(Function);
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
-#[xmlserde(root = b"function-inline")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FunctionInline {
     // Callable attributes
-    #[xmlserde(name = b"name", ty = "attr")]
     name: String,
-    #[xmlserde(name = b"c:identifier", ty = "attr")]
     c_identifier: Option<String>,
-    #[xmlserde(name = b"shadows", ty = "attr")]
     shadows: Option<String>,
-    #[xmlserde(name = b"shadowed-by", ty = "attr")]
     shadowed_by: Option<String>,
-    #[xmlserde(name = b"throws", ty = "attr")]
     throws: Option<bool>,
-    #[xmlserde(name = b"moved-to", ty = "attr")]
     moved_to: Option<String>,
-    #[xmlserde(name = b"glib:async-func", ty = "attr")]
     async_func: Option<String>,
-    #[xmlserde(name = b"glib:finish-func", ty = "attr")]
     finish_func: Option<String>,
-    #[xmlserde(name = b"glib:sync-func", ty = "attr")]
     sync_func: Option<String>,
     // Common attributes
-    #[xmlserde(name = b"introspectable", ty = "attr")]
     introspectable: Option<bool>,
-    #[xmlserde(name = b"deprecated", ty = "attr")]
     deprecated: Option<bool>,
-    #[xmlserde(name = b"version", ty = "attr")]
     version: Option<Version>,
-    #[xmlserde(name = b"deprecated-version", ty = "attr")]
     deprecated_version: Option<Version>,
-    #[xmlserde(name = b"stability", ty = "attr")]
     stability: Option<Stability>,
     // Documentation
-    #[xmlserde(name = b"doc", ty = "child")]
     doc: Option<Documentation>,
-    #[xmlserde(name = b"doc-deprecated", ty = "child")]
     doc_deprecated: Option<DocDeprecated>,
-    #[xmlserde(name = b"doc-stability", ty = "child")]
     doc_stability: Option<DocStability>,
-    #[xmlserde(name = b"doc-version", ty = "child")]
     doc_version: Option<DocVersion>,
-    #[xmlserde(name = b"source-position", ty = "child")]
     source_position: Option<SourcePosition>,
     // Attributes: 0 or more
-    #[xmlserde(name = b"attribute", ty = "child")]
     attributes: Vec<Attribute>,
 
-    #[xmlserde(name = b"return-value", ty = "child")]
     return_value: ReturnValue,
-    #[xmlserde(name = b"parameters", ty = "child", default = "Parameters::default")]
     parameters: Parameters,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl_info!(FunctionInline);
@@ -130,3 +92,169 @@ impl_attributable!(FunctionInline);
 impl_documentable!(FunctionInline);
 impl_callable!(FunctionInline);
 impl_function_like!(FunctionInline);
+
+impl_extensible!(Function);
+impl_spanned!(Function);
+impl_builder!(Function => FunctionBuilder {
+    new {
+        name: String => name_mut;
+    }
+    optional {
+        c_identifier: String => c_identifier, c_identifier_mut;
+        shadows: String => shadows, shadows_mut;
+        shadowed_by: String => shadowed_by, shadowed_by_mut;
+        throws: bool => throws, throws_mut;
+        moved_to: String => moved_to, moved_to_mut;
+        async_func: String => async_func, async_func_mut;
+        finish_func: String => finish_func, finish_func_mut;
+        sync_func: String => sync_func, sync_func_mut;
+        introspectable: bool => introspectable, introspectable_mut;
+        deprecated: bool => deprecated, deprecated_mut;
+        version: Version => version, version_mut;
+        deprecated_version: Version => deprecated_version, deprecated_version_mut;
+        stability: Stability => stability, stability_mut;
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+    }
+    list {
+        attributes: Attribute => attribute, attributes_mut;
+    }
+    value {
+        return_value: ReturnValue => return_value, return_value_mut;
+        parameters: Parameters => parameters, parameters_mut;
+    }
+    hidden { span, extra }
+});
+impl_parameters_builder!(FunctionBuilder);
+impl FromNode for Function {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            c_identifier: node.opt_attr("c:identifier"),
+            shadows: node.opt_attr("shadows"),
+            shadowed_by: node.opt_attr("shadowed-by"),
+            throws: node.opt_attr("throws"),
+            moved_to: node.opt_attr("moved-to"),
+            async_func: node.opt_attr("glib:async-func"),
+            finish_func: node.opt_attr("glib:finish-func"),
+            sync_func: node.opt_attr("glib:sync-func"),
+            introspectable: node.opt_attr("introspectable"),
+            deprecated: node.opt_attr("deprecated"),
+            version: node.opt_attr("version"),
+            deprecated_version: node.opt_attr("deprecated-version"),
+            stability: node.opt_attr("stability"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            attributes: node.children("attribute"),
+            return_value: node.required("return-value"),
+            parameters: node.child("parameters").unwrap_or_default(),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Function {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .callable(self)
+            .info(self)
+            .documentation(self)
+            .attributes(self)
+            .child("return-value", &self.return_value)
+            .opt_child(
+                "parameters",
+                (!self.parameters.is_empty()).then_some(&self.parameters),
+            )
+            .extra(self)
+    }
+}
+
+impl_extensible!(FunctionInline);
+impl_spanned!(FunctionInline);
+impl_builder!(FunctionInline => FunctionInlineBuilder {
+    new {
+        name: String => name_mut;
+    }
+    optional {
+        c_identifier: String => c_identifier, c_identifier_mut;
+        shadows: String => shadows, shadows_mut;
+        shadowed_by: String => shadowed_by, shadowed_by_mut;
+        throws: bool => throws, throws_mut;
+        moved_to: String => moved_to, moved_to_mut;
+        async_func: String => async_func, async_func_mut;
+        finish_func: String => finish_func, finish_func_mut;
+        sync_func: String => sync_func, sync_func_mut;
+        introspectable: bool => introspectable, introspectable_mut;
+        deprecated: bool => deprecated, deprecated_mut;
+        version: Version => version, version_mut;
+        deprecated_version: Version => deprecated_version, deprecated_version_mut;
+        stability: Stability => stability, stability_mut;
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+    }
+    list {
+        attributes: Attribute => attribute, attributes_mut;
+    }
+    value {
+        return_value: ReturnValue => return_value, return_value_mut;
+        parameters: Parameters => parameters, parameters_mut;
+    }
+    hidden { span, extra }
+});
+impl_parameters_builder!(FunctionInlineBuilder);
+impl FromNode for FunctionInline {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            c_identifier: node.opt_attr("c:identifier"),
+            shadows: node.opt_attr("shadows"),
+            shadowed_by: node.opt_attr("shadowed-by"),
+            throws: node.opt_attr("throws"),
+            moved_to: node.opt_attr("moved-to"),
+            async_func: node.opt_attr("glib:async-func"),
+            finish_func: node.opt_attr("glib:finish-func"),
+            sync_func: node.opt_attr("glib:sync-func"),
+            introspectable: node.opt_attr("introspectable"),
+            deprecated: node.opt_attr("deprecated"),
+            version: node.opt_attr("version"),
+            deprecated_version: node.opt_attr("deprecated-version"),
+            stability: node.opt_attr("stability"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            attributes: node.children("attribute"),
+            return_value: node.required("return-value"),
+            parameters: node.child("parameters").unwrap_or_default(),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for FunctionInline {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .callable(self)
+            .info(self)
+            .documentation(self)
+            .attributes(self)
+            .child("return-value", &self.return_value)
+            .opt_child(
+                "parameters",
+                (!self.parameters.is_empty()).then_some(&self.parameters),
+            )
+            .extra(self)
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{
+    class::Class,
+    environment::{GirEnvironment, ResolveError, ResolvedType},
+    interface::Interface,
+    namespace::Namespace,
+};
+
+pub(crate) fn qualified_name(name: &str, namespace: &str) -> String {
+    if name.contains('.') {
+        name.to_owned()
+    } else {
+        format!("{namespace}.{name}")
+    }
+}
+
+impl GirEnvironment {
+    pub fn resolve_class<'a>(
+        &'a self,
+        name: &str,
+        namespace: &str,
+    ) -> Result<(&'a Namespace, &'a Class), ResolveError> {
+        match self.lookup(name, namespace)? {
+            (ns, ResolvedType::Class(class)) => Ok((ns, class)),
+            _ => Err(ResolveError::NotAClass(qualified_name(name, namespace))),
+        }
+    }
+
+    pub fn resolve_interface<'a>(
+        &'a self,
+        name: &str,
+        namespace: &str,
+    ) -> Result<(&'a Namespace, &'a Interface), ResolveError> {
+        match self.lookup(name, namespace)? {
+            (ns, ResolvedType::Interface(interface)) => Ok((ns, interface)),
+            _ => Err(ResolveError::NotAnInterface(qualified_name(
+                name, namespace,
+            ))),
+        }
+    }
+
+    /// The parent classes of a class, starting with its direct parent and
+    /// ending with the root of the hierarchy.
+    pub fn ancestors<'a>(
+        &'a self,
+        name: &str,
+        namespace: &str,
+    ) -> Result<Vec<(&'a Namespace, &'a Class)>, ResolveError> {
+        let (mut ns, mut class) = self.resolve_class(name, namespace)?;
+        let mut visited = HashSet::from([qualified_name(class.name(), ns.name())]);
+        let mut ancestors = Vec::new();
+        while let Some(parent) = class.parent() {
+            (ns, class) = self.resolve_class(parent, ns.name())?;
+            if !visited.insert(qualified_name(class.name(), ns.name())) {
+                return Err(ResolveError::InheritanceCycle(qualified_name(
+                    class.name(),
+                    ns.name(),
+                )));
+            }
+            ancestors.push((ns, class));
+        }
+        Ok(ancestors)
+    }
+
+    /// Whether `ancestor` is one of the parent classes of the class. It is
+    /// resolved like `name`, so `Object` and `GObject.Object` both work
+    /// from the `GObject` namespace. A class is not a subclass of itself.
+    pub fn is_subclass_of(
+        &self,
+        name: &str,
+        namespace: &str,
+        ancestor: &str,
+    ) -> Result<bool, ResolveError> {
+        let (_, ancestor) = self.resolve_class(ancestor, namespace)?;
+        Ok(self
+            .ancestors(name, namespace)?
+            .iter()
+            .any(|(_, class)| std::ptr::eq(*class, ancestor)))
+    }
+
+    /// Every interface implemented by a class, either directly, through one of
+    /// its parent classes or as a prerequisite of another implemented
+    /// interface. Each interface is listed once, in discovery order.
+    pub fn all_implemented_interfaces<'a>(
+        &'a self,
+        name: &str,
+        namespace: &str,
+    ) -> Result<Vec<(&'a Namespace, &'a Interface)>, ResolveError> {
+        let class = self.resolve_class(name, namespace)?;
+        let mut interfaces = Vec::new();
+        let mut seen = HashSet::new();
+        for (ns, class) in std::iter::once(class).chain(self.ancestors(name, namespace)?) {
+            for implements in class.implements() {
+                self.collect_interfaces(
+                    implements.name(),
+                    ns.name(),
+                    &mut Vec::new(),
+                    &mut seen,
+                    &mut interfaces,
+                )?;
+            }
+        }
+        Ok(interfaces)
+    }
+
+    /// Every interface required by an interface, through its prerequisites
+    /// and their own prerequisites. Class prerequisites are skipped.
+    pub fn all_prerequisites<'a>(
+        &'a self,
+        name: &str,
+        namespace: &str,
+    ) -> Result<Vec<(&'a Namespace, &'a Interface)>, ResolveError> {
+        let (ns, interface) = self.resolve_interface(name, namespace)?;
+        let mut stack = vec![qualified_name(interface.name(), ns.name())];
+        let mut seen = HashSet::new();
+        let mut interfaces = Vec::new();
+        self.collect_prerequisites(ns, interface, &mut stack, &mut seen, &mut interfaces)?;
+        Ok(interfaces)
+    }
+
+    fn collect_interfaces<'a>(
+        &'a self,
+        name: &str,
+        namespace: &str,
+        stack: &mut Vec<String>,
+        seen: &mut HashSet<String>,
+        interfaces: &mut Vec<(&'a Namespace, &'a Interface)>,
+    ) -> Result<(), ResolveError> {
+        let (ns, interface) = match self.lookup(name, namespace)? {
+            (ns, ResolvedType::Interface(interface)) => (ns, interface),
+            // Prerequisites can also be classes, which are part of the
+            // class hierarchy instead.
+            (_, ResolvedType::Class(_)) if !stack.is_empty() => return Ok(()),
+            _ => {
+                return Err(ResolveError::NotAnInterface(qualified_name(
+                    name, namespace,
+                )))
+            }
+        };
+        let qualified = qualified_name(interface.name(), ns.name());
+        if stack.contains(&qualified) {
+            return Err(ResolveError::InheritanceCycle(qualified));
+        }
+        if !seen.insert(qualified.clone()) {
+            return Ok(());
+        }
+        interfaces.push((ns, interface));
+        stack.push(qualified);
+        self.collect_prerequisites(ns, interface, stack, seen, interfaces)?;
+        stack.pop();
+        Ok(())
+    }
+
+    fn collect_prerequisites<'a>(
+        &'a self,
+        ns: &'a Namespace,
+        interface: &'a Interface,
+        stack: &mut Vec<String>,
+        seen: &mut HashSet<String>,
+        interfaces: &mut Vec<(&'a Namespace, &'a Interface)>,
+    ) -> Result<(), ResolveError> {
+        let names = interface
+            .prerequisites()
+            .iter()
+            .map(|p| p.name())
+            .chain(interface.implements().iter().map(|i| i.name()));
+        for name in names {
+            self.collect_interfaces(name, ns.name(), stack, seen, interfaces)?;
+        }
+        Ok(())
+    }
+
+    /// Every class inheriting from a class, directly or not, across all the
+    /// repositories of the environment. Direct children come first.
+    ///
+    /// The classes whose parent can't be resolved, such as when the
+    /// repository of the parent isn't part of the environment, are left out
+    /// along with their own descendants. Fails when the class is part of an
+    /// inheritance cycle.
+    pub fn descendants<'a>(
+        &'a self,
+        name: &str,
+        namespace: &str,
+    ) -> Result<Vec<(&'a Namespace, &'a Class)>, ResolveError> {
+        let (ns, class) = self.resolve_class(name, namespace)?;
+        let children = self.children.get_or_init(|| self.index_children());
+        let qualified = qualified_name(class.name(), ns.name());
+        let mut seen = HashSet::from([qualified.as_str()]);
+        let mut descendants = Vec::new();
+        let mut queue = VecDeque::from([qualified.as_str()]);
+        while let Some(class) = queue.pop_front() {
+            for child in children.get(class).into_iter().flatten() {
+                // Classes have a single parent, so a class is only found twice
+                // when it inherits from itself.
+                if !seen.insert(child) {
+                    return Err(ResolveError::InheritanceCycle(child.clone()));
+                }
+                descendants.push(self.resolve_class(child, "")?);
+                queue.push_back(child);
+            }
+        }
+        Ok(descendants)
+    }
+
+    /// The qualified names of the children of each class, skipping the
+    /// classes whose parent can't be resolved.
+    fn index_children(&self) -> HashMap<String, Vec<String>> {
+        let mut children = HashMap::<_, Vec<_>>::new();
+        for repository in self.repositories() {
+            let ns = repository.namespace();
+            for class in ns.classes() {
+                let Some(parent) = class.parent() else {
+                    continue;
+                };
+                let Ok((parent_ns, parent)) = self.resolve_class(parent, ns.name()) else {
+                    continue;
+                };
+                children
+                    .entry(qualified_name(parent.name(), parent_ns.name()))
+                    .or_default()
+                    .push(qualified_name(class.name(), ns.name()));
+            }
+        }
+        children
+    }
+}
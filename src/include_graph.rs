@@ -0,0 +1,234 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    path::Path,
+    sync::{Condvar, Mutex},
+};
+
+use crate::{
+    error::ParseWarning, repository::Repository, search_path::GirSearchPath, ParseOptions,
+    ParserError,
+};
+
+/// A set of GIR files loaded along with the files of the namespaces they
+/// include, recursively.
+///
+/// Repositories are ordered so each one comes after the ones it includes,
+/// with ties broken by file name, making the order deterministic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncludeGraph {
+    repositories: Vec<(String, Repository)>,
+}
+
+#[derive(Default)]
+struct LoadState {
+    /// The files left to load.
+    queue: VecDeque<String>,
+    seen: HashSet<String>,
+    in_flight: usize,
+    loaded: HashMap<String, Repository>,
+    /// The failure of the first file by name.
+    error: Option<(String, ParserError)>,
+}
+
+impl LoadState {
+    fn push(&mut self, package_file: String) {
+        if self.seen.insert(package_file.clone()) {
+            self.queue.push_back(package_file);
+        }
+    }
+}
+
+impl IncludeGraph {
+    /// Load GIR files, such as `Gtk-4.0.gir`, and their includes from a
+    /// search path. Each file is parsed once, in parallel.
+    ///
+    /// When several files fail to load, the error of the first of them by
+    /// file name is returned, whichever failed first.
+    pub fn load(
+        package_files: impl IntoIterator<Item = impl AsRef<str>>,
+        search_path: &GirSearchPath,
+    ) -> Result<Self, ParserError> {
+        Self::load_with(package_files, search_path, &|path| {
+            Repository::from_path(path)
+        })
+    }
+
+    /// Like [`IncludeGraph::load`], parsing the files with `options`. The
+    /// warnings of all the files are returned, ordered by file.
+    ///
+    /// Sharing an [`Interner`](crate::Interner) through the options
+    /// deduplicates names across all the loaded repositories.
+    pub fn load_with_options(
+        package_files: impl IntoIterator<Item = impl AsRef<str>>,
+        search_path: &GirSearchPath,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        let warnings = Mutex::new(Vec::new());
+        let graph = Self::load_with(package_files, search_path, &|path| {
+            let (repository, file_warnings) = Repository::from_path_with_options(path, options)?;
+            warnings
+                .lock()
+                .expect("warnings poisoned")
+                .extend(file_warnings);
+            Ok(repository)
+        })?;
+        let mut warnings = warnings.into_inner().expect("warnings poisoned");
+        warnings.sort_by(|a, b| (a.path(), a.offset()).cmp(&(b.path(), b.offset())));
+        Ok((graph, warnings))
+    }
+
+    /// Load the include graph, reading each located file with `read`.
+    pub(crate) fn load_with(
+        package_files: impl IntoIterator<Item = impl AsRef<str>>,
+        search_path: &GirSearchPath,
+        read: &(dyn Fn(&Path) -> Result<Repository, ParserError> + Sync),
+    ) -> Result<Self, ParserError> {
+        let mut state = LoadState::default();
+        for package_file in package_files {
+            state.push(package_file.as_ref().to_owned());
+        }
+        // Includes are queued as files load, so workers without a file wait
+        // for one rather than sizing the pool from the initial queue.
+        let workers = std::thread::available_parallelism().map_or(1, usize::from);
+        let state = Mutex::new(state);
+        let changed = Condvar::new();
+        std::thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| load_worker(&state, &changed, search_path, read));
+            }
+        });
+
+        let state = state.into_inner().expect("loader state poisoned");
+        if let Some((_, error)) = state.error {
+            return Err(error);
+        }
+        Self::sort(state.loaded)
+    }
+
+    /// Order the repositories topologically, using Kahn's algorithm.
+    fn sort(mut loaded: HashMap<String, Repository>) -> Result<Self, ParserError> {
+        let mut dependents = BTreeMap::<&str, Vec<&str>>::new();
+        let mut pending = BTreeMap::new();
+        for (package_file, repository) in &loaded {
+            let includes = repository
+                .namespace_includes()
+                .iter()
+                .map(|include| include.as_package_file())
+                .collect::<BTreeSet<_>>();
+            pending.insert(package_file.as_str(), includes.len());
+            for include in includes {
+                let (include, _) = loaded.get_key_value(&include).expect("includes are loaded");
+                dependents
+                    .entry(include.as_str())
+                    .or_default()
+                    .push(package_file);
+            }
+        }
+        let mut ready = pending
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(package_file, _)| *package_file)
+            .collect::<BTreeSet<_>>();
+        let mut order = Vec::with_capacity(loaded.len());
+        while let Some(package_file) = ready.pop_first() {
+            pending.remove(package_file);
+            order.push(package_file.to_owned());
+            for dependent in dependents.get(package_file).into_iter().flatten() {
+                let count = pending.get_mut(dependent).expect("dependent is pending");
+                *count -= 1;
+                if *count == 0 {
+                    ready.insert(dependent);
+                }
+            }
+        }
+        if let Some((package_file, _)) = pending.first_key_value() {
+            return Err(ParserError::IncludeCycle(package_file.to_string()));
+        }
+        let repositories = order
+            .into_iter()
+            .map(|package_file| {
+                let repository = loaded.remove(&package_file).expect("file is loaded");
+                (package_file, repository)
+            })
+            .collect();
+        Ok(Self { repositories })
+    }
+
+    pub fn len(&self) -> usize {
+        self.repositories.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.repositories.is_empty()
+    }
+
+    /// The repositories along with their file name, in topological order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Repository)> {
+        self.repositories
+            .iter()
+            .map(|(package_file, repository)| (package_file.as_str(), repository))
+    }
+
+    pub fn get(&self, package_file: &str) -> Option<&Repository> {
+        self.iter()
+            .find(|(file, _)| *file == package_file)
+            .map(|(_, repository)| repository)
+    }
+
+    pub fn repositories(&self) -> impl Iterator<Item = &Repository> {
+        self.repositories.iter().map(|(_, repository)| repository)
+    }
+
+    pub fn into_repositories(self) -> impl Iterator<Item = Repository> {
+        self.repositories
+            .into_iter()
+            .map(|(_, repository)| repository)
+    }
+}
+
+fn load_worker(
+    state: &Mutex<LoadState>,
+    changed: &Condvar,
+    search_path: &GirSearchPath,
+    read: &(dyn Fn(&Path) -> Result<Repository, ParserError> + Sync),
+) {
+    let mut guard = state.lock().expect("loader state poisoned");
+    loop {
+        let Some(package_file) = guard.queue.pop_front() else {
+            if guard.in_flight == 0 {
+                return;
+            }
+            guard = changed.wait(guard).expect("loader state poisoned");
+            continue;
+        };
+        guard.in_flight += 1;
+        drop(guard);
+
+        let result = search_path
+            .locate(&package_file)
+            .and_then(|path| read(&path));
+
+        guard = state.lock().expect("loader state poisoned");
+        guard.in_flight -= 1;
+        match result {
+            Ok(repository) => {
+                for include in repository.namespace_includes() {
+                    guard.push(include.as_package_file());
+                }
+                guard.loaded.insert(package_file, repository);
+            }
+            // Loading goes on after a failure, so the files which fail, and
+            // the one reported, don't depend on the order they loaded in.
+            Err(error) => {
+                if guard
+                    .error
+                    .as_ref()
+                    .is_none_or(|(first, _)| package_file < *first)
+                {
+                    guard.error = Some((package_file, error));
+                }
+            }
+        }
+        changed.notify_all();
+    }
+}
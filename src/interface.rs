@@ -1,5 +1,3 @@
-use xmlserde_derives::XmlDeserialize;
-
 use crate::{
     attribute::Attribute,
     callable::Callable,
@@ -7,23 +5,29 @@ use crate::{
     class::Implements,
     constant::Constant,
     documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition},
+    extra::Extra,
     field::Field,
     function::{Function, FunctionInline},
+    interner::Atom,
     method::{Method, MethodInline},
     prelude::*,
     property::Property,
     signal::Signal,
+    span::NodeSpan,
+    tree::{FromNode, FromTag, Node},
     version::Version,
     virtual_method::VirtualMethod,
+    writer::{Element, ToXml},
     Record, Stability, Union,
 };
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, XmlDeserialize)]
-#[xmlserde(root = b"prerequisite")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Prerequisite {
-    #[xmlserde(name = b"name", ty = "attr")]
-    name: String,
+    name: Atom,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Prerequisite {
@@ -32,91 +36,69 @@ impl Prerequisite {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 // FIXME: The `Type` / `AnyType` fields are quite huge and some boxing would
-// probably be useful here but `xmlserde` does not seem to support that.
+// probably be useful here.
 #[allow(clippy::large_enum_variant)]
 pub enum InterfaceField {
-    #[xmlserde(name = b"field")]
     Field(Field),
-    #[xmlserde(name = b"union")]
     Union(Union),
-    #[xmlserde(name = b"record")]
     Record(Record),
-    #[xmlserde(name = b"callback")]
     Callback(Callback),
 }
 
-#[derive(Clone, Debug, XmlDeserialize)]
-#[xmlserde(root = b"interface")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Interface {
-    #[xmlserde(name = b"name", ty = "attr")]
     name: String,
-    #[xmlserde(name = b"c:symbol-prefix", ty = "attr")]
     symbol_prefix: Option<String>,
-    #[xmlserde(name = b"c:type", ty = "attr")]
     c_type: Option<String>,
-    #[xmlserde(name = b"glib:type-name", ty = "attr")]
     g_type_name: String,
-    #[xmlserde(name = b"glib:get-type", ty = "attr")]
     g_get_type: String,
-    #[xmlserde(name = b"glib:type-struct", ty = "attr")]
     g_type_struct: Option<String>,
     // Common attributes
-    #[xmlserde(name = b"introspectable", ty = "attr")]
     introspectable: Option<bool>,
-    #[xmlserde(name = b"deprecated", ty = "attr")]
     deprecated: Option<bool>,
-    #[xmlserde(name = b"version", ty = "attr")]
     version: Option<Version>,
-    #[xmlserde(name = b"deprecated-version", ty = "attr")]
     deprecated_version: Option<Version>,
-    #[xmlserde(name = b"stability", ty = "attr")]
     stability: Option<Stability>,
     // Documentation
-    #[xmlserde(name = b"doc", ty = "child")]
     doc: Option<Documentation>,
-    #[xmlserde(name = b"doc-deprecated", ty = "child")]
     doc_deprecated: Option<DocDeprecated>,
-    #[xmlserde(name = b"doc-stability", ty = "child")]
     doc_stability: Option<DocStability>,
-    #[xmlserde(name = b"doc-version", ty = "child")]
     doc_version: Option<DocVersion>,
-    #[xmlserde(name = b"source-position", ty = "child")]
     source_position: Option<SourcePosition>,
     // Attributes: 0 or more
-    #[xmlserde(name = b"attribute", ty = "child")]
     attributes: Vec<Attribute>,
 
-    #[xmlserde(name = b"prerequisite", ty = "child")]
     prerequisites: Vec<Prerequisite>,
 
-    #[xmlserde(name = b"implements", ty = "child")]
     implements: Vec<Implements>,
 
-    #[xmlserde(name = b"function-inline", ty = "child")]
+    #[cfg_attr(feature = "serde", serde(rename = "inlined_functions"))]
     inline_functions: Vec<FunctionInline>,
 
-    #[xmlserde(name = b"inline-methods", ty = "child")]
+    #[cfg_attr(feature = "serde", serde(rename = "inlined_methods"))]
     inline_methods: Vec<MethodInline>,
 
-    #[xmlserde(name = b"virtual-method", ty = "child")]
     virtual_methods: Vec<VirtualMethod>,
 
-    #[xmlserde(ty = "untag")]
     fields: Vec<InterfaceField>,
-    #[xmlserde(ty = "untag")]
     callables: Vec<Callable>,
 
-    #[xmlserde(name = b"property", ty = "child")]
     properties: Vec<Property>,
 
-    #[xmlserde(name = b"glib:signal", ty = "child")]
     signals: Vec<Signal>,
 
-    #[xmlserde(name = b"constant", ty = "child")]
     constants: Vec<Constant>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Interface {
@@ -209,3 +191,156 @@ impl Interface {
 impl_documentable!(Interface);
 impl_attributable!(Interface);
 impl_info!(Interface);
+
+impl FromTag for InterfaceField {
+    fn from_tag(node: Node) -> Option<Self> {
+        match node.tag() {
+            "field" => Some(Self::Field(Field::from_node(node))),
+            "union" => Some(Self::Union(Union::from_node(node))),
+            "record" => Some(Self::Record(Record::from_node(node))),
+            "callback" => Some(Self::Callback(Callback::from_node(node))),
+            _ => None,
+        }
+    }
+}
+
+impl ToXml for InterfaceField {
+    fn to_xml(&self, _tag: &str) -> Element {
+        match self {
+            Self::Field(f) => f.to_xml("field"),
+            Self::Union(u) => u.to_xml("union"),
+            Self::Record(r) => r.to_xml("record"),
+            Self::Callback(c) => c.to_xml("callback"),
+        }
+    }
+}
+
+impl_extensible!(Prerequisite);
+impl_spanned!(Prerequisite);
+impl_builder!(Prerequisite => PrerequisiteBuilder {
+    new {
+        name: Atom => name_mut;
+    }
+    optional {}
+    list {}
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for Prerequisite {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Prerequisite {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag).attr("name", &self.name).extra(self)
+    }
+}
+
+impl_extensible!(Interface);
+impl_spanned!(Interface);
+impl_builder!(Interface => InterfaceBuilder {
+    new {
+        name: String => name_mut;
+        g_type_name: String => g_type_name_mut;
+        g_get_type: String => g_get_type_mut;
+    }
+    optional {
+        symbol_prefix: String => symbol_prefix, symbol_prefix_mut;
+        c_type: String => c_type, c_type_mut;
+        g_type_struct: String => g_type_struct, g_type_struct_mut;
+        introspectable: bool => introspectable, introspectable_mut;
+        deprecated: bool => deprecated, deprecated_mut;
+        version: Version => version, version_mut;
+        deprecated_version: Version => deprecated_version, deprecated_version_mut;
+        stability: Stability => stability, stability_mut;
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+    }
+    list {
+        attributes: Attribute => attribute, attributes_mut;
+        prerequisites: Prerequisite => prerequisite, prerequisites_mut;
+        implements: Implements => implements, implements_mut;
+        inline_functions: FunctionInline => inlined_function, inlined_functions_mut;
+        inline_methods: MethodInline => inlined_method, inlined_methods_mut;
+        virtual_methods: VirtualMethod => virtual_method, virtual_methods_mut;
+        fields: InterfaceField => field, fields_mut;
+        callables: Callable => callable, callables_mut;
+        properties: Property => property, properties_mut;
+        signals: Signal => signal, signals_mut;
+        constants: Constant => constant, constants_mut;
+    }
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for Interface {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            symbol_prefix: node.opt_attr("c:symbol-prefix"),
+            c_type: node.opt_attr("c:type"),
+            g_type_name: node.attr("glib:type-name"),
+            g_get_type: node.attr("glib:get-type"),
+            g_type_struct: node.opt_attr("glib:type-struct"),
+            introspectable: node.opt_attr("introspectable"),
+            deprecated: node.opt_attr("deprecated"),
+            version: node.opt_attr("version"),
+            deprecated_version: node.opt_attr("deprecated-version"),
+            stability: node.opt_attr("stability"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            attributes: node.children("attribute"),
+            prerequisites: node.children("prerequisite"),
+            implements: node.children("implements"),
+            inline_functions: node.children("function-inline"),
+            inline_methods: node.children("method-inline"),
+            virtual_methods: node.children("virtual-method"),
+            fields: node.all_of(),
+            callables: node.all_of(),
+            properties: node.children("property"),
+            signals: node.children("glib:signal"),
+            constants: node.children("constant"),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Interface {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .attr("name", &self.name)
+            .opt_attr("c:symbol-prefix", &self.symbol_prefix)
+            .opt_attr("c:type", &self.c_type)
+            .attr("glib:type-name", &self.g_type_name)
+            .attr("glib:get-type", &self.g_get_type)
+            .opt_attr("glib:type-struct", &self.g_type_struct)
+            .info(self)
+            .documentation(self)
+            .attributes(self)
+            .children("prerequisite", &self.prerequisites)
+            .children("implements", &self.implements)
+            .untagged(&self.fields)
+            .untagged(&self.callables)
+            .children("function-inline", &self.inline_functions)
+            .children("method-inline", &self.inline_methods)
+            .children("virtual-method", &self.virtual_methods)
+            .children("property", &self.properties)
+            .children("glib:signal", &self.signals)
+            .children("constant", &self.constants)
+            .extra(self)
+    }
+}
@@ -0,0 +1,217 @@
+use std::{
+    borrow::Borrow,
+    cell::RefCell,
+    collections::HashSet,
+    hash::{BuildHasher, BuildHasherDefault, DefaultHasher},
+    sync::{Arc, Mutex},
+};
+
+use xmlserde::XmlValue;
+
+/// An immutable string shared between the nodes holding the same value, such
+/// as type names and C types.
+///
+/// Atoms created by the same [`Interner`] share their allocation, which makes
+/// comparing equal atoms a pointer comparison.
+#[derive(Clone, PartialOrd, Ord)]
+pub struct Atom(Arc<str>);
+
+impl Atom {
+    /// Create an atom, deduplicated by the interner the current thread is
+    /// parsing with, if any.
+    pub(crate) fn new(value: &str) -> Self {
+        CURRENT.with_borrow_mut(|scope| match scope {
+            Some(scope) => scope.intern(value),
+            None => Self(value.into()),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether both atoms share the same allocation, which is the case for
+    /// equal atoms created by the same interner.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl PartialEq for Atom {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || self.0 == other.0
+    }
+}
+
+impl Eq for Atom {}
+
+impl std::hash::Hash for Atom {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl PartialEq<str> for Atom {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl std::ops::Deref for Atom {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Atom {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Atom {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Atom {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<String> for Atom {
+    fn from(value: String) -> Self {
+        Self::new(&value)
+    }
+}
+
+impl std::fmt::Debug for Atom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::fmt::Display for Atom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl XmlValue for Atom {
+    fn serialize(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn deserialize(s: &str) -> Result<Self, String> {
+        Ok(Self::new(s))
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Scope>> = const { RefCell::new(None) };
+}
+
+/// The interner a thread is parsing with, along with the strings it already
+/// got from it, so the strings repeated within a document don't go through
+/// its locks.
+struct Scope {
+    interner: Interner,
+    seen: HashSet<Arc<str>>,
+}
+
+impl Scope {
+    fn intern(&mut self, value: &str) -> Atom {
+        if let Some(string) = self.seen.get(value) {
+            return Atom(string.clone());
+        }
+        let atom = self.interner.intern(value);
+        self.seen.insert(atom.0.clone());
+        atom
+    }
+}
+
+/// The number of independently locked sets the strings are spread over, so
+/// the threads parsing in parallel rarely wait on each other.
+const SHARDS: usize = 16;
+
+/// Picks the shard of a string. It's deterministic so all the clones of an
+/// interner agree.
+type ShardHasher = BuildHasherDefault<DefaultHasher>;
+
+/// A set of strings shared by the repositories parsed with it, so names
+/// repeated across files, like `utf8` or `GObject.Object`, are only stored
+/// once.
+///
+/// Interners are cheap to clone and can be shared between threads.
+#[derive(Clone, Default)]
+pub struct Interner {
+    shards: Arc<[Mutex<HashSet<Arc<str>>>; SHARDS]>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&self, value: &str) -> Atom {
+        let shard = ShardHasher::default().hash_one(value) as usize % SHARDS;
+        let mut strings = self.shards[shard].lock().expect("interner poisoned");
+        if let Some(string) = strings.get(value) {
+            return Atom(string.clone());
+        }
+        let string = Arc::<str>::from(value);
+        strings.insert(string.clone());
+        Atom(string)
+    }
+
+    /// The number of distinct strings.
+    pub fn len(&self) -> usize {
+        self.shards
+            .iter()
+            .map(|strings| strings.lock().expect("interner poisoned").len())
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Run `f` with the atoms created on this thread going through the
+    /// interner.
+    pub(crate) fn scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        struct Restore(Option<Scope>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT.set(self.0.take());
+            }
+        }
+
+        let scope = Scope {
+            interner: self.clone(),
+            seen: HashSet::new(),
+        };
+        let _restore = Restore(CURRENT.replace(Some(scope)));
+        f()
+    }
+}
+
+impl PartialEq for Interner {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shards, &other.shards)
+    }
+}
+
+impl Eq for Interner {}
+
+impl std::fmt::Debug for Interner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Interner")
+            .field("len", &self.len())
+            .finish()
+    }
+}
@@ -1,10 +1,18 @@
 #![doc = include_str!("../README.md")]
 use xmlserde::xml_serde_enum;
 
+/// Why a GIR file couldn't be loaded. New kinds of failures may be added
+/// without a breaking change.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ParserError {
     IO(std::io::Error),
+    /// No longer returned, parse failures are reported through
+    /// [`ParserError::Parse`] along with their location.
+    #[deprecated(note = "parse failures are reported as `ParserError::Parse`")]
     Xml(String),
+    Parse(ParseError),
+    IncludeCycle(String),
 }
 
 impl From<std::io::Error> for ParserError {
@@ -13,12 +21,23 @@ impl From<std::io::Error> for ParserError {
     }
 }
 
+impl From<ParseError> for ParserError {
+    fn from(value: ParseError) -> Self {
+        Self::Parse(value)
+    }
+}
+
 impl std::error::Error for ParserError {}
 impl std::fmt::Display for ParserError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::IO(e) => f.write_fmt(format_args!("I/O operation failed {e}")),
+            #[allow(deprecated)]
             Self::Xml(e) => f.write_fmt(format_args!("Failed to parse xml file: {e}")),
+            Self::Parse(e) => f.write_fmt(format_args!("Failed to parse xml file: {e}")),
+            Self::IncludeCycle(file) => {
+                f.write_fmt(format_args!("{file} is part of an include cycle"))
+            }
         }
     }
 }
@@ -94,6 +113,8 @@ xml_serde_enum! {
 
 #[macro_use]
 mod traits;
+#[macro_use]
+mod builder;
 pub mod prelude {
     pub use xmlserde::XmlValue;
 
@@ -101,63 +122,117 @@ pub mod prelude {
 }
 
 mod alias;
-pub use alias::Alias;
+pub use alias::{Alias, AliasBuilder};
 mod array;
-pub use array::Array;
+pub use array::{Array, ArrayBuilder};
 mod attribute;
-pub use attribute::Attribute;
+pub use attribute::{Attribute, AttributeBuilder};
 mod bitfield;
-pub use bitfield::BitField;
+pub use bitfield::{BitField, BitFieldBuilder};
+pub mod borrowed;
 mod boxed;
-pub use boxed::Boxed;
+pub use boxed::{Boxed, BoxedBuilder};
+#[cfg(feature = "cache")]
+mod cache;
 mod callable;
 pub use callable::Callable;
 mod callback;
-pub use callback::Callback;
+pub use callback::{Callback, CallbackBuilder};
+mod checker;
 mod class;
-pub use class::{Class, ClassField, Implements};
+pub use class::{Class, ClassBuilder, ClassField, Implements, ImplementsBuilder};
+mod compression;
 mod constant;
-pub use constant::Constant;
+pub use constant::{Constant, ConstantBuilder};
+pub mod cst;
+pub mod diff;
+mod doc_section;
+pub use doc_section::{DocSection, DocSectionBuilder};
 mod documentation;
-pub use documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition};
+pub use documentation::{
+    DocDeprecated, DocDeprecatedBuilder, DocStability, DocStabilityBuilder, DocVersion,
+    DocVersionBuilder, Documentation, DocumentationBuilder, SourcePosition, SourcePositionBuilder,
+};
 mod enums;
-pub use enums::Enumeration;
+pub use enums::{Enumeration, EnumerationBuilder};
+mod environment;
+pub use environment::{GirEnvironment, ResolveError, ResolvedType};
+mod error;
+pub use error::{ParseError, ParseWarning};
+mod extra;
 mod field;
-pub use field::{Field, FieldType};
+pub use field::{Field, FieldBuilder, FieldType};
 mod function;
-pub use function::{Function, FunctionInline};
+pub use function::{Function, FunctionBuilder, FunctionInline, FunctionInlineBuilder};
 mod function_macro;
-pub use function_macro::FunctionMacro;
+pub use function_macro::{FunctionMacro, FunctionMacroBuilder};
+mod hierarchy;
+mod include_graph;
+pub use include_graph::IncludeGraph;
 mod interface;
-pub use interface::{Interface, InterfaceField, Prerequisite};
+pub use interface::{
+    Interface, InterfaceBuilder, InterfaceField, Prerequisite, PrerequisiteBuilder,
+};
+mod interner;
+pub use interner::{Atom, Interner};
 mod member;
-pub use member::Member;
+pub use member::{Member, MemberBuilder};
+mod metadata;
+pub use metadata::Metadata;
 mod method;
-pub use method::{Method, MethodInline};
+pub use method::{Method, MethodBuilder, MethodInline, MethodInlineBuilder};
 mod namespace;
-pub use namespace::Namespace;
+pub use namespace::{Namespace, NamespaceBuilder};
+mod options;
+pub use options::ParseOptions;
 mod parameter;
 pub use parameter::{
-    AnyParameter, Direction, InstanceParameter, Parameter, ParameterType, Parameters,
+    AnyParameter, Direction, InstanceParameter, InstanceParameterBuilder, Parameter,
+    ParameterBuilder, ParameterType, Parameters, ParametersBuilder,
 };
 mod property;
-pub use property::Property;
+pub use property::{Property, PropertyBuilder};
 mod record;
-pub use record::{Record, RecordField};
+pub use record::{Record, RecordBuilder, RecordField};
 mod repository;
-pub use repository::{DocFormat, HeaderInclude, NamespaceInclude, Package, Repository};
+pub use repository::{
+    DocFormat, HeaderInclude, HeaderIncludeBuilder, NamespaceInclude, NamespaceIncludeBuilder,
+    Package, PackageBuilder, Repository, RepositoryBuilder,
+};
+mod repository_header;
+pub use repository_header::RepositoryHeader;
 mod return_value;
-pub use return_value::ReturnValue;
+pub use return_value::{ReturnValue, ReturnValueBuilder};
+mod schema;
+mod search_path;
+pub use search_path::{GirLocation, GirSearchPath};
+#[cfg(feature = "serde")]
+mod serialize;
 mod signal;
-pub use signal::Signal;
+pub use signal::{Signal, SignalBuilder};
+mod span;
+pub use span::Span;
+mod stream;
+pub use stream::{
+    ClassHeader, GirEvent, GirStream, InterfaceHeader, NamespaceHeader, RecordHeader,
+    RepositoryAttributes, UnionHeader,
+};
+mod symbol_index;
+pub use symbol_index::{Symbol, SymbolContainer, SymbolEntry, SymbolIndex};
+mod tree;
 mod r#type;
-pub use r#type::{AnyType, Type};
+pub use r#type::{AnyType, Type, TypeBuilder};
 mod union;
-pub use union::{Union, UnionField};
+pub use union::{Union, UnionBuilder, UnionField};
+mod validate;
+pub use validate::Violation;
 mod version;
 pub use version::Version;
+mod versions;
+pub use versions::{AnySymbol, VersionConflict, VersionIndex, VersionedSymbol};
 mod virtual_method;
-pub use virtual_method::VirtualMethod;
+pub use virtual_method::{VirtualMethod, VirtualMethodBuilder};
+mod writer;
 
 #[cfg(test)]
 mod tests {
@@ -304,6 +379,2012 @@ mod tests {
         }
     }
 
+    #[test]
+    fn serialize_all_gir_files() {
+        let paths = std::fs::read_dir("./gir-files").unwrap();
+
+        for path in paths {
+            let path = path.unwrap().path();
+            let repository = Repository::from_path(&path).unwrap();
+            let xml = repository.to_xml();
+            let reparsed = Repository::from_str(&xml).unwrap();
+            assert_eq!(
+                repository,
+                reparsed,
+                "{} did not round-trip",
+                path.display()
+            );
+        }
+    }
+
+    #[test]
+    fn serialize_escapes_values() {
+        let content = r#"
+<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <constant name="CHARS" value="&amp;&lt;&quot;" c:type="TEST_CHARS">
+      <doc xml:space="preserve">Compares `a &lt; b` &amp;&amp; "c"</doc>
+      <type name="utf8" c:type="gchar*"/>
+    </constant>
+  </namespace>
+</repository>"#;
+        let repo = Repository::from_str(content).unwrap();
+        let constant = &repo.namespace().constants()[0];
+        assert_eq!(constant.value(), "&<\"");
+        assert_eq!(constant.doc().unwrap().text(), "Compares `a < b` && \"c\"");
+
+        let reparsed = Repository::from_str(&repo.to_xml()).unwrap();
+        assert_eq!(repo, reparsed);
+    }
+
+    #[test]
+    fn serialize_type_parameters_in_order() {
+        use crate::AnyType;
+
+        let content = r#"
+<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <constant name="TABLE" value="0" c:type="TEST_TABLE">
+      <type name="GLib.HashTable" c:type="GHashTable*">
+        <array c:type="gchar**">
+          <type name="utf8"/>
+        </array>
+        <type name="gint"/>
+      </type>
+    </constant>
+  </namespace>
+</repository>"#;
+        let repo = Repository::from_str(content).unwrap();
+        let AnyType::Type(ty) = repo.namespace().constants()[0].ty() else {
+            panic!("expected a type");
+        };
+        assert!(ty.parameters()[0].is_array());
+        assert_eq!(ty.parameters()[1].as_type().name(), Some("gint"));
+
+        let xml = repo.to_xml();
+        assert!(xml.find("<array ").unwrap() < xml.find(r#"<type name="gint""#).unwrap());
+        let reparsed = Repository::from_str(&xml).unwrap();
+        assert_eq!(repo, reparsed);
+    }
+
+    #[test]
+    fn resolve_types_across_repositories() {
+        use crate::{GirEnvironment, ResolveError, ResolvedType};
+
+        let repositories =
+            Repository::from_path_follow_namespaces("GstVideo-1.0.gir", "./gir-files").unwrap();
+        let env = GirEnvironment::try_from(repositories).unwrap();
+
+        let resolved = env.resolve_name("VideoInfo", "GstVideo").unwrap();
+        assert!(matches!(resolved, ResolvedType::Record(r) if r.c_type() == Some("GstVideoInfo")));
+        let resolved = env.resolve_name("VideoFlags", "GstVideo").unwrap();
+        assert!(matches!(resolved, ResolvedType::BitField(_)));
+        let resolved = env.resolve_name("Gst.Element", "GstVideo").unwrap();
+        assert!(matches!(resolved, ResolvedType::Class(c) if c.g_type_name() == "GstElement"));
+        let resolved = env.resolve_name("GObject.Object", "Gst").unwrap();
+        assert_eq!(resolved.name(), Some("Object"));
+        assert_eq!(
+            env.resolve_name("gint", "GstVideo"),
+            Ok(ResolvedType::Fundamental("gint"))
+        );
+        assert_eq!(
+            env.resolve_name("time_t", "GstVideo"),
+            Ok(ResolvedType::Fundamental("time_t"))
+        );
+
+        let class = env
+            .namespace("GstVideo")
+            .unwrap()
+            .classes()
+            .iter()
+            .find(|c| c.name() == "VideoFilter")
+            .unwrap();
+        let parent = env.resolve_name(class.parent().unwrap(), "GstVideo");
+        assert!(matches!(parent, Ok(ResolvedType::Class(c)) if c.name() == "BaseTransform"));
+
+        assert_eq!(
+            env.resolve_name("Element", "GstVideo"),
+            Err(ResolveError::UnknownType {
+                namespace: "GstVideo".to_owned(),
+                name: "Element".to_owned(),
+            })
+        );
+        assert_eq!(
+            env.resolve_name("Gtk.Widget", "GstVideo"),
+            Err(ResolveError::UnknownNamespace("Gtk".to_owned()))
+        );
+    }
+
+    #[test]
+    fn class_hierarchy() {
+        use crate::GirEnvironment;
+
+        let repositories =
+            Repository::from_path_follow_namespaces("GstVideo-1.0.gir", "./gir-files").unwrap();
+        let env = GirEnvironment::try_from(repositories).unwrap();
+
+        let ancestors = env
+            .ancestors("Pipeline", "Gst")
+            .unwrap()
+            .into_iter()
+            .map(|(ns, class)| format!("{}.{}", ns.name(), class.name()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ancestors,
+            vec![
+                "Gst.Bin",
+                "Gst.Element",
+                "Gst.Object",
+                "GObject.InitiallyUnowned",
+                "GObject.Object"
+            ]
+        );
+        assert!(env
+            .is_subclass_of("VideoFilter", "GstVideo", "GObject.Object")
+            .unwrap());
+        assert!(!env
+            .is_subclass_of("VideoFilter", "GstVideo", "Gst.Bin")
+            .unwrap());
+        // The ancestor is resolved in the namespace of the class.
+        assert!(env.is_subclass_of("Pipeline", "Gst", "Element").unwrap());
+        assert!(!env.is_subclass_of("Element", "Gst", "Pipeline").unwrap());
+        assert!(env.is_subclass_of("Pipeline", "Gst", "Missing").is_err());
+
+        let interfaces = env.all_implemented_interfaces("Gst.Pipeline", "").unwrap();
+        assert_eq!(interfaces.len(), 1);
+        assert_eq!(interfaces[0].1.name(), "ChildProxy");
+
+        let descendants = env.descendants("Element", "Gst").unwrap();
+        assert!(descendants
+            .iter()
+            .any(|(ns, class)| ns.name() == "Gst" && class.name() == "Pipeline"));
+        assert!(descendants
+            .iter()
+            .any(|(ns, class)| ns.name() == "GstVideo" && class.name() == "VideoFilter"));
+    }
+
+    #[test]
+    fn class_hierarchy_errors() {
+        use crate::{GirEnvironment, ResolveError};
+
+        let content = r#"
+<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <class name="A" parent="B" glib:type-name="TestA" glib:get-type="test_a_get_type"/>
+    <class name="B" parent="A" glib:type-name="TestB" glib:get-type="test_b_get_type"/>
+    <class name="C" parent="Missing" glib:type-name="TestC" glib:get-type="test_c_get_type">
+      <implements name="Derived"/>
+    </class>
+    <class name="D" glib:type-name="TestD" glib:get-type="test_d_get_type"/>
+    <class name="E" parent="D" glib:type-name="TestE" glib:get-type="test_e_get_type"/>
+    <interface name="Base" glib:type-name="TestBase" glib:get-type="test_base_get_type">
+      <prerequisite name="Derived"/>
+    </interface>
+    <interface name="Derived" glib:type-name="TestDerived" glib:get-type="test_derived_get_type">
+      <prerequisite name="Base"/>
+    </interface>
+  </namespace>
+</repository>"#;
+        let env = GirEnvironment::new([Repository::from_str(content).unwrap()]).unwrap();
+        assert_eq!(
+            env.ancestors("A", "Test"),
+            Err(ResolveError::InheritanceCycle("Test.A".to_owned()))
+        );
+        assert_eq!(
+            env.ancestors("C", "Test"),
+            Err(ResolveError::UnknownType {
+                namespace: "Test".to_owned(),
+                name: "Missing".to_owned(),
+            })
+        );
+        assert_eq!(
+            env.all_prerequisites("Base", "Test"),
+            Err(ResolveError::InheritanceCycle("Test.Base".to_owned()))
+        );
+        assert_eq!(
+            env.ancestors("Base", "Test"),
+            Err(ResolveError::NotAClass("Test.Base".to_owned()))
+        );
+        // The missing parent of `C` only leaves `C` out of the descendants.
+        let descendants = env.descendants("D", "Test").unwrap();
+        assert_eq!(descendants.len(), 1);
+        assert_eq!(descendants[0].1.name(), "E");
+        assert!(env.descendants("C", "Test").unwrap().is_empty());
+        assert_eq!(
+            env.descendants("A", "Test"),
+            Err(ResolveError::InheritanceCycle("Test.A".to_owned()))
+        );
+    }
+
+    #[test]
+    fn symbol_index() {
+        use crate::{GirEnvironment, Symbol, SymbolContainer, SymbolIndex};
+
+        let repositories =
+            Repository::from_path_follow_namespaces("GstVideo-1.0.gir", "./gir-files").unwrap();
+        let env = GirEnvironment::try_from(repositories).unwrap();
+        let index = env.symbol_index();
+
+        let entry = index.by_c_identifier("gst_element_link").unwrap();
+        assert_eq!(entry.namespace().name(), "Gst");
+        assert!(matches!(entry.symbol(), Symbol::Method(m) if m.name() == "link"));
+        assert!(matches!(entry.container(), SymbolContainer::Class(c) if c.name() == "Element"));
+        assert_eq!(index.by_name("Gst.Element.link"), Some(entry));
+
+        let entry = index.by_g_type_name("GstElement").unwrap();
+        assert!(matches!(entry.symbol(), Symbol::Class(c) if c.name() == "Element"));
+        assert!(matches!(entry.container(), SymbolContainer::Namespace(ns) if ns.name() == "Gst"));
+        assert_eq!(index.by_g_get_type("gst_element_get_type"), Some(entry));
+        assert_eq!(index.by_c_type("GstElement"), Some(entry));
+        assert_eq!(index.by_name("Gst.Element"), Some(entry));
+
+        let entry = index.by_c_identifier("gst_video_info_init").unwrap();
+        assert!(matches!(entry.symbol(), Symbol::Function(f) if f.name() == "init"));
+        assert!(
+            matches!(entry.container(), SymbolContainer::Record(r) if r.name() == Some("VideoInfo"))
+        );
+
+        let entry = index.by_c_identifier("GST_VIDEO_FORMAT_I420").unwrap();
+        assert!(matches!(entry.symbol(), Symbol::Member(m) if m.name() == "i420"));
+        assert!(
+            matches!(entry.container(), SymbolContainer::Enumeration(e) if e.name() == "VideoFormat")
+        );
+
+        assert!(index.by_c_identifier("gtk_widget_show").is_none());
+
+        let repository = Repository::from_str(
+            r#"<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <record c:type="TestAnonymous">
+      <method name="free" c:identifier="test_anonymous_free">
+        <return-value><type name="none"/></return-value>
+      </method>
+    </record>
+  </namespace>
+</repository>"#,
+        )
+        .unwrap();
+        let index = SymbolIndex::new([&repository]);
+        assert!(index.by_c_type("TestAnonymous").is_some());
+        let entry = index.by_c_identifier("test_anonymous_free").unwrap();
+        assert!(matches!(entry.symbol(), Symbol::Method(m) if m.name() == "free"));
+        assert!(matches!(entry.container(), SymbolContainer::Record(r) if r.name().is_none()));
+        assert!(index.by_name("Test..free").is_none());
+    }
+
+    #[test]
+    fn parse_errors() {
+        use crate::{ParseError, ParserError};
+
+        fn parse_error(content: &str) -> ParseError {
+            match Repository::from_str(content) {
+                Err(ParserError::Parse(e)) => e,
+                other => panic!("expected a parse error, got {other:?}"),
+            }
+        }
+
+        let content = r#"<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <class name="Widget" glib:type-name="TestWidget" glib:get-type="test_widget_get_type">
+      <method name="show" c:identifier="test_widget_show">
+        <return-value><type name="none"/></return-value>
+        <parameters>
+          <instance-parameter name="self"><type name="Widget"/></instance-parameter>
+          <parameter name="a"><type name="gint"/></parameter>
+          <parameter name="b" colour="red"><type name="gint"/></parameter>
+        </parameters>
+      </method>
+    </class>
+  </namespace>
+</repository>"#;
+        let error = parse_error(content);
+        assert_eq!(error.message(), "unknown attribute `colour`");
+        assert_eq!(error.path(), None);
+        assert_eq!((error.line(), error.column()), (9, 31));
+        assert_eq!(&content[error.offset()..][..6], "colour");
+        assert_eq!(
+            error.element_path(),
+            "namespace[@name=Test]/class[@name=Widget]/method[@name=show]/parameters/parameter[2]"
+        );
+        assert_eq!(
+            error.diagnostic(),
+            r#"error: unknown attribute `colour`
+ --> 9:31
+  |
+9 |           <parameter name="b" colour="red"><type name="gint"/></parameter>
+  |                               ^
+  = in namespace[@name=Test]/class[@name=Widget]/method[@name=show]/parameters/parameter[2]
+"#
+        );
+
+        let error = parse_error(
+            r#"<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <constant name="MAX" c:type="TEST_MAX"><type name="gint"/></constant>
+  </namespace>
+</repository>"#,
+        );
+        assert_eq!(error.message(), "missing required attribute `value`");
+        assert_eq!((error.line(), error.column()), (3, 5));
+        assert_eq!(
+            error.element_path(),
+            "namespace[@name=Test]/constant[@name=MAX]"
+        );
+
+        let error = parse_error(
+            r#"<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <function name="run" c:identifier="test_run" throws="maybe">
+      <return-value><type name="none"/></return-value>
+    </function>
+  </namespace>
+</repository>"#,
+        );
+        assert!(error
+            .message()
+            .starts_with("invalid value `maybe` for attribute `throws`"));
+        assert_eq!((error.line(), error.column()), (3, 50));
+
+        let error = parse_error(
+            r#"<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <function name="run" c:identifier="test_run"/>
+  </namespace>
+</repository>"#,
+        );
+        assert_eq!(error.message(), "missing `return-value` element");
+        assert_eq!(
+            error.element_path(),
+            "namespace[@name=Test]/function[@name=run]"
+        );
+
+        let error = parse_error(
+            "<repository version=\"1.2\">\n  <namespace name=\"Test\" version=\"1.0\">",
+        );
+        assert_eq!(error.message(), "unexpected end of document");
+        assert_eq!(error.line(), 2);
+
+        let error = parse_error(
+            "<repository version=\"1.2\">\n  <namespace name=\"Test\" version=\"1.0\"/>\n</repository>\n<repository version=\"1.2\"/>",
+        );
+        assert_eq!(
+            error.message(),
+            "unexpected element after the document root"
+        );
+        assert_eq!((error.line(), error.column()), (4, 1));
+
+        let path = std::env::temp_dir().join("gir-parser-parse-errors.gir");
+        std::fs::write(
+            &path,
+            "<repository version=\"1.2\">\n  <package/>\n</repository>",
+        )
+        .unwrap();
+        let result = Repository::from_path(&path);
+        std::fs::remove_file(&path).unwrap();
+        let Err(ParserError::Parse(error)) = result else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(error.path(), Some(path.as_path()));
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "{}:2:3: missing required attribute `name` (in package)",
+                path.display()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_lenient() {
+        use crate::{ParseOptions, ParserError};
+
+        let content = r#"<repository version="1.2">
+  <namespace name="Test" version="1.0" future-attribute="1">
+    <class name="Widget" glib:type-name="TestWidget" glib:get-type="test_widget_get_type"
+           colour = 'red'>
+      <future-element name="Nested"><future-child/></future-element>
+      <method name="show" c:identifier="test_widget_show" introspectable="maybe">
+        <return-value><type name="none"/></return-value>
+      </method>
+    </class>
+  </namespace>
+</repository>"#;
+        assert!(matches!(
+            Repository::from_str(content),
+            Err(ParserError::Parse(_))
+        ));
+        let (repository, warnings) =
+            Repository::from_str_with_options(content, &ParseOptions::lenient()).unwrap();
+        let warnings = warnings
+            .iter()
+            .map(|w| (w.line(), w.message(), w.element_path()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            warnings,
+            [
+                (
+                    2,
+                    "unknown attribute `future-attribute`",
+                    "namespace[@name=Test]"
+                ),
+                (
+                    4,
+                    "unknown attribute `colour`",
+                    "namespace[@name=Test]/class[@name=Widget]"
+                ),
+                (
+                    5,
+                    "unknown element `future-element`",
+                    "namespace[@name=Test]/class[@name=Widget]/future-element[@name=Nested]"
+                ),
+                (
+                    6,
+                    "invalid value `maybe` for attribute `introspectable`: Cannot parse maybe into a boolean",
+                    "namespace[@name=Test]/class[@name=Widget]/method[@name=show]"
+                ),
+            ]
+        );
+        let expected = Repository::from_str(
+            r#"<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <class name="Widget" glib:type-name="TestWidget" glib:get-type="test_widget_get_type">
+      <method name="show" c:identifier="test_widget_show">
+        <return-value><type name="none"/></return-value>
+      </method>
+    </class>
+  </namespace>
+</repository>"#,
+        )
+        .unwrap();
+        let class = &repository.namespace().classes()[0];
+        assert!(class
+            .methods()
+            .eq(expected.namespace().classes()[0].methods()));
+
+        // Missing required attributes can't be skipped.
+        assert!(Repository::from_str_with_options(
+            r#"<repository version="1.2"><namespace name="Test"/></repository>"#,
+            &ParseOptions::lenient()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn preserve_extra_content() {
+        use crate::{AnyType, ClassField, FieldType, ParseOptions, ParserError};
+
+        let content = r#"<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <class name="Widget" glib:type-name="TestWidget" glib:get-type="test_widget_get_type" vala:symbol="widget">
+      <implements name="Buildable" gjs:hint="a &amp; b"/>
+      <gjs:hint kind="lazy"><gjs:detail>text</gjs:detail></gjs:hint>
+      <field name="data">
+        <type name="gpointer"/>
+        <vala:owned><type name="GObject.Object"/></vala:owned>
+      </field>
+      <field name="rows">
+        <array zero-terminated="0"><array name="GLib.Array"><type name="gint"/></array></array>
+      </field>
+    </class>
+  </namespace>
+</repository>"#;
+        let (repository, _) =
+            Repository::from_str_with_options(content, &ParseOptions::lenient()).unwrap();
+        let class = &repository.namespace().classes()[0];
+        assert_eq!(
+            class.extra_attributes(),
+            [("vala:symbol".to_owned(), "widget".to_owned())]
+        );
+        assert_eq!(
+            class.extra_children(),
+            [r#"<gjs:hint kind="lazy"><gjs:detail>text</gjs:detail></gjs:hint>"#]
+        );
+        assert_eq!(
+            class.implements()[0].extra_attributes(),
+            [("gjs:hint".to_owned(), "a & b".to_owned())]
+        );
+        // Unknown elements are kept whole, their content isn't looked at.
+        let ClassField::Field(field) = &class.fields()[0] else {
+            panic!("expected a field");
+        };
+        assert_eq!(
+            field.extra_children(),
+            [r#"<vala:owned><type name="GObject.Object"/></vala:owned>"#]
+        );
+        let FieldType::Type(ty) = field.ty() else {
+            panic!("expected a type field");
+        };
+        assert_eq!(ty.name(), Some("gpointer"));
+        let ClassField::Field(field) = &class.fields()[1] else {
+            panic!("expected a field");
+        };
+        let FieldType::Array(array) = field.ty() else {
+            panic!("expected an array field");
+        };
+        // The element type of nested arrays is flattened by `ty()`.
+        assert_eq!(array.ty().name(), Some("gint"));
+        let AnyType::Array(array) = array.element() else {
+            panic!("expected an array of arrays");
+        };
+        assert_eq!(array.name(), Some("GLib.Array"));
+        assert_eq!(array.ty().name(), Some("gint"));
+
+        let xml = repository.to_xml();
+        assert!(xml.contains(r#"vala:symbol="widget""#));
+        // Unknown elements are indented like the known ones around them.
+        assert!(xml.contains(
+            r#"
+      <gjs:hint kind="lazy"><gjs:detail>text</gjs:detail></gjs:hint>
+    </class>"#
+        ));
+        let (reparsed, _) =
+            Repository::from_str_with_options(&xml, &ParseOptions::lenient()).unwrap();
+        assert_eq!(reparsed, repository);
+
+        // Unknown elements are kept in strict mode too, unlike attributes.
+        let strict = Repository::from_str(
+            &content
+                .replace(r#" vala:symbol="widget""#, "")
+                .replace(r#" gjs:hint="a &amp; b""#, ""),
+        )
+        .unwrap();
+        assert_eq!(
+            strict.namespace().classes()[0].extra_children(),
+            class.extra_children()
+        );
+        let strict = Repository::from_str(content);
+        let Err(ParserError::Parse(error)) = strict else {
+            panic!("expected a parse error, got {strict:?}");
+        };
+        assert_eq!(error.message(), "unknown attribute `vala:symbol`");
+        assert_eq!(
+            error.element_path(),
+            "namespace[@name=Test]/class[@name=Widget]"
+        );
+    }
+
+    #[test]
+    fn doc_sections() {
+        let repo = parse_gir("GstVideo-1.0");
+        let namespace = repo.namespace();
+        assert!(namespace.extra_children().is_empty());
+        let sections = namespace.doc_sections();
+        assert_eq!(sections[0].name(), "gstvideo");
+        let doc = sections[0].doc().unwrap();
+        assert!(doc
+            .text()
+            .starts_with("This library contains some helper functions"));
+        let sections_count = repo.to_xml().matches("<docsection ").count();
+        assert_eq!(sections_count, sections.len());
+    }
+
+    #[test]
+    fn gir_search_path() {
+        use crate::GirSearchPath;
+
+        let search_path = GirSearchPath::from_env(
+            ["/opt/project/gir", ""],
+            Some("/opt/gi:/opt/project/gir".into()),
+            Some("/opt/data".into()),
+            Some("/home/user".into()),
+            Some("/opt/share:/usr/share".into()),
+        );
+        assert_eq!(
+            search_path.dirs(),
+            [
+                "/opt/project/gir",
+                "/opt/gi",
+                "/opt/data/gir-1.0",
+                "/opt/share/gir-1.0",
+                "/usr/share/gir-1.0",
+            ]
+            .map(PathBuf::from)
+        );
+        let search_path = GirSearchPath::from_env(
+            Vec::<PathBuf>::new(),
+            None,
+            Some("".into()),
+            Some("/home/user".into()),
+            Some("".into()),
+        );
+        assert_eq!(
+            search_path.dirs(),
+            [
+                "/home/user/.local/share/gir-1.0",
+                "/usr/local/share/gir-1.0",
+                "/usr/share/gir-1.0"
+            ]
+            .map(PathBuf::from)
+        );
+
+        let root = std::env::temp_dir().join("gir-parser-search-path");
+        let (local, system) = (root.join("local"), root.join("system"));
+        for dir in [&local, &system] {
+            std::fs::create_dir_all(dir).unwrap();
+        }
+        std::fs::copy("./gir-files/GLib-2.0.gir", local.join("GLib-2.0.gir")).unwrap();
+        for file in ["GLib-2.0.gir", "GObject-2.0.gir"] {
+            std::fs::copy(format!("./gir-files/{file}"), system.join(file)).unwrap();
+        }
+        let search_path = GirSearchPath::new([&local, &system]);
+        let location = search_path.find("GLib-2.0.gir").unwrap();
+        assert_eq!(location.path(), local.join("GLib-2.0.gir"));
+        assert_eq!(location.shadowed(), [system.join("GLib-2.0.gir")]);
+        assert!(search_path.find("Gtk-4.0.gir").is_none());
+
+        let repositories =
+            Repository::from_search_path_follow_namespaces("GObject-2.0.gir", &search_path)
+                .unwrap();
+        let mut files = repositories.keys().collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(files, ["GLib-2.0.gir", "GObject-2.0.gir"]);
+        let gobject = &repositories["GObject-2.0.gir"];
+        let location = search_path
+            .resolve(&gobject.namespace_includes()[0])
+            .unwrap();
+        assert_eq!(location.path(), local.join("GLib-2.0.gir"));
+
+        assert!(
+            Repository::from_search_path_follow_namespaces("Gtk-4.0.gir", &search_path).is_err()
+        );
+
+        // Files including each other are loaded once.
+        for (name, include) in [("A", "B"), ("B", "A")] {
+            let content = format!(
+                r#"<repository version="1.2">
+  <include name="{include}" version="1.0"/>
+  <namespace name="{name}" version="1.0"/>
+</repository>"#
+            );
+            std::fs::write(local.join(format!("{name}-1.0.gir")), content).unwrap();
+        }
+        let repositories =
+            Repository::from_search_path_follow_namespaces("A-1.0.gir", &search_path).unwrap();
+        let mut files = repositories.keys().collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(files, ["A-1.0.gir", "B-1.0.gir"]);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn include_graph() {
+        use crate::{GirSearchPath, IncludeGraph, ParserError};
+
+        let search_path = GirSearchPath::new(["./gir-files"]);
+        let graph = IncludeGraph::load(["GstVideo-1.0.gir", "GLib-2.0.gir"], &search_path).unwrap();
+        let files = graph.iter().map(|(file, _)| file).collect::<Vec<_>>();
+        assert_eq!(
+            files,
+            [
+                "GLib-2.0.gir",
+                "GModule-2.0.gir",
+                "GObject-2.0.gir",
+                "Gst-1.0.gir",
+                "GstBase-1.0.gir",
+                "GstVideo-1.0.gir",
+            ]
+        );
+        assert_eq!(
+            graph.get("Gst-1.0.gir").map(|r| r.namespace().name()),
+            Some("Gst")
+        );
+        assert_eq!(
+            IncludeGraph::load(["GstVideo-1.0.gir"], &search_path).unwrap(),
+            graph
+        );
+
+        let root = std::env::temp_dir().join("gir-parser-include-graph");
+        std::fs::create_dir_all(&root).unwrap();
+        for (name, include) in [("A", "B"), ("B", "A")] {
+            let content = format!(
+                r#"<repository version="1.2"><include name="{include}" version="1.0"/><namespace name="{name}" version="1.0"/></repository>"#
+            );
+            std::fs::write(root.join(format!("{name}-1.0.gir")), content).unwrap();
+        }
+        let result = IncludeGraph::load(["A-1.0.gir"], &GirSearchPath::new([&root]));
+        assert!(matches!(result, Err(ParserError::IncludeCycle(file)) if file == "A-1.0.gir"));
+        // The failure of the first file by name is reported, whichever of the
+        // files including them loaded first.
+        for (name, include) in [("C", "Zb"), ("D", "Za")] {
+            let content = format!(
+                r#"<repository version="1.2"><include name="{include}" version="1.0"/><namespace name="{name}" version="1.0"/></repository>"#
+            );
+            std::fs::write(root.join(format!("{name}-1.0.gir")), content).unwrap();
+        }
+        let result = IncludeGraph::load(["C-1.0.gir", "D-1.0.gir"], &GirSearchPath::new([&root]));
+        std::fs::remove_dir_all(&root).unwrap();
+        match result {
+            Err(ParserError::IO(error)) => assert!(error.to_string().starts_with("Za-1.0.gir")),
+            result => panic!("unexpected result {result:?}"),
+        }
+        assert!(matches!(
+            IncludeGraph::load(["Missing-1.0.gir"], &search_path),
+            Err(ParserError::IO(_))
+        ));
+        let files = (0..16).rev().map(|i| format!("Missing{i}-1.0.gir"));
+        match IncludeGraph::load(files, &search_path) {
+            Err(ParserError::IO(error)) => {
+                assert!(error.to_string().starts_with("Missing0-1.0.gir"))
+            }
+            result => panic!("unexpected result {result:?}"),
+        }
+    }
+
+    #[cfg(feature = "cache")]
+    #[test]
+    fn load_cached() {
+        use crate::{GirSearchPath, IncludeGraph, ParseOptions};
+
+        for file in ["GLib-2.0.gir", "GstVideo-1.0.gir", "Pango-1.0.gir"] {
+            let repository = Repository::from_path(format!("./gir-files/{file}")).unwrap();
+            let bytes = crate::cache::to_bytes(&repository).unwrap();
+            let cached = crate::cache::from_bytes::<Repository>(&bytes).unwrap();
+            assert_eq!(cached, repository);
+            let span = repository.namespace().span().unwrap();
+            let cached_span = cached.namespace().span().unwrap();
+            assert_eq!(cached_span.range(), span.range());
+            assert_eq!(cached_span.line(), span.line());
+            assert_eq!(
+                crate::cache::from_bytes::<Repository>(&bytes[..bytes.len() - 1]),
+                None
+            );
+        }
+
+        let root =
+            std::env::temp_dir().join(format!("gir-parser-load-cached-{}", std::process::id()));
+        let cache_dir = root.join("cache");
+        std::fs::create_dir_all(&root).unwrap();
+        let path = root.join("A-1.0.gir");
+        let write = |name: &str| {
+            let content = format!(
+                r#"<repository version="1.2"><namespace name="{name}" version="1.0"/></repository>"#
+            );
+            std::fs::write(&path, content).unwrap();
+        };
+        let cache_files = || std::fs::read_dir(&cache_dir).unwrap().count();
+
+        write("A");
+        let repository = Repository::load_cached(&path, &cache_dir).unwrap();
+        assert_eq!(repository, Repository::from_path(&path).unwrap());
+        assert_eq!(cache_files(), 1);
+        let cached = Repository::load_cached(&path, &cache_dir).unwrap();
+        assert_eq!(cached, repository);
+
+        // A stale snapshot is rebuilt.
+        write("B");
+        let repository = Repository::load_cached(&path, &cache_dir).unwrap();
+        assert_eq!(repository.namespace().name(), "B");
+        assert_eq!(
+            Repository::load_cached(&path, &cache_dir).unwrap(),
+            repository
+        );
+        assert_eq!(cache_files(), 1);
+
+        // A change made right after the snapshot was written is caught even
+        // when the modification time stays the same.
+        let mtime = std::fs::metadata(&path).unwrap().modified().unwrap();
+        write("C");
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(mtime).unwrap();
+        let repository = Repository::load_cached(&path, &cache_dir).unwrap();
+        assert_eq!(repository.namespace().name(), "C");
+
+        // A corrupted snapshot is rebuilt as well.
+        let cache_path = std::fs::read_dir(&cache_dir)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        let mut bytes = std::fs::read(&cache_path).unwrap();
+        bytes.truncate(bytes.len() / 2);
+        std::fs::write(&cache_path, &bytes).unwrap();
+        assert_eq!(
+            Repository::load_cached(&path, &cache_dir).unwrap(),
+            repository
+        );
+        std::fs::write(&cache_path, b"GIRCACHE\x02").unwrap();
+        assert_eq!(
+            Repository::load_cached(&path, &cache_dir).unwrap(),
+            repository
+        );
+        assert!(std::fs::read(&cache_path).unwrap().len() > 9);
+        // A snapshot that can't be written doesn't fail the load.
+        assert_eq!(Repository::load_cached(&path, &path).unwrap(), repository);
+        // Neither does a file whose path isn't valid UTF-8, which the spans
+        // keep.
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+
+            let path = root.join(std::ffi::OsStr::from_bytes(b"\xff-1.0.gir"));
+            std::fs::copy(root.join("A-1.0.gir"), &path).unwrap();
+            for _ in 0..2 {
+                let cached = Repository::load_cached(&path, &cache_dir).unwrap();
+                assert_eq!(cached, repository);
+                let span = cached.namespace().span().unwrap();
+                assert_eq!(span.file(), Some(path.as_path()));
+            }
+        }
+
+        // Snapshots are kept apart for the options changing the model, and
+        // keep the warnings.
+        let content = r#"<repository version="1.2">
+  <namespace name="D" version="1.0">
+    <alias name="Id" c:type="DId" colour="red">
+      <doc>An identifier.</doc>
+      <type name="gint" c:type="gint"/>
+    </alias>
+  </namespace>
+</repository>"#;
+        std::fs::write(&path, content).unwrap();
+        let interner = crate::Interner::new();
+        let lenient = ParseOptions {
+            interner: Some(interner.clone()),
+            ..ParseOptions::lenient()
+        };
+        let skip_docs = ParseOptions {
+            skip_docs: true,
+            ..ParseOptions::lenient()
+        };
+        let without_spans = ParseOptions {
+            spans: false,
+            ..ParseOptions::lenient()
+        };
+        assert!(Repository::load_cached(&path, &cache_dir).is_err());
+        let expected = Repository::from_path_with_options(&path, &lenient).unwrap();
+        for _ in 0..2 {
+            let loaded = Repository::load_cached_with_options(&path, &cache_dir, &lenient).unwrap();
+            assert_eq!(loaded, expected);
+            assert_eq!(loaded.1.len(), 1);
+            let skipped =
+                Repository::load_cached_with_options(&path, &cache_dir, &skip_docs).unwrap();
+            assert!(skipped.0.namespace().aliases()[0].doc().is_none());
+            let unspanned =
+                Repository::load_cached_with_options(&path, &cache_dir, &without_spans).unwrap();
+            assert!(unspanned.0.namespace().span().is_none());
+            assert!(loaded.0.namespace().span().is_some());
+        }
+        // Cached repositories go through the interner too.
+        let interned = Repository::load_cached_with_options(&path, &cache_dir, &lenient)
+            .unwrap()
+            .0;
+        let c_type = |repository: &Repository| {
+            let alias = &repository.namespace().aliases()[0];
+            alias.ty().as_type().c_type().unwrap().as_ptr()
+        };
+        assert_eq!(c_type(&interned), c_type(&expected.0));
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let cache_dir = std::env::temp_dir().join(format!(
+            "gir-parser-load-cached-graph-{}",
+            std::process::id()
+        ));
+        let search_path = GirSearchPath::new(["./gir-files"]);
+        let graph = IncludeGraph::load(["GObject-2.0.gir"], &search_path).unwrap();
+        for _ in 0..2 {
+            let cached =
+                IncludeGraph::load_cached(["GObject-2.0.gir"], &search_path, &cache_dir).unwrap();
+            assert_eq!(cached, graph);
+        }
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+    }
+
+    #[test]
+    fn skip_docs() {
+        use crate::{borrowed, ParseOptions};
+
+        let options = ParseOptions {
+            skip_docs: true,
+            ..ParseOptions::default()
+        };
+        let path = "./gir-files/GObject-2.0.gir";
+        let (repository, _) = Repository::from_path_with_options(path, &options).unwrap();
+        let full = Repository::from_path(path).unwrap();
+        let classes = repository.namespace().classes();
+        assert_eq!(classes.len(), full.namespace().classes().len());
+        for (class, full_class) in classes.iter().zip(full.namespace().classes()) {
+            assert_eq!(class.name(), full_class.name());
+            assert!(class.doc().is_none());
+            assert!(class.source_position().is_none());
+            for (method, full_method) in class.methods().zip(full_class.methods()) {
+                assert_eq!(method.name(), full_method.name());
+                assert!(method.doc().is_none());
+                assert!(method.doc_version().is_none());
+                assert_eq!(method.parameters().len(), full_method.parameters().len());
+            }
+        }
+        assert!(full.namespace().classes()[0].doc().is_some());
+
+        let source = std::fs::read_to_string(path).unwrap();
+        let (repository, _) = borrowed::Repository::parse_with_options(&source, &options).unwrap();
+        let class = &repository.namespace().classes()[0];
+        assert!(class.doc().is_none());
+        assert_eq!(class.name(), classes[0].name());
+
+        // Skipped documentation isn't checked, its unknown content is dropped
+        // with it.
+        let content = r#"<repository version="1.2"><namespace name="A" version="1.0"><alias name="B" c:type="B"><doc foo="1">text<b/></doc><source-position filename="a.h" line="1"><x/></source-position><type name="gint"/></alias></namespace></repository>"#;
+        let options = ParseOptions {
+            skip_docs: true,
+            ..ParseOptions::lenient()
+        };
+        let (repository, warnings) = Repository::from_str_with_options(content, &options).unwrap();
+        assert!(warnings.is_empty());
+        let alias = &repository.namespace().aliases()[0];
+        assert!(alias.doc().is_none());
+        assert!(alias.source_position().is_none());
+        assert_eq!(alias.ty().as_type().name(), Some("gint"));
+
+        // It's never recorded either, so it costs no more than reading it.
+        fn has_docs(node: crate::tree::Node) -> bool {
+            node.elements()
+                .any(|child| matches!(child.tag(), "doc" | "source-position") || has_docs(child))
+        }
+        let (tree, _) = crate::tree::Tree::parse(
+            &source,
+            crate::schema::Kind::ROOT,
+            None,
+            &ParseOptions {
+                skip_docs: true,
+                ..ParseOptions::default()
+            },
+            crate::tree::Origin::START,
+        )
+        .unwrap();
+        assert!(!has_docs(tree.root()));
+    }
+
+    #[test]
+    fn repository_header() {
+        use crate::{ParserError, RepositoryHeader};
+
+        for file in ["Gdk-4.0", "GObject-2.0", "Gst-1.0", "GstVideo-1.0"] {
+            let path = format!("./gir-files/{file}.gir");
+            let header = RepositoryHeader::from_path(&path).unwrap();
+            let repository = Repository::from_path(&path).unwrap();
+            assert_eq!(header.version(), repository.version());
+            assert_eq!(header.namespace_includes(), repository.namespace_includes());
+            assert_eq!(header.header_includes(), repository.header_includes());
+            assert_eq!(header.packages(), repository.packages());
+            assert_eq!(header.doc_format(), repository.doc_format());
+            let namespace = header.namespace();
+            assert_eq!(namespace.name(), repository.namespace().name());
+            assert_eq!(namespace.version(), repository.namespace().version());
+            assert_eq!(namespace.link_name(), repository.namespace().link_name());
+            assert!(namespace
+                .c_identifier_prefixes()
+                .eq(repository.namespace().c_identifier_prefixes()));
+            assert!(namespace
+                .c_symbol_prefixes()
+                .eq(repository.namespace().c_symbol_prefixes()));
+            assert!(namespace.classes().is_empty());
+            assert!(namespace.functions().is_empty());
+        }
+
+        // Errors after the first namespace child are not reported.
+        let content = r#"<repository version="1.2"><include name="GObject" version="2.0"/><namespace name="A" version="1.0"><alias/></namespace></repository>"#;
+        let header = content.parse::<RepositoryHeader>().unwrap();
+        assert_eq!(header.namespace_includes()[0].as_package(), "GObject-2.0");
+        assert_eq!(header.namespace().name(), "A");
+
+        // Errors before it are, at their offset in the file.
+        let content = r#"<repository version="1.2"><include name="GObject"/><namespace name="A" version="1.0"><alias/></namespace></repository>"#;
+        let Err(ParserError::Parse(error)) = content.parse::<RepositoryHeader>() else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(error.offset(), content.find("<include").unwrap());
+
+        let content =
+            r#"<repository version="1.2"><namespace name="A" version="1.0"/></repository>"#;
+        let header = content.parse::<RepositoryHeader>().unwrap();
+        assert_eq!(header.namespace().version().to_string(), "1.0");
+    }
+
+    #[test]
+    fn from_reader() {
+        use crate::RepositoryHeader;
+
+        let path = "./gir-files/GObject-2.0.gir";
+        let content = std::fs::read(path).unwrap();
+        let repository = Repository::from_path(path).unwrap();
+        assert_eq!(
+            Repository::from_reader(content.as_slice()).unwrap(),
+            repository
+        );
+        let header = RepositoryHeader::from_reader(content.as_slice()).unwrap();
+        assert_eq!(header, RepositoryHeader::from_path(path).unwrap());
+
+        #[cfg(not(feature = "gz"))]
+        {
+            use crate::ParserError;
+
+            let Err(ParserError::IO(e)) = Repository::from_reader(&[0x1f, 0x8b, 0x08][..]) else {
+                panic!("expected an I/O error");
+            };
+            assert_eq!(e.kind(), std::io::ErrorKind::Unsupported);
+        }
+
+        #[cfg(any(feature = "gz", feature = "xz"))]
+        {
+            use std::io::Write;
+
+            use crate::GirSearchPath;
+
+            let dir =
+                std::env::temp_dir().join(format!("gir-parser-compressed-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            let mut files = Vec::new();
+            #[cfg(feature = "gz")]
+            {
+                let file = dir.join("gz").join("GObject-2.0.gir.gz");
+                std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+                let mut encoder = flate2::write::GzEncoder::new(
+                    std::fs::File::create(&file).unwrap(),
+                    flate2::Compression::default(),
+                );
+                encoder.write_all(&content).unwrap();
+                encoder.finish().unwrap();
+                files.push(file);
+            }
+            #[cfg(feature = "xz")]
+            {
+                let file = dir.join("xz").join("GObject-2.0.gir.xz");
+                std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+                let mut encoder =
+                    xz2::write::XzEncoder::new(std::fs::File::create(&file).unwrap(), 6);
+                encoder.write_all(&content).unwrap();
+                encoder.finish().unwrap();
+                files.push(file);
+            }
+            for file in files {
+                assert_eq!(Repository::from_path(&file).unwrap(), repository);
+                assert_eq!(RepositoryHeader::from_path(&file).unwrap(), header);
+                let search_path = GirSearchPath::new([file.parent().unwrap()]);
+                assert_eq!(search_path.find("GObject-2.0.gir").unwrap().path(), file);
+            }
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn stream() {
+        use crate::{GirEvent, GirStream, ParseOptions, ParserError};
+
+        let path = "./gir-files/Gdk-4.0.gir";
+        let repository = Repository::from_path(path).unwrap();
+        let namespace = repository.namespace();
+        let mut includes = Vec::new();
+        let mut functions = Vec::new();
+        let mut classes = Vec::new();
+        let mut methods = Vec::new();
+        let mut in_class = false;
+        let mut depth = 0;
+        for event in GirStream::from_path(path).unwrap() {
+            match event.unwrap() {
+                GirEvent::StartRepository(attributes) => {
+                    assert_eq!(attributes.version(), repository.version());
+                }
+                GirEvent::Include(include) => includes.push(include),
+                GirEvent::Function(function) if depth == 0 => functions.push(function),
+                GirEvent::StartClass(class) => {
+                    classes.push(class);
+                    in_class = true;
+                    depth += 1;
+                }
+                GirEvent::StartInterface(_)
+                | GirEvent::StartRecord(_)
+                | GirEvent::StartUnion(_) => depth += 1,
+                GirEvent::Method(method) if in_class => methods.push(method),
+                GirEvent::EndClass => {
+                    in_class = false;
+                    depth -= 1;
+                }
+                GirEvent::EndInterface | GirEvent::EndRecord | GirEvent::EndUnion => depth -= 1,
+                _ => (),
+            }
+        }
+        assert_eq!(includes, repository.namespace_includes());
+        assert_eq!(functions, namespace.functions());
+        assert_eq!(classes.len(), namespace.classes().len());
+        for (class, full) in classes.iter().zip(namespace.classes()) {
+            assert_eq!(class.name(), full.name());
+            assert_eq!(class.parent(), full.parent());
+            assert_eq!(class.doc(), full.doc());
+        }
+        assert!(methods
+            .iter()
+            .eq(namespace.classes().iter().flat_map(|c| c.methods())));
+
+        // Errors and warnings point to the same place as when parsing the
+        // whole document.
+        let content = r#"<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <class name="Widget" glib:type-name="TestWidget" glib:get-type="test_widget_get_type" colour="red">
+      <doc>A widget.</doc>
+      <method name="show" c:identifier="test_widget_show">
+        <return-value><type name="none"/></return-value>
+        <parameters>
+          <parameter name="a" colour="red"><type name="gint"/></parameter>
+        </parameters>
+      </method>
+      <future-element/>
+    </class>
+    <record name="Empty"/>
+  </namespace>
+</repository>"#;
+        let Err(ParserError::Parse(expected)) = Repository::from_str(content) else {
+            panic!("expected a parse error");
+        };
+        let events = GirStream::from_reader(content.as_bytes())
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert!(matches!(events[0], Ok(GirEvent::StartRepository(_))));
+        assert!(matches!(events[1], Ok(GirEvent::StartNamespace(_))));
+        let Some(Err(ParserError::Parse(error))) = events.last() else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(*error, expected);
+
+        let options = ParseOptions::lenient();
+        let (_, expected) = Repository::from_str_with_options(content, &options).unwrap();
+        let mut stream = GirStream::from_reader_with_options(content.as_bytes(), &options).unwrap();
+        let events = stream.by_ref().collect::<Result<Vec<_>, _>>().unwrap();
+        let GirEvent::StartClass(class) = &events[2] else {
+            panic!("expected a class");
+        };
+        assert_eq!(class.doc().unwrap().text(), "A widget.");
+        assert!(matches!(events[3], GirEvent::Method(_)));
+        assert!(matches!(events[4], GirEvent::EndClass));
+        assert!(matches!(events[5], GirEvent::StartRecord(_)));
+        assert!(matches!(events[6], GirEvent::EndRecord));
+        assert!(matches!(events[7], GirEvent::EndNamespace));
+        assert!(matches!(events[8], GirEvent::EndRepository));
+        assert_eq!(events.len(), 9);
+        assert_eq!(stream.warnings(), expected);
+
+        // Unknown members are skipped by both in strict mode, without a
+        // warning.
+        let content = r#"<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <vendor-thing/>
+    <alias name="A" c:type="A"><type name="gint"/></alias>
+  </namespace>
+</repository>"#;
+        let repository = Repository::from_str(content).unwrap();
+        assert_eq!(repository.namespace().aliases().len(), 1);
+        let mut stream = GirStream::from_reader(content.as_bytes()).unwrap();
+        let events = stream.by_ref().collect::<Result<Vec<_>, _>>().unwrap();
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, GirEvent::Alias(_))));
+        assert!(stream.warnings().is_empty());
+
+        // Documentation following the members of a type is reported, as it
+        // can't be part of the header read before them.
+        let content = r#"<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <class name="Widget" glib:type-name="TestWidget" glib:get-type="test_widget_get_type">
+      <method name="show" c:identifier="test_widget_show">
+        <return-value><type name="none"/></return-value>
+      </method>
+      <doc>A widget.</doc>
+    </class>
+  </namespace>
+</repository>"#;
+        assert_eq!(
+            Repository::from_str(content).unwrap().namespace().classes()[0]
+                .doc()
+                .unwrap()
+                .text(),
+            "A widget."
+        );
+        let mut stream = GirStream::from_reader(content.as_bytes()).unwrap();
+        let events = stream.by_ref().collect::<Result<Vec<_>, _>>().unwrap();
+        let GirEvent::StartClass(class) = &events[2] else {
+            panic!("expected a class");
+        };
+        assert!(class.doc().is_none());
+        let [warning] = stream.warnings() else {
+            panic!("expected a warning, got {:?}", stream.warnings());
+        };
+        assert_eq!(
+            warning.message(),
+            "`doc` element after the members of its parent"
+        );
+        assert_eq!(
+            warning.element_path(),
+            "namespace[@name=Test]/class[@name=Widget]/doc"
+        );
+        assert_eq!(warning.line(), 7);
+        let options = ParseOptions {
+            skip_docs: true,
+            ..ParseOptions::default()
+        };
+        let mut stream = GirStream::from_reader_with_options(content.as_bytes(), &options).unwrap();
+        assert!(stream.by_ref().all(|event| event.is_ok()));
+        assert!(stream.warnings().is_empty());
+
+        // So do the ones about the attributes of the root element.
+        let content = r#"<repository version="1.2" colour="red">
+  <namespace name="Test" version="1.0"/>
+</repository>"#;
+        let Err(ParserError::Parse(expected)) = Repository::from_str(content) else {
+            panic!("expected a parse error");
+        };
+        let mut stream = GirStream::from_reader(content.as_bytes()).unwrap();
+        let Some(Err(ParserError::Parse(error))) = stream.next() else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(error, expected);
+
+        // And an element following the root one.
+        let content = r#"<repository version="1.2">
+  <namespace name="Test" version="1.0"/>
+</repository>
+<repository version="1.2"/>"#;
+        let Err(ParserError::Parse(expected)) = Repository::from_str(content) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(
+            expected.message(),
+            "unexpected element after the document root"
+        );
+        let Some(Err(ParserError::Parse(error))) = GirStream::from_reader(content.as_bytes())
+            .unwrap()
+            .find(Result::is_err)
+        else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(error, expected);
+
+        // Elements sharing a line with the ones before them are located on
+        // it, while the text before them isn't kept.
+        let content = r#"<repository version="1.2"><namespace name="Test" version="1.0"><alias name="A" c:type="A"><type name="gint"/></alias><alias name="B" c:type="B" colour="red"><type name="gint"/></alias></namespace></repository>"#;
+        let Err(ParserError::Parse(expected)) = Repository::from_str(content) else {
+            panic!("expected a parse error");
+        };
+        let Some(Err(ParserError::Parse(error))) = GirStream::from_reader(content.as_bytes())
+            .unwrap()
+            .find(Result::is_err)
+        else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(
+            (error.offset(), error.line(), error.column()),
+            (expected.offset(), expected.line(), expected.column())
+        );
+        assert_eq!(&content[error.offset()..][..6], "colour");
+        assert!(error
+            .diagnostic()
+            .contains(&format!("{}^", " ".repeat(error.column() - 1))));
+
+        let content = content.replace(r#" colour="red""#, "");
+        let options = ParseOptions {
+            spans: true,
+            ..ParseOptions::default()
+        };
+        let (repository, _) = Repository::from_str_with_options(&content, &options).unwrap();
+        let events = GirStream::from_reader_with_options(content.as_bytes(), &options)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let GirEvent::Alias(alias) = &events[3] else {
+            panic!("expected an alias");
+        };
+        let span = alias.span().unwrap();
+        assert_eq!(span, repository.namespace().aliases()[1].span().unwrap());
+        assert_eq!((span.line(), span.column()), (1, span.start() + 1));
+    }
+
+    #[test]
+    fn spans() {
+        use std::{hash::BuildHasher, path::Path};
+
+        use crate::{GirEvent, GirStream, ParseOptions};
+
+        let path = "./gir-files/Gdk-4.0.gir";
+        let source = std::fs::read_to_string(path).unwrap();
+        let options = ParseOptions::default();
+        let (repository, _) = Repository::from_path_with_options(path, &options).unwrap();
+        let without_spans = ParseOptions {
+            spans: false,
+            ..ParseOptions::default()
+        };
+        let (without_spans, _) = Repository::from_path_with_options(path, &without_spans).unwrap();
+        assert!(without_spans.namespace().span().is_none());
+        let class = &repository.namespace().classes()[0];
+        let span = class.span().unwrap();
+        assert_eq!(span.file(), Some(Path::new(path)));
+        let text = &source[span.range()];
+        assert!(text.starts_with(&format!("<class name=\"{}\"", class.name())));
+        assert!(text.ends_with("</class>"));
+        let line_start = source[..span.start()].rfind('\n').unwrap() + 1;
+        assert_eq!(span.line(), source[..line_start].lines().count() + 1);
+        assert_eq!(span.column(), span.start() - line_start + 1);
+        let method = class.methods().next().unwrap();
+        let parameter = method.parameters().instance().unwrap();
+        let text = &source[parameter.span().unwrap().range()];
+        assert!(text.starts_with("<instance-parameter"));
+        assert!(text.ends_with("</instance-parameter>"));
+
+        // Spans only differ by the file, and aren't part of the equality of
+        // nodes.
+        let (from_reader, _) =
+            Repository::from_reader_with_options(source.as_bytes(), &options).unwrap();
+        let reader_span = from_reader.namespace().classes()[0].span().unwrap();
+        assert_eq!(reader_span.file(), None);
+        assert_eq!(reader_span.range(), span.range());
+        assert_eq!(from_reader, repository);
+        assert_eq!(from_reader, without_spans);
+        let state = std::hash::RandomState::new();
+        assert_eq!(
+            state.hash_one(from_reader.namespace().classes()[0].doc()),
+            state.hash_one(without_spans.namespace().classes()[0].doc())
+        );
+
+        // Leaf events have the same spans as when parsing the whole document,
+        // while the spans of start events end with their header.
+        let mut classes = 0;
+        for event in GirStream::from_path_with_options(path, &options).unwrap() {
+            match event.unwrap() {
+                GirEvent::StartClass(start) if classes == 0 => {
+                    let header = start.span().unwrap();
+                    assert_eq!(header.start(), span.start());
+                    assert!(source[header.range()].ends_with("</doc>"));
+                    classes += 1;
+                }
+                GirEvent::Method(event) if classes == 1 => {
+                    assert_eq!(event.span(), method.span());
+                    assert_eq!(
+                        event.parameters().instance().unwrap().span(),
+                        parameter.span()
+                    );
+                    break;
+                }
+                _ => (),
+            }
+        }
+
+        // Nodes take the span of their own element, past the documentation
+        // that is skipped and the unknown elements that are moved around.
+        let path = "./gir-files/GtkSource-5.gir";
+        let source = std::fs::read_to_string(path).unwrap();
+        for options in [
+            options.clone(),
+            ParseOptions {
+                skip_docs: true,
+                ..options.clone()
+            },
+        ] {
+            let (repository, _) = Repository::from_str_with_options(&source, &options).unwrap();
+            for class in repository.namespace().classes() {
+                for method in class.methods() {
+                    let text = &source[method.span().unwrap().range()];
+                    assert!(text.starts_with(&format!("<method name=\"{}\"", method.name())));
+                    let text = &source[method.return_value().span().unwrap().range()];
+                    assert!(text.starts_with("<return-value"));
+                    for parameter in method.parameters().inner() {
+                        let text = &source[parameter.span().unwrap().range()];
+                        assert!(
+                            text.starts_with(&format!("<parameter name=\"{}\"", parameter.name()))
+                        );
+                    }
+                    if let Some(doc) = method.doc() {
+                        assert!(source[doc.span().unwrap().range()].starts_with("<doc "));
+                    }
+                }
+            }
+        }
+        let source = r#"<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <glib:future name="a"><glib:nested/></glib:future>
+    <function name="run" c:identifier="test_run">
+      <return-value><type name="none"/></return-value>
+    </function>
+  </namespace>
+</repository>"#;
+        let options = ParseOptions {
+            strict: false,
+            ..options
+        };
+        let (repository, _) = Repository::from_str_with_options(source, &options).unwrap();
+        let function = &repository.namespace().functions()[0];
+        assert!(source[function.span().unwrap().range()].starts_with("<function name=\"run\""));
+    }
+
+    #[test]
+    fn cst() {
+        use crate::cst::{Document, Element};
+
+        let path = "./gir-files/Gdk-4.0.gir";
+        let source = std::fs::read_to_string(path).unwrap();
+        let document = Document::from_path(path).unwrap();
+        assert_eq!(document.to_string(), source);
+
+        let content = r#"<?xml version="1.0"?>
+<!-- Fixups for Test -->
+<repository version="1.2">
+  <namespace name="Test"   version='1.0' >
+    <function name="run" c:identifier="test_run">
+      <doc xml:space="preserve">Runs &lt;things&gt;.</doc>
+      <return-value><type name="none"/></return-value>
+      <parameters>
+        <!-- May be null -->
+        <parameter name="data" transfer-ownership="none">
+          <type name="gpointer"/>
+        </parameter>
+      </parameters>
+    </function>
+  </namespace>
+</repository>
+"#;
+        let mut document = Document::from_str(content).unwrap();
+        assert_eq!(document.to_string(), content);
+        let function = &document.repository().namespace().functions()[0];
+        let span = function.parameters().inner()[0].span().unwrap().clone();
+        let doc_span = function.doc().unwrap().span().unwrap().clone();
+        let parameter = document.element_mut(&span).unwrap();
+        assert_eq!(parameter.attribute("name").as_deref(), Some("data"));
+        parameter.set_attribute("nullable", "1");
+        parameter.set_attribute("transfer-ownership", "full");
+        let doc = document.element_mut(&doc_span).unwrap();
+        assert_eq!(doc.attribute("xml:space").as_deref(), Some("preserve"));
+        doc.set_text("Runs <other> things.");
+        let namespace = document.root_mut().elements_mut().next().unwrap();
+        namespace.remove_attribute("version");
+        let mut constant = Element::new("constant");
+        constant.set_attribute("name", "A & B");
+        namespace.append(constant);
+        assert_eq!(
+            document.to_string(),
+            content
+                .replace("   version='1.0' ", " ")
+                .replace(
+                    r#"transfer-ownership="none""#,
+                    r#"transfer-ownership="full" nullable="1""#
+                )
+                .replace("Runs &lt;things&gt;.", "Runs &lt;other&gt; things.")
+                .replace(
+                    "    </function>\n",
+                    "    </function>\n    <constant name=\"A &amp; B\"/>\n"
+                )
+        );
+
+        let namespace = document.root_mut().elements_mut().next().unwrap();
+        namespace.set_attribute("version", "1.0");
+        namespace
+            .children_mut()
+            .retain(|node| node.as_element().is_none_or(|e| e.name() != "constant"));
+        document.reparse().unwrap();
+        let function = &document.repository().namespace().functions()[0];
+        let parameter = &function.parameters().inner()[0];
+        assert_eq!(parameter.is_nullable(), Some(true));
+        assert_eq!(function.doc().unwrap().text(), "Runs <other> things.");
+        let span = parameter.span().unwrap();
+        assert_eq!(document.element(span).unwrap().name(), "parameter");
+    }
+
+    #[test]
+    fn builders() {
+        use crate::{
+            Callable, Class, DocFormat, Documentation, Function, InstanceParameter, Method,
+            Namespace, Parameter, Type, Version,
+        };
+
+        let boolean = Type::builder().name("gboolean").c_type("gboolean").build();
+        let show = Method::builder("show")
+            .c_identifier("test_widget_show")
+            .doc(Documentation::builder("Shows the widget.").build())
+            .instance_parameter(
+                InstanceParameter::builder("widget")
+                    .ty(Type::builder().name("Widget").c_type("TestWidget*").build())
+                    .build(),
+            )
+            .parameter(Parameter::builder("animate").ty(boolean).build())
+            .build();
+        let class = Class::builder("Widget", "TestWidget", "test_widget_get_type")
+            .c_type("TestWidget")
+            .parent("GObject.Object")
+            .callable(Callable::Method(show))
+            .build();
+        let init = Function::builder("init")
+            .c_identifier("test_init")
+            .throws(true)
+            .build();
+        let namespace = Namespace::builder("Test", Version::new(1, 0, 0))
+            .class(class)
+            .function(init)
+            .build();
+        let repository = Repository::builder(namespace)
+            .version(Version::new(1, 2, 0))
+            .doc_format(DocFormat::GiDocgen)
+            .build();
+        let xml = repository.to_xml();
+        // The prefixes used by the document are declared.
+        for prefix in ["xmlns", "xmlns:c", "xmlns:glib", "xmlns:doc"] {
+            assert!(xml.contains(&format!(r#" {prefix}="http://www.gtk.org/introspection/"#)));
+        }
+        // A function without parameters has no `parameters` element.
+        assert!(!xml.contains("<parameters/>"));
+        let parsed = Repository::from_str(&xml).unwrap();
+        assert_eq!(parsed, repository);
+        assert_eq!(parsed.doc_format(), DocFormat::GiDocgen);
+        let namespace = parsed.namespace();
+        let method = namespace.classes()[0].methods().next().unwrap();
+        assert_eq!(method.parameters().inner()[0].name(), "animate");
+        assert_eq!(method.doc().unwrap().text(), "Shows the widget.");
+        let init = &namespace.functions()[0];
+        assert!(init.throws());
+        assert_eq!(init.return_value().ty().as_type().name(), Some("none"));
+
+        // Patch a parsed repository.
+        let mut repository = Repository::from_path("./gir-files/Gdk-4.0.gir").unwrap();
+        let function = repository
+            .namespace_mut()
+            .functions_mut()
+            .iter_mut()
+            .find(|function| function.name() == "content_deserialize_async")
+            .unwrap();
+        *function.parameters_mut().inner_mut()[1].nullable_mut() = Some(true);
+        function.doc_mut().take();
+        let parsed = Repository::from_str(&repository.to_xml()).unwrap();
+        let function = parsed
+            .namespace()
+            .functions()
+            .iter()
+            .find(|function| function.name() == "content_deserialize_async")
+            .unwrap();
+        assert_eq!(function.parameters().inner()[1].is_nullable(), Some(true));
+        assert!(function.doc().is_none());
+        assert_eq!(parsed, repository);
+    }
+
+    #[test]
+    fn metadata() {
+        use crate::{
+            AnyType, Metadata, ParameterType, ParserError, RecordField, TransferOwnership,
+        };
+
+        let metadata = Metadata::from_str(
+            r#"// Fixes for Gdk
+Gdk.content_deserialize_async.mime_type nullable=true transfer-ownership=full
+Gdk.Display#class
+  .get_default.return nullable=false
+  .beep throws
+  .beep#property skip
+Gdk.RGBA.parse name="parse_string"
+Gdk.content_formats_* version=4.2 // all of them
+Gdk.Display nullable
+Gdk.CLIPBOARD#function-macro.obj nullable
+Gdk.CLIPBOARD name=CLIPBOARD_CAST
+"#,
+        )
+        .unwrap();
+        let mut repository = Repository::from_path("./gir-files/Gdk-4.0.gir").unwrap();
+        let warnings = metadata.apply(&mut repository);
+        let namespace = repository.namespace();
+        let function = namespace
+            .functions()
+            .iter()
+            .find(|function| function.name() == "content_deserialize_async")
+            .unwrap();
+        let parameter = &function.parameters().inner()[1];
+        assert_eq!(parameter.is_nullable(), Some(true));
+        assert_eq!(
+            parameter.transfer_ownership(),
+            Some(TransferOwnership::Full)
+        );
+        let display = namespace
+            .classes()
+            .iter()
+            .find(|class| class.name() == "Display")
+            .unwrap();
+        let get_default = display
+            .functions()
+            .find(|function| function.name() == "get_default")
+            .unwrap();
+        assert_eq!(get_default.return_value().is_nullable(), Some(false));
+        let beep = display.methods().find(|m| m.name() == "beep").unwrap();
+        assert!(beep.throws());
+        let rgba = namespace
+            .records()
+            .iter()
+            .find(|record| record.name() == Some("RGBA"))
+            .unwrap();
+        assert!(rgba.methods().any(|method| method.name() == "parse_string"));
+        let formats = namespace
+            .functions()
+            .iter()
+            .filter(|function| function.name().starts_with("content_formats_"));
+        let version = Version::from_str("4.2").unwrap();
+        assert!(formats
+            .clone()
+            .all(|function| function.version() == Some(&version)));
+        assert!(formats.count() > 0);
+        let clipboard = namespace
+            .macros()
+            .iter()
+            .find(|function| function.name() == "CLIPBOARD_CAST")
+            .unwrap();
+        assert_eq!(clipboard.parameters().inner()[0].is_nullable(), Some(true));
+
+        let warnings = warnings
+            .iter()
+            .map(|warning| {
+                (
+                    warning.line(),
+                    warning.column(),
+                    warning.element_path(),
+                    warning.message(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            warnings,
+            [
+                (
+                    6,
+                    3,
+                    "Gdk.Display#class.beep#property",
+                    "path matched nothing"
+                ),
+                (
+                    9,
+                    13,
+                    "Gdk.Display",
+                    "`nullable` doesn't apply to class nodes"
+                ),
+            ]
+        );
+
+        let Err(ParserError::Parse(error)) = Metadata::from_str("Gdk.Display\n  .beep thrown")
+        else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((error.line(), error.column()), (2, 9));
+        assert_eq!(error.message(), "unknown change `thrown`");
+
+        let metadata = Metadata::from_str("GLib.clear_fd#function-inline skip=false").unwrap();
+        let mut repository = Repository::from_path("./gir-files/GLib-2.0.gir").unwrap();
+        assert!(metadata.apply(&mut repository).is_empty());
+        let clear_fd = repository
+            .namespace()
+            .inlined_functions()
+            .iter()
+            .find(|function| function.name() == "clear_fd")
+            .unwrap();
+        assert!(clear_fd.is_introspectable());
+
+        let metadata = Metadata::from_str(
+            r#"Test.sum.values type=gint64[]
+Test.sum.count type=gsize
+Test.sum.return type=gint64[][]
+Test.sum.values type=gint
+Test.Point.x type=gdouble
+Test.Point.notify type=gpointer
+"#,
+        )
+        .unwrap();
+        let mut repository = Repository::from_str(
+            r#"<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <record name="Point" c:type="TestPoint">
+      <field name="x"><type name="gint" c:type="int"/></field>
+      <field name="notify">
+        <callback name="notify"><return-value><type name="none"/></return-value></callback>
+      </field>
+    </record>
+    <function name="sum" c:identifier="test_sum">
+      <return-value><type name="gint" c:type="int"/></return-value>
+      <parameters>
+        <parameter name="values">
+          <array length="1" c:type="int*"><type name="gint" c:type="int"/></array>
+        </parameter>
+        <parameter name="count"><type name="guint" c:type="unsigned"/></parameter>
+      </parameters>
+    </function>
+  </namespace>
+</repository>"#,
+        )
+        .unwrap();
+        let warnings = metadata
+            .apply(&mut repository)
+            .iter()
+            .map(|warning| {
+                (
+                    warning.line(),
+                    warning.column(),
+                    warning.message().to_owned(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            warnings,
+            [
+                (
+                    4,
+                    17,
+                    "`type=gint` doesn't apply to parameter nodes".to_owned()
+                ),
+                (
+                    6,
+                    19,
+                    "`type=gpointer` doesn't apply to field nodes".to_owned()
+                ),
+            ]
+        );
+        let namespace = repository.namespace();
+        let sum = &namespace.functions()[0];
+        let parameters = sum.parameters().inner();
+        let Some(ParameterType::Array(values)) = parameters[0].ty() else {
+            panic!("expected an array");
+        };
+        assert_eq!((values.length(), values.c_type()), (Some(1), Some("int*")));
+        assert_eq!(values.ty().name(), Some("gint64"));
+        let Some(ParameterType::Type(count)) = parameters[1].ty() else {
+            panic!("expected a type");
+        };
+        assert_eq!(
+            (count.name(), count.c_type()),
+            (Some("gsize"), Some("unsigned"))
+        );
+        let AnyType::Array(array) = sum.return_value().ty() else {
+            panic!("expected an array");
+        };
+        assert_eq!(array.c_type(), Some("int"));
+        assert!(array.element().is_array());
+        assert_eq!(array.ty().name(), Some("gint64"));
+        let fields = namespace.records()[0].fields();
+        let [RecordField::Field(x), RecordField::Field(notify)] = fields else {
+            panic!("expected two fields");
+        };
+        assert_eq!(x.ty().as_type().name(), Some("gdouble"));
+        assert!(notify.ty().is_callback());
+    }
+
+    #[test]
+    fn interner() {
+        use crate::{GirSearchPath, IncludeGraph, Interner, ParseOptions};
+
+        let search_path = GirSearchPath::new(["./gir-files"]);
+        let interner = Interner::new();
+        let options = ParseOptions {
+            interner: Some(interner.clone()),
+            ..ParseOptions::default()
+        };
+        let (graph, warnings) =
+            IncludeGraph::load_with_options(["GstVideo-1.0.gir"], &search_path, &options).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(
+            graph,
+            IncludeGraph::load(["GstVideo-1.0.gir"], &search_path).unwrap()
+        );
+        assert!(!interner.is_empty());
+
+        let return_type = |file: &str, class: &str, method: &str| {
+            let classes = graph.get(file).unwrap().namespace().classes();
+            let class = classes.iter().find(|c| c.name() == class).unwrap();
+            let method = class.methods().find(|m| m.name() == method).unwrap();
+            method
+                .return_value()
+                .ty()
+                .as_type()
+                .name_atom()
+                .unwrap()
+                .clone()
+        };
+        let a = return_type("GObject-2.0.gir", "Object", "get_data");
+        let b = return_type("Gst-1.0.gir", "Object", "get_name");
+        assert_eq!(*a, *"gpointer");
+        assert_eq!(*b, *"utf8");
+        let c = return_type("Gst-1.0.gir", "Object", "get_path_string");
+        assert!(b.ptr_eq(&c));
+        assert_eq!(interner.intern("utf8"), b);
+        assert!(interner.intern("utf8").ptr_eq(&b));
+
+        // Without an interner, equal names are still equal.
+        let repository = Repository::from_path("./gir-files/Gst-1.0.gir").unwrap();
+        let object = repository.namespace().classes();
+        let object = object.iter().find(|c| c.name() == "Object").unwrap();
+        let names = object
+            .methods()
+            .filter_map(|m| m.return_value().ty().as_type().name_atom())
+            .filter(|name| **name == *"utf8")
+            .collect::<Vec<_>>();
+        assert!(names.len() > 1);
+        assert_eq!(names[0], names[1]);
+        assert!(!names[0].ptr_eq(names[1]));
+    }
+
+    #[test]
+    fn borrowed_repository() {
+        use crate::{borrowed, ParseOptions};
+
+        fn assert_same_doc(owned: &impl Documentable, doc: Option<&borrowed::Documentation>) {
+            assert_eq!(owned.doc().map(|d| d.text()), doc.map(|d| d.text()));
+        }
+
+        // Implementing the attributes is enough to look them up.
+        struct Annotated(Vec<crate::Attribute>);
+
+        impl Attributable for Annotated {
+            fn attributes(&self) -> &[crate::Attribute] {
+                &self.0
+            }
+        }
+
+        let annotated = Annotated(vec![crate::Attribute::builder(
+            "element-type",
+            "utf8".to_owned(),
+        )
+        .build()]);
+        assert_eq!(annotated.element_type(), Some("utf8"));
+        assert_eq!(annotated.attribute("missing"), None);
+
+        // The traits of the prelude are implemented by both models.
+        type Summary<'a> = (
+            bool,
+            bool,
+            Option<&'a Version>,
+            usize,
+            Option<&'a str>,
+            bool,
+            bool,
+        );
+
+        fn summary<M: Model>(callable: &impl FunctionLike<M>) -> Summary<'_> {
+            (
+                callable.is_introspectable(),
+                callable.is_deprecated(),
+                callable.version(),
+                callable.attributes().len(),
+                callable.element_type(),
+                callable.doc().is_some(),
+                callable.throws(),
+            )
+        }
+
+        fn assert_same_callable(owned: &impl FunctionLike, callable: &borrowed::Function) {
+            assert_eq!(summary(owned), summary(callable));
+            assert_eq!(owned.throws(), callable.throws());
+            assert_eq!(
+                owned.return_value().ty().is_array(),
+                callable.return_value().ty().is_array()
+            );
+            assert_eq!(
+                owned
+                    .parameters()
+                    .all()
+                    .map(|p| (p.name().to_owned(), p.direction(), p.transfer_ownership()))
+                    .collect::<Vec<_>>(),
+                callable
+                    .parameters()
+                    .all()
+                    .map(|p| (p.name().to_owned(), p.direction(), p.transfer_ownership()))
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        for file in ["GObject-2.0", "Gdk-4.0", "GstVideo-1.0"] {
+            let source = std::fs::read_to_string(format!("./gir-files/{file}.gir")).unwrap();
+            let owned = Repository::from_str(&source).unwrap();
+            let repository = borrowed::Repository::parse(&source).unwrap();
+            assert_eq!(repository.version(), owned.version());
+            assert_eq!(repository.doc_format(), owned.doc_format());
+            assert_eq!(
+                repository
+                    .namespace_includes()
+                    .iter()
+                    .map(|i| i.as_package_file())
+                    .collect::<Vec<_>>(),
+                owned
+                    .namespace_includes()
+                    .iter()
+                    .map(|i| i.as_package_file())
+                    .collect::<Vec<_>>()
+            );
+
+            let namespace = repository.namespace();
+            let owned_namespace = owned.namespace();
+            assert_eq!(namespace.name(), owned_namespace.name());
+            assert_eq!(namespace.version(), owned_namespace.version());
+            assert_eq!(namespace.link_name(), owned_namespace.link_name());
+            // Names are slices of the source.
+            let range = source.as_bytes().as_ptr_range();
+            assert!(range.contains(&namespace.name().as_ptr()));
+
+            assert_eq!(namespace.classes().len(), owned_namespace.classes().len());
+            for (class, owned_class) in namespace.classes().iter().zip(owned_namespace.classes()) {
+                assert_eq!(class.name(), owned_class.name());
+                assert_eq!(class.parent(), owned_class.parent());
+                assert_eq!(class.g_type_name(), owned_class.g_type_name());
+                assert_eq!(class.is_abstract(), owned_class.is_abstract());
+                assert_eq!(class.version(), owned_class.version());
+                assert_same_doc(owned_class, class.doc());
+                assert_eq!(class.fields().len(), owned_class.fields().len());
+                assert_eq!(
+                    class
+                        .properties()
+                        .iter()
+                        .map(|p| p.name())
+                        .collect::<Vec<_>>(),
+                    owned_class
+                        .properties()
+                        .iter()
+                        .map(|p| p.name())
+                        .collect::<Vec<_>>()
+                );
+                assert_eq!(class.methods().count(), owned_class.methods().count());
+                for (method, owned_method) in class.methods().zip(owned_class.methods()) {
+                    assert_eq!(method.name(), owned_method.name());
+                    assert_eq!(method.c_identifier(), owned_method.c_identifier());
+                    assert_eq!(summary(method), summary(owned_method));
+                    assert_same_doc(owned_method, method.doc());
+                    assert_eq!(
+                        method.doc_deprecated().map(|d| d.text()),
+                        owned_method.doc_deprecated().map(|d| d.text())
+                    );
+                }
+                for (function, owned_function) in
+                    class.constructors().zip(owned_class.constructors())
+                {
+                    assert_same_callable(owned_function, function);
+                }
+            }
+            for (function, owned_function) in namespace
+                .functions()
+                .iter()
+                .zip(owned_namespace.functions())
+            {
+                assert_eq!(function.name(), owned_function.name());
+                assert_same_doc(owned_function, function.doc());
+                assert_same_callable(owned_function, function);
+            }
+            for (record, owned_record) in namespace.records().iter().zip(owned_namespace.records())
+            {
+                assert_eq!(record.name(), owned_record.name());
+                assert_eq!(
+                    record.g_is_gtype_struct_for(),
+                    owned_record.g_is_gtype_struct_for()
+                );
+                assert_eq!(record.fields().len(), owned_record.fields().len());
+            }
+            for (enumeration, owned_enumeration) in
+                namespace.enums().iter().zip(owned_namespace.enums())
+            {
+                assert_eq!(
+                    enumeration
+                        .members()
+                        .iter()
+                        .map(|m| (m.name(), m.value()))
+                        .collect::<Vec<_>>(),
+                    owned_enumeration
+                        .members()
+                        .iter()
+                        .map(|m| (m.name(), m.value()))
+                        .collect::<Vec<_>>()
+                );
+            }
+        }
+
+        let source = r#"<repository version="1.2" vendor="x"><namespace name="A" version="1.0"><vendor:thing/><alias name="B" c:type="B"><doc>a &lt; b</doc><type name="gint"/></alias></namespace></repository>"#;
+        assert!(borrowed::Repository::parse(source).is_err());
+        let (repository, warnings) =
+            borrowed::Repository::parse_with_options(source, &ParseOptions::lenient()).unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(
+            repository.extra_attributes(),
+            [("vendor".into(), "x".into())]
+        );
+        let namespace = repository.namespace();
+        assert_eq!(namespace.extra_children(), ["<vendor:thing/>"]);
+        let alias = &namespace.aliases()[0];
+        assert_eq!(alias.doc().unwrap().text(), "a < b");
+        assert_eq!(alias.ty().as_type().name(), Some("gint"));
+    }
+
     #[test]
     fn parse_doc_format_is_missing() {
         // doc:format is missing
@@ -334,4 +2415,588 @@ mod tests {
         let repo = Repository::from_str(content).unwrap();
         assert_eq!(repo.doc_format(), crate::DocFormat::GiDocgen);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let repository = Repository::from_path("./gir-files/Gdk-4.0.gir").unwrap();
+        let json = serde_json::to_value(&repository).unwrap();
+        assert_eq!(json["version"], "1.2");
+        assert_eq!(json["doc_format"], "gi-docgen");
+        let namespace = &json["namespace"];
+        assert_eq!(namespace["name"], "Gdk");
+        assert_eq!(namespace["version"], "4.0");
+        let surface = namespace["classes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|class| class["name"] == "Surface")
+            .unwrap();
+        assert_eq!(surface["abstract"], true);
+        assert_eq!(surface["parent"], "GObject.Object");
+        let get_display = surface["callables"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|callable| &callable["method"])
+            .find(|method| method["name"] == "get_display")
+            .unwrap();
+        let return_value = &get_display["return_value"];
+        assert_eq!(return_value["transfer_ownership"], "none");
+        assert_eq!(return_value["type"]["type"]["name"], "Display");
+        let instance = &get_display["parameters"]["instance_parameter"];
+        assert_eq!(instance["name"], "surface");
+        // Spans are left out of human-readable formats.
+        assert!(instance["span"].is_null());
+
+        // Arrays can't be loaded without the type of their elements.
+        let ty = crate::Type::builder().name("utf8").build();
+        let array = serde_json::to_value(crate::Array::builder(ty).build()).unwrap();
+        assert_eq!(array["element"]["type"]["name"], "utf8");
+        assert!(serde_json::from_value::<crate::Array>(array.clone()).is_ok());
+        for element in [serde_json::Value::Null, serde_json::json!({})] {
+            let mut array = array.clone();
+            array["element"] = element;
+            assert!(serde_json::from_value::<crate::Array>(array).is_err());
+        }
+        let mut array = array;
+        array.as_object_mut().unwrap().remove("element");
+        assert!(serde_json::from_value::<crate::Array>(array).is_err());
+
+        let text = serde_json::to_string(&repository).unwrap();
+        let loaded = serde_json::from_str::<Repository>(&text).unwrap();
+        assert_eq!(loaded, repository);
+        let (mut written, mut expected) = (Vec::new(), Vec::new());
+        loaded.write_to(&mut written).unwrap();
+        repository.write_to(&mut expected).unwrap();
+        assert_eq!(written, expected);
+    }
+
+    #[test]
+    fn validate() {
+        use crate::ParseOptions;
+
+        let content = r#"<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <union name="Value" c:type="TestValue">
+      <doc xml:space="default">A value.</doc>
+      <callback name="Notify">
+        <return-value><type name="none"/></return-value>
+      </callback>
+    </union>
+    <function name="log" c:identifier="test_log">
+      <return-value>
+        <type name="none"/>
+        <varargs/>
+      </return-value>
+      <parameters>
+        <parameter name="format"><type name="utf8"/></parameter>
+        <parameter name="..."><varargs/></parameter>
+      </parameters>
+    </function>
+    <glib:boxed glib:name="Pair" foreign="1"/>
+  </namespace>
+</repository>"#;
+        let options = ParseOptions {
+            spans: false,
+            ..ParseOptions::lenient()
+        };
+        let (repository, _) = Repository::from_str_with_options(content, &options).unwrap();
+        let violations = repository
+            .validate()
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            violations,
+            [
+                "namespace[@name=Test]/function[@name=log]/return-value/varargs: `varargs` elements aren't allowed in `return-value` elements",
+                "namespace[@name=Test]/union[@name=Value]/doc: invalid value `default` for attribute `xml:space`: expected one of `preserve`",
+                "namespace[@name=Test]/union[@name=Value]/doc: missing required attribute `filename`",
+                "namespace[@name=Test]/union[@name=Value]/doc: missing required attribute `line`",
+                "namespace[@name=Test]/union[@name=Value]/callback[@name=Notify]: `callback` elements aren't allowed in `union` elements",
+                "namespace[@name=Test]/glib:boxed: unknown attribute `foreign`",
+            ]
+        );
+
+        // With spans, the default, the violations follow the document and
+        // locate it.
+        let (repository, _) =
+            Repository::from_str_with_options(content, &ParseOptions::lenient()).unwrap();
+        let violations = repository
+            .validate()
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            violations,
+            [
+                "4:7: namespace[@name=Test]/union[@name=Value]/doc: invalid value `default` for attribute `xml:space`: expected one of `preserve`",
+                "4:7: namespace[@name=Test]/union[@name=Value]/doc: missing required attribute `filename`",
+                "4:7: namespace[@name=Test]/union[@name=Value]/doc: missing required attribute `line`",
+                "5:7: namespace[@name=Test]/union[@name=Value]/callback[@name=Notify]: `callback` elements aren't allowed in `union` elements",
+                // Elements that aren't modelled are located by their parent.
+                "10:7: namespace[@name=Test]/function[@name=log]/return-value/varargs: `varargs` elements aren't allowed in `return-value` elements",
+                "19:5: namespace[@name=Test]/glib:boxed: unknown attribute `foreign`",
+            ]
+        );
+
+        let repository = Repository::from_path("./gir-files/GObject-2.0.gir").unwrap();
+        assert_eq!(repository.validate(), []);
+        let repository = Repository::from_path("./gir-files/Gsk-4.0.gir").unwrap();
+        let violations = repository.validate();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].element_path(),
+            "namespace[@name=Gsk]/record[@name=PathPoint]/union/record"
+        );
+        assert_eq!(violations[0].message(), "missing required attribute `name`");
+    }
+
+    #[test]
+    fn diff() {
+        use crate::diff::{ApiDiff, Severity};
+
+        let old = Repository::from_str(
+            r#"<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <class name="Widget" parent="GObject.Object" glib:type-name="TestWidget" glib:get-type="test_widget_get_type">
+      <method name="get_label" c:identifier="test_widget_get_label">
+        <return-value transfer-ownership="none"><type name="utf8"/></return-value>
+        <parameters>
+          <instance-parameter name="widget"><type name="Widget"/></instance-parameter>
+        </parameters>
+      </method>
+      <method name="set_label" c:identifier="test_widget_set_label">
+        <return-value><type name="none"/></return-value>
+        <parameters>
+          <instance-parameter name="widget"><type name="Widget"/></instance-parameter>
+          <parameter name="label" nullable="1"><type name="utf8"/></parameter>
+        </parameters>
+      </method>
+      <method name="show" c:identifier="test_widget_show">
+        <return-value><type name="none"/></return-value>
+        <parameters>
+          <instance-parameter name="widget"><type name="Widget"/></instance-parameter>
+        </parameters>
+      </method>
+      <property name="label" writable="1"><type name="utf8"/></property>
+      <glib:signal name="clicked"><return-value><type name="none"/></return-value></glib:signal>
+      <glib:signal name="query">
+        <return-value><type name="utf8"/></return-value>
+        <parameters>
+          <parameter name="key"><type name="utf8"/></parameter>
+        </parameters>
+      </glib:signal>
+    </class>
+    <enumeration name="Align" c:type="TestAlign">
+      <member name="start" value="0" c:identifier="TEST_ALIGN_START"/>
+      <member name="end" value="1" c:identifier="TEST_ALIGN_END"/>
+    </enumeration>
+    <callback name="Filter" c:type="TestFilter">
+      <return-value><type name="utf8"/></return-value>
+      <parameters>
+        <parameter name="item"><type name="utf8"/></parameter>
+      </parameters>
+    </callback>
+    <constant name="MINOR_VERSION" value="0" c:type="TEST_MINOR_VERSION"><type name="gint"/></constant>
+  </namespace>
+</repository>"#,
+        )
+        .unwrap();
+        let new = Repository::from_str(
+            r#"<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <class name="Widget" parent="GObject.InitiallyUnowned" glib:type-name="TestWidget" glib:get-type="test_widget_get_type">
+      <method name="get_label" c:identifier="test_widget_get_label">
+        <return-value transfer-ownership="none" nullable="1"><type name="utf8"/></return-value>
+        <parameters>
+          <instance-parameter name="widget"><type name="Widget"/></instance-parameter>
+        </parameters>
+      </method>
+      <method name="set_label" c:identifier="test_widget_set_label" throws="1">
+        <return-value><type name="none"/></return-value>
+        <parameters>
+          <instance-parameter name="widget"><type name="Widget"/></instance-parameter>
+          <parameter name="label"><type name="utf8"/></parameter>
+        </parameters>
+      </method>
+      <property name="label" readable="0" writable="1"><type name="utf8"/></property>
+      <glib:signal name="clicked"><return-value><type name="none"/></return-value></glib:signal>
+      <glib:signal name="query">
+        <return-value nullable="1"><type name="utf8"/></return-value>
+        <parameters>
+          <parameter name="key" nullable="1"><type name="utf8"/></parameter>
+        </parameters>
+      </glib:signal>
+    </class>
+    <enumeration name="Align" c:type="TestAlign">
+      <member name="start" value="0" c:identifier="TEST_ALIGN_START"/>
+      <member name="center" value="1" c:identifier="TEST_ALIGN_CENTER"/>
+      <member name="end" value="2" c:identifier="TEST_ALIGN_END"/>
+    </enumeration>
+    <callback name="Filter" c:type="TestFilter">
+      <return-value nullable="1"><type name="utf8"/></return-value>
+      <parameters>
+        <parameter name="item" nullable="1"><type name="utf8"/></parameter>
+      </parameters>
+    </callback>
+    <constant name="MINOR_VERSION" value="2" c:type="TEST_MINOR_VERSION"><type name="gint"/></constant>
+  </namespace>
+</repository>"#,
+        )
+        .unwrap();
+
+        let diff = ApiDiff::new(&old, &new);
+        assert_eq!(diff.severity(), Severity::AbiBreaking);
+        assert_eq!(ApiDiff::new(&old, &old).severity(), Severity::Compatible);
+        assert!(ApiDiff::new(&new, &new).is_empty());
+        assert_eq!(
+            diff.to_string(),
+            "ABI-breaking changes:
+  changed parent of class `Test.Widget` from `GObject.Object` to `GObject.InitiallyUnowned`
+  changed throws of method `Test.Widget.set_label` from `false` to `true`
+  removed method `Test.Widget.show`
+  changed value of member `Test.Align.end` from `1` to `2`
+
+API-breaking changes:
+  changed nullable of return value `Test.Widget.get_label.return` from `false` to `true`
+  changed nullable of parameter `Test.Widget.set_label.label` from `true` to `false`
+  changed nullable of parameter `Test.Widget.query.key` from `false` to `true`
+  changed readable of property `Test.Widget.label` from `true` to `false`
+  changed nullable of parameter `Test.Filter.item` from `false` to `true`
+
+Compatible changes:
+  changed nullable of return value `Test.Widget.query.return` from `false` to `true`
+  added member `Test.Align.center`
+  changed nullable of return value `Test.Filter.return` from `false` to `true`
+  changed value of constant `Test.MINOR_VERSION` from `0` to `2`
+"
+        );
+    }
+
+    #[test]
+    fn diff_layout() {
+        use crate::diff::{ApiDiff, Severity};
+
+        let old = Repository::from_str(
+            r#"<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <class name="Widget" parent="GObject.Object" glib:type-name="TestWidget" glib:get-type="test_widget_get_type">
+      <implements name="Gtk.Buildable"/>
+      <implements name="Gtk.Accessible"/>
+      <method name="move" c:identifier="test_widget_move">
+        <return-value><type name="none"/></return-value>
+        <parameters>
+          <instance-parameter name="widget"><type name="Widget"/></instance-parameter>
+          <parameter name="x"><type name="gint"/></parameter>
+          <parameter name="label" transfer-ownership="none"><type name="utf8"/></parameter>
+          <parameter name="position" direction="out"><type name="gint"/></parameter>
+        </parameters>
+      </method>
+      <virtual-method name="draw">
+        <return-value><type name="none"/></return-value>
+        <parameters>
+          <instance-parameter name="widget"><type name="Widget"/></instance-parameter>
+          <parameter name="cr"><type name="cairo.Context"/></parameter>
+        </parameters>
+      </virtual-method>
+      <virtual-method name="size">
+        <return-value><type name="gint"/></return-value>
+      </virtual-method>
+      <field name="parent_instance"><type name="GObject.Object"/></field>
+      <field name="priv" private="1"><type name="gpointer"/></field>
+    </class>
+    <interface name="Scrollable" glib:type-name="TestScrollable" glib:get-type="test_scrollable_get_type">
+      <prerequisite name="GObject.Object"/>
+      <prerequisite name="Gio.ListModel"/>
+    </interface>
+    <record name="Rect" c:type="TestRect">
+      <field name="x"><type name="gint"/></field>
+      <field name="y"><type name="gint"/></field>
+      <field name="flags" bits="2"><type name="guint"/></field>
+      <union name="data">
+        <field name="i"><type name="gint"/></field>
+        <field name="p"><type name="gpointer"/></field>
+      </union>
+    </record>
+    <record name="WidgetClass" glib:is-gtype-struct-for="Widget">
+      <field name="parent_class"><type name="GObject.ObjectClass"/></field>
+      <field name="draw">
+        <callback name="draw">
+          <return-value><type name="none"/></return-value>
+          <parameters>
+            <parameter name="widget"><type name="Widget"/></parameter>
+          </parameters>
+        </callback>
+      </field>
+    </record>
+    <record name="Opaque" c:type="TestOpaque"/>
+    <enumeration name="Align" c:type="TestAlign">
+      <member name="start" value="0" c:identifier="TEST_ALIGN_START"/>
+      <member name="end" value="1" c:identifier="TEST_ALIGN_END"/>
+    </enumeration>
+  </namespace>
+</repository>"#,
+        )
+        .unwrap();
+        let new = Repository::from_str(
+            r#"<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <class name="Widget" parent="GObject.Object" glib:type-name="TestWidget" glib:get-type="test_widget_get_type">
+      <implements name="Gtk.Buildable"/>
+      <implements name="Gio.ListModel"/>
+      <method name="move" c:identifier="test_widget_move">
+        <return-value><type name="none"/></return-value>
+        <parameters>
+          <instance-parameter name="widget"><type name="Widget"/></instance-parameter>
+          <parameter name="x"><type name="gdouble"/></parameter>
+          <parameter name="label" transfer-ownership="full"><type name="utf8"/></parameter>
+          <parameter name="position" direction="inout"><type name="gint"/></parameter>
+        </parameters>
+      </method>
+      <virtual-method name="draw">
+        <return-value nullable="1"><type name="none"/></return-value>
+        <parameters>
+          <instance-parameter name="widget"><type name="Widget"/></instance-parameter>
+          <parameter name="cr" nullable="1"><type name="cairo.Context"/></parameter>
+        </parameters>
+      </virtual-method>
+      <virtual-method name="measure">
+        <return-value><type name="gint"/></return-value>
+      </virtual-method>
+      <field name="parent_instance"><type name="GObject.Object"/></field>
+      <field name="priv" private="1"><type name="gpointer"/></field>
+    </class>
+    <interface name="Scrollable" glib:type-name="TestScrollable" glib:get-type="test_scrollable_get_type">
+      <prerequisite name="GObject.Object"/>
+      <prerequisite name="Gtk.Widget"/>
+    </interface>
+    <record name="Rect" c:type="TestRect">
+      <field name="y"><type name="gint"/></field>
+      <field name="x"><type name="gint"/></field>
+      <field name="z"><type name="gint"/></field>
+      <field name="flags" bits="3"><type name="guint"/></field>
+      <union name="data">
+        <field name="i"><type name="gint"/></field>
+        <field name="p"><type name="guint64"/></field>
+      </union>
+    </record>
+    <record name="WidgetClass" glib:is-gtype-struct-for="Widget">
+      <field name="parent_class"><type name="GObject.ObjectClass"/></field>
+      <field name="draw">
+        <callback name="draw">
+          <return-value><type name="none"/></return-value>
+          <parameters>
+            <parameter name="widget"><type name="Widget"/></parameter>
+            <parameter name="cr"><type name="cairo.Context"/></parameter>
+          </parameters>
+        </callback>
+      </field>
+    </record>
+    <record name="Opaque" c:type="TestOpaque">
+      <field name="data"><type name="gpointer"/></field>
+    </record>
+    <enumeration name="Align" c:type="TestAlign">
+      <member name="start" value="0" c:identifier="TEST_ALIGN_START"/>
+    </enumeration>
+  </namespace>
+</repository>"#,
+        )
+        .unwrap();
+
+        let diff = ApiDiff::new(&old, &new);
+        assert_eq!(diff.severity(), Severity::AbiBreaking);
+        assert_eq!(
+            diff.to_string(),
+            "ABI-breaking changes:
+  changed type of parameter `Test.Widget.move.x` from `gint` to `gdouble`
+  changed transfer ownership of parameter `Test.Widget.move.label` from `none` to `full`
+  changed direction of parameter `Test.Widget.move.position` from `out` to `inout`
+  removed virtual method `Test.Widget.size`
+  removed implemented interface `Test.Widget.Gtk.Accessible`
+  added prerequisite `Test.Scrollable.Gtk.Widget`
+  changed type of field `Test.Rect.flags` from `guint:2` to `guint:3`
+  changed type of field `Test.Rect.data` from `union { i: gint; p: gpointer }` to `union { i: gint; p: guint64 }`
+  added field `Test.Rect.z`
+  changed layout of record `Test.Rect` from `x, y, flags, data` to `y, x, flags, data`
+  changed type of field `Test.WidgetClass.draw` from `fn(Widget) -> none` to `fn(Widget, cairo.Context) -> none`
+
+API-breaking changes:
+  changed nullable of parameter `Test.Widget.draw.cr` from `false` to `true`
+  removed prerequisite `Test.Scrollable.Gio.ListModel`
+  removed member `Test.Align.end`
+
+Compatible changes:
+  changed nullable of return value `Test.Widget.draw.return` from `false` to `true`
+  added virtual method `Test.Widget.measure`
+  added implemented interface `Test.Widget.Gio.ListModel`
+"
+        );
+    }
+
+    #[test]
+    fn diff_namespace() {
+        use crate::diff::{ApiDiff, Severity};
+
+        let old = Repository::from_str(
+            r#"<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <alias name="Id" c:type="TestId"><type name="gint"/></alias>
+    <alias name="Size" c:type="TestSize"><type name="gsize"/></alias>
+    <class name="Widget" parent="Object" glib:type-name="TestWidget" glib:get-type="test_widget_get_type"/>
+    <class name="Object" glib:type-name="TestObject" glib:get-type="test_object_get_type"/>
+    <glib:boxed glib:name="Token" glib:type-name="TestToken" glib:get-type="test_token_get_type">
+      <function name="token_new" c:identifier="test_token_new">
+        <return-value><type name="Token"/></return-value>
+      </function>
+    </glib:boxed>
+    <glib:boxed glib:name="Handle" glib:type-name="TestHandle" glib:get-type="test_handle_get_type"/>
+    <function-inline name="max" c:identifier="test_max">
+      <return-value><type name="gint"/></return-value>
+      <parameters>
+        <parameter name="a"><type name="gint"/></parameter>
+        <parameter name="b"><type name="gint"/></parameter>
+      </parameters>
+    </function-inline>
+    <function-inline name="min" c:identifier="test_min">
+      <return-value><type name="gint"/></return-value>
+    </function-inline>
+    <function-macro name="CHECK" c:identifier="TEST_CHECK">
+      <parameters>
+        <parameter name="value"/>
+      </parameters>
+    </function-macro>
+  </namespace>
+</repository>"#,
+        )
+        .unwrap();
+        let new = Repository::from_str(
+            r#"<repository version="1.2">
+  <namespace name="Test" version="1.0">
+    <alias name="Id" c:type="TestId"><type name="gint64"/></alias>
+    <class name="Widget" parent="Test.Object" glib:type-name="TestWidget" glib:get-type="test_widget_get_type"/>
+    <class name="Object" glib:type-name="TestObject" glib:get-type="test_object_get_type"/>
+    <glib:boxed glib:name="Token" glib:type-name="TestToken" glib:get-type="test_token_get_type">
+      <function name="token_new" c:identifier="test_token_new">
+        <return-value><type name="Token"/></return-value>
+      </function>
+      <function name="token_copy" c:identifier="test_token_copy">
+        <return-value><type name="Token"/></return-value>
+      </function>
+    </glib:boxed>
+    <function-inline name="max" c:identifier="test_max">
+      <return-value><type name="gint"/></return-value>
+      <parameters>
+        <parameter name="a"><type name="gint64"/></parameter>
+        <parameter name="b"><type name="gint"/></parameter>
+      </parameters>
+    </function-inline>
+    <function-macro name="CHECK" c:identifier="TEST_CHECK">
+      <parameters>
+        <parameter name="value"/>
+        <parameter name="message"/>
+      </parameters>
+    </function-macro>
+    <function-macro name="ASSERT" c:identifier="TEST_ASSERT"/>
+  </namespace>
+</repository>"#,
+        )
+        .unwrap();
+
+        let diff = ApiDiff::new(&old, &new);
+        assert_eq!(diff.severity(), Severity::AbiBreaking);
+        assert_eq!(
+            diff.to_string(),
+            "ABI-breaking changes:
+  changed type of alias `Test.Id` from `gint` to `gint64`
+  removed boxed type `Test.Handle`
+
+API-breaking changes:
+  removed alias `Test.Size`
+  changed type of parameter `Test.max.a` from `gint` to `gint64`
+  removed inline function `Test.min`
+  changed number of parameters of function macro `Test.CHECK` from `1` to `2`
+
+Compatible changes:
+  added function `Test.Token.token_copy`
+  added function macro `Test.ASSERT`
+"
+        );
+    }
+
+    #[test]
+    fn version_index() {
+        use crate::{AnySymbol, Symbol, Version, VersionIndex};
+
+        let repository = Repository::from_str(
+            r#"<repository version="1.2">
+  <namespace name="Test" version="4.0">
+    <class name="Widget" version="4.2" glib:type-name="TestWidget" glib:get-type="test_widget_get_type">
+      <method name="show" c:identifier="test_widget_show">
+        <return-value><type name="none"/></return-value>
+      </method>
+      <method name="hide" c:identifier="test_widget_hide" version="4.12">
+        <return-value><type name="none"/></return-value>
+      </method>
+      <method name="map" c:identifier="test_widget_map" version="4.1">
+        <return-value><type name="none"/></return-value>
+      </method>
+      <property name="label" version="4.12.0"><type name="utf8"/></property>
+    </class>
+    <function name="init" c:identifier="test_init">
+      <return-value><type name="none"/></return-value>
+    </function>
+  </namespace>
+</repository>"#,
+        )
+        .unwrap();
+        let index = VersionIndex::new(repository.namespace());
+        assert_eq!(index.baseline().to_string(), "4.0");
+        assert_eq!(
+            index.versions().map(|v| v.to_string()).collect::<Vec<_>>(),
+            ["4.0", "4.2", "4.12"]
+        );
+        let names = |version: &str| {
+            index
+                .since(version.parse().unwrap())
+                .map(|symbol| symbol.name().to_owned())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names("4"), ["Test.init"]);
+        assert_eq!(
+            names("4.2"),
+            ["Test.Widget", "Test.Widget.show", "Test.Widget.map"]
+        );
+        assert_eq!(names("4.12"), ["Test.Widget.hide", "Test.Widget.label"]);
+        let label = index.since("4.12".parse().unwrap()).nth(1).unwrap();
+        assert!(matches!(label.symbol(), AnySymbol::Property(p) if p.name() == "label"));
+        let init = index.since("4".parse().unwrap()).next().unwrap();
+        assert!(matches!(
+            init.symbol(),
+            AnySymbol::Symbol(Symbol::Function(f)) if f.name() == "init"
+        ));
+        assert_eq!(label.declared_version(), Some(&Version::new(4, 12, 0)));
+
+        assert_eq!(
+            index
+                .conflicts()
+                .iter()
+                .map(|conflict| conflict.to_string())
+                .collect::<Vec<_>>(),
+            ["`Test.Widget.map` is available since 4.1, before `Test.Widget` which is available since 4.2"]
+        );
+
+        let repository = Repository::from_path("./gir-files/GLib-2.0.gir").unwrap();
+        let index = VersionIndex::new(repository.namespace());
+        let conflict = &index.conflicts()[0];
+        assert_eq!(conflict.name(), "GLib.Uri.escape_string");
+        assert_eq!(
+            conflict.container_version(),
+            Version::new(2, 66, 0).normalize()
+        );
+        assert!(index
+            .since(conflict.container_version())
+            .any(|symbol| symbol.name() == "GLib.Uri.escape_string"));
+    }
 }
@@ -0,0 +1,775 @@
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::{
+    prelude::*, Alias, AnyType, Array, BitField, Boxed, Callable as CallableNode, Callback, Class,
+    ClassField, Constant, Direction, Enumeration, Field, FieldType, Function, FunctionInline,
+    FunctionMacro, InstanceParameter, Interface, InterfaceField, Member, Method, Namespace,
+    Parameter, ParameterType, Parameters, ParseError, ParseWarning, ParserError, Property, Record,
+    RecordField, Repository, ReturnValue, Signal, TransferOwnership, Type, Union, UnionField,
+    Version, VirtualMethod,
+};
+
+/// Rules patching a [`Repository`] once loaded, read from an override file in
+/// the spirit of Vala's `.metadata` files, for the fixes bindings need to apply
+/// to GIR files they don't own.
+///
+/// Each line selects nodes by their path from the namespace, followed by the
+/// changes to apply to them:
+///
+/// ```text
+/// // Comments start with two slashes.
+/// Gtk.Widget.get_parent.return nullable=true
+/// Gtk.*.get_type* skip
+/// Gtk.Widget#class
+///   .show throws
+///   .set_parent.parent transfer-ownership=none
+/// ```
+///
+/// A path is made of the names of the nodes separated by dots, where `*` and
+/// `?` match any sequence of characters and any single character. A name can
+/// be followed by `#` and a kind, such as `#method` or `#property`, to only
+/// match the nodes of that kind. The return value of a callable is named
+/// `return`. A path starting with a dot continues the last path which didn't.
+///
+/// The changes are:
+///
+/// - `name=`: rename the node;
+/// - `skip`: skip a parameter or return value, or make any other node not
+///   introspectable;
+/// - `introspectable`, `deprecated`, `deprecated-version=` and `version=`;
+/// - `nullable`, `transfer-ownership=` and `type=` for parameters, return
+///   values and the typed nodes, where `type=name[]` makes an array of
+///   `name`. Arrays, callbacks and varargs can't be given a plain type;
+/// - `direction=`, `optional` and `caller-allocates` for parameters;
+/// - `throws` for callables.
+///
+/// Boolean changes are true unless given `=false` or `=0`.
+#[derive(Debug, Clone)]
+pub struct Metadata {
+    source: String,
+    path: Option<PathBuf>,
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    offset: usize,
+    selector: String,
+    segments: Vec<Segment>,
+    changes: Vec<(usize, Change)>,
+}
+
+#[derive(Debug, Clone)]
+struct Segment {
+    pattern: String,
+    kind: Option<&'static str>,
+}
+
+#[derive(Debug, Clone)]
+enum Change {
+    Name(String),
+    Skip(bool),
+    Introspectable(bool),
+    Deprecated(bool),
+    DeprecatedVersion(Version),
+    Version(Version),
+    Nullable(bool),
+    TransferOwnership(TransferOwnership),
+    Type(String),
+    Direction(Direction),
+    Optional(bool),
+    CallerAllocates(bool),
+    Throws(bool),
+}
+
+const KINDS: &[&str] = &[
+    "namespace",
+    "alias",
+    "bitfield",
+    "boxed",
+    "callback",
+    "class",
+    "constant",
+    "constructor",
+    "enumeration",
+    "field",
+    "function",
+    "function-inline",
+    "function-macro",
+    "instance-parameter",
+    "interface",
+    "member",
+    "method",
+    "parameter",
+    "property",
+    "record",
+    "return-value",
+    "signal",
+    "union",
+    "virtual-method",
+];
+
+impl Metadata {
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ParserError> {
+        let source = std::fs::read_to_string(path.as_ref())?;
+        Ok(Self::parse(source, Some(path.as_ref()))?)
+    }
+
+    fn parse(source: String, path: Option<&Path>) -> Result<Self, ParseError> {
+        let error = |offset, message: String| {
+            ParseError::new(&source, path, offset, String::new(), message)
+        };
+        let mut rules = Vec::new();
+        let mut parent: Option<String> = None;
+        let mut line_start = 0;
+        for line in source.split_inclusive('\n') {
+            let offset = line_start;
+            line_start += line.len();
+            let mut tokens = tokens(line).map(|(start, token)| (offset + start, token));
+            let Some((offset, selector)) = tokens.next() else {
+                continue;
+            };
+            let selector = match (selector.strip_prefix('.'), &parent) {
+                (Some(_), None) => {
+                    return Err(error(offset, "relative path without a parent".to_owned()));
+                }
+                (Some(_), Some(parent)) => format!("{parent}{selector}"),
+                (None, _) => {
+                    parent = Some(selector.to_owned());
+                    selector.to_owned()
+                }
+            };
+            let segments = selector
+                .split('.')
+                .map(Segment::parse)
+                .collect::<Result<_, _>>()
+                .map_err(|message| error(offset, message))?;
+            let changes = tokens
+                .map(|(offset, token)| match Change::parse(token) {
+                    Ok(change) => Ok((offset, change)),
+                    Err(message) => Err((offset, message)),
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|(offset, message)| error(offset, message))?;
+            if !changes.is_empty() {
+                rules.push(Rule {
+                    offset,
+                    selector,
+                    segments,
+                    changes,
+                });
+            }
+        }
+        Ok(Self {
+            path: path.map(Path::to_path_buf),
+            source,
+            rules,
+        })
+    }
+
+    /// Apply the rules to `repository`, in order. The rules selecting no node
+    /// and the changes which don't apply to the selected nodes are reported
+    /// as warnings, pointing to the override file.
+    pub fn apply(&self, repository: &mut Repository) -> Vec<ParseWarning> {
+        let mut warnings = Vec::new();
+        for rule in &self.rules {
+            let mut matched = false;
+            let mut unsupported: Vec<(usize, &'static str)> = Vec::new();
+            let namespace = Node::Namespace(repository.namespace_mut());
+            let (first, rest) = rule.segments.split_first().expect("paths aren't empty");
+            if first.matches(&namespace) {
+                visit(namespace, rest, &mut |node| {
+                    matched = true;
+                    for (offset, change) in &rule.changes {
+                        let kind = node.kind();
+                        if !node.apply(change) && !unsupported.contains(&(*offset, kind)) {
+                            unsupported.push((*offset, kind));
+                        }
+                    }
+                });
+            }
+            if !matched {
+                warnings.push(self.warning(rule, rule.offset, "path matched nothing".to_owned()));
+            }
+            for (offset, kind) in unsupported {
+                let change = tokens(&self.source[offset..]).next().map_or("", |(_, t)| t);
+                let message = format!("`{change}` doesn't apply to {kind} nodes");
+                warnings.push(self.warning(rule, offset, message));
+            }
+        }
+        warnings
+    }
+
+    fn warning(&self, rule: &Rule, offset: usize, message: String) -> ParseWarning {
+        ParseWarning::new(ParseError::new(
+            &self.source,
+            self.path.as_deref(),
+            offset,
+            rule.selector.clone(),
+            message,
+        ))
+    }
+}
+
+impl FromStr for Metadata {
+    type Err = ParserError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::parse(s.to_owned(), None)?)
+    }
+}
+
+/// Split a line in whitespace separated tokens, up to a `//` comment. Double
+/// quotes keep the whitespace in a token.
+fn tokens(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut rest = line;
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        let trimmed = rest.trim_start();
+        offset += rest.len() - trimmed.len();
+        rest = trimmed;
+        if rest.is_empty() || rest.starts_with("//") {
+            return None;
+        }
+        let mut quoted = false;
+        let end = rest
+            .char_indices()
+            .find(|&(_, c)| {
+                quoted ^= c == '"';
+                !quoted && c.is_whitespace()
+            })
+            .map_or(rest.len(), |(end, _)| end);
+        let token = (offset, &rest[..end]);
+        offset += end;
+        rest = &rest[end..];
+        Some(token)
+    })
+}
+
+impl Segment {
+    fn parse(segment: &str) -> Result<Self, String> {
+        let (pattern, kind) = match segment.split_once('#') {
+            Some((pattern, kind)) => match KINDS.iter().find(|known| **known == kind) {
+                Some(kind) => (pattern, Some(*kind)),
+                None => return Err(format!("unknown node kind `{kind}`")),
+            },
+            None => (segment, None),
+        };
+        if pattern.is_empty() {
+            return Err(format!("empty name in `{segment}`"));
+        }
+        Ok(Self {
+            pattern: pattern.to_owned(),
+            kind,
+        })
+    }
+
+    fn matches(&self, node: &Node<'_>) -> bool {
+        self.kind.is_none_or(|kind| kind == node.kind())
+            && node.name().is_some_and(|name| glob(&self.pattern, name))
+    }
+}
+
+/// Match `name` against a pattern where `*` matches any sequence of
+/// characters and `?` any single character.
+fn glob(pattern: &str, name: &str) -> bool {
+    match pattern.chars().next() {
+        None => name.is_empty(),
+        Some('*') => {
+            let pattern = &pattern[1..];
+            name.char_indices()
+                .map(|(index, _)| index)
+                .chain([name.len()])
+                .any(|index| glob(pattern, &name[index..]))
+        }
+        Some(c) => {
+            let mut chars = name.chars();
+            match chars.next() {
+                Some(first) if c == '?' || c == first => {
+                    glob(&pattern[c.len_utf8()..], chars.as_str())
+                }
+                _ => false,
+            }
+        }
+    }
+}
+
+impl Change {
+    fn parse(token: &str) -> Result<Self, String> {
+        let (key, value) = match token.split_once('=') {
+            Some((key, value)) => (key, Some(value.trim_matches('"'))),
+            None => (token, None),
+        };
+        let boolean = || match value {
+            None | Some("true" | "1") => Ok(true),
+            Some("false" | "0") => Ok(false),
+            Some(value) => Err(format!("invalid boolean `{value}` for `{key}`")),
+        };
+        let string = || match value {
+            Some(value) if !value.is_empty() => Ok(value),
+            _ => Err(format!("`{key}` needs a value")),
+        };
+        let version = || string().and_then(Version::from_str);
+        match key {
+            "name" => string().map(|value| Self::Name(value.to_owned())),
+            "skip" => boolean().map(Self::Skip),
+            "introspectable" => boolean().map(Self::Introspectable),
+            "deprecated" => boolean().map(Self::Deprecated),
+            "deprecated-version" => version().map(Self::DeprecatedVersion),
+            "version" => version().map(Self::Version),
+            "nullable" => boolean().map(Self::Nullable),
+            "transfer-ownership" => string()
+                .and_then(TransferOwnership::deserialize)
+                .map(Self::TransferOwnership),
+            "type" => string().map(|value| Self::Type(value.to_owned())),
+            "direction" => string()
+                .and_then(Direction::deserialize)
+                .map(Self::Direction),
+            "optional" => boolean().map(Self::Optional),
+            "caller-allocates" => boolean().map(Self::CallerAllocates),
+            "throws" => boolean().map(Self::Throws),
+            _ => Err(format!("unknown change `{key}`")),
+        }
+    }
+}
+
+/// A node of a namespace which rules can select.
+enum Node<'a> {
+    Namespace(&'a mut Namespace),
+    Alias(&'a mut Alias),
+    BitField(&'a mut BitField),
+    Boxed(&'a mut Boxed),
+    Callback(&'a mut Callback),
+    Class(&'a mut Class),
+    Constant(&'a mut Constant),
+    Constructor(&'a mut Function),
+    Enumeration(&'a mut Enumeration),
+    Field(&'a mut Field),
+    Function(&'a mut Function),
+    FunctionInline(&'a mut FunctionInline),
+    FunctionMacro(&'a mut FunctionMacro),
+    InstanceParameter(&'a mut InstanceParameter),
+    Interface(&'a mut Interface),
+    Member(&'a mut Member),
+    Method(&'a mut Method),
+    Parameter(&'a mut Parameter),
+    Property(&'a mut Property),
+    Record(&'a mut Record),
+    ReturnValue(&'a mut ReturnValue),
+    Signal(&'a mut Signal),
+    Union(&'a mut Union),
+    VirtualMethod(&'a mut VirtualMethod),
+}
+
+/// Run `$body` for the nodes which are [`Info`], binding them to `$node`.
+macro_rules! with_info {
+    ($self:expr, $node:ident => $body:expr) => {
+        match $self {
+            Node::Alias($node) => $body,
+            Node::BitField($node) => $body,
+            Node::Boxed($node) => $body,
+            Node::Callback($node) => $body,
+            Node::Class($node) => $body,
+            Node::Constant($node) => $body,
+            Node::Constructor($node) | Node::Function($node) => $body,
+            Node::FunctionInline($node) => $body,
+            Node::FunctionMacro($node) => $body,
+            Node::Enumeration($node) => $body,
+            Node::Field($node) => $body,
+            Node::Interface($node) => $body,
+            Node::Member($node) => $body,
+            Node::Method($node) => $body,
+            Node::Property($node) => $body,
+            Node::Record($node) => $body,
+            Node::Signal($node) => $body,
+            Node::Union($node) => $body,
+            Node::VirtualMethod($node) => $body,
+            Node::Namespace(_)
+            | Node::InstanceParameter(_)
+            | Node::Parameter(_)
+            | Node::ReturnValue(_) => return false,
+        }
+    };
+}
+
+/// Pass each of `$nodes` to `$f` as a `$variant` node.
+macro_rules! each {
+    ($f:expr, $variant:ident, $nodes:expr) => {
+        for node in $nodes {
+            $f(Node::$variant(node));
+        }
+    };
+}
+
+/// Pass the return value and the parameters of a callable to `$f`.
+macro_rules! callable_children {
+    ($callable:expr, $f:expr) => {{
+        $f(Node::ReturnValue($callable.return_value_mut()));
+        parameters($callable.parameters_mut(), $f);
+    }};
+}
+
+fn parameters(parameters: &mut Parameters, f: &mut dyn FnMut(Node<'_>)) {
+    if let Some(instance) = parameters.instance_mut() {
+        f(Node::InstanceParameter(instance));
+    }
+    for parameter in parameters.inner_mut() {
+        f(Node::Parameter(parameter));
+    }
+}
+
+fn callables(callables: &mut [CallableNode], f: &mut dyn FnMut(Node<'_>)) {
+    for callable in callables {
+        f(match callable {
+            CallableNode::Constructor(function) => Node::Constructor(function),
+            CallableNode::Function(function) => Node::Function(function),
+            CallableNode::Method(method) => Node::Method(method),
+        });
+    }
+}
+
+/// Pass the nodes of the `fields` of a compound type to `f`.
+macro_rules! compound_fields {
+    ($fields:expr, $field_type:ident, $f:expr) => {
+        for field in $fields {
+            $f(match field {
+                $field_type::Field(field) => Node::Field(field),
+                $field_type::Union(union) => Node::Union(union),
+                $field_type::Record(record) => Node::Record(record),
+                $field_type::Callback(callback) => Node::Callback(callback),
+            });
+        }
+    };
+}
+
+impl Node<'_> {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Namespace(_) => "namespace",
+            Self::Alias(_) => "alias",
+            Self::BitField(_) => "bitfield",
+            Self::Boxed(_) => "boxed",
+            Self::Callback(_) => "callback",
+            Self::Class(_) => "class",
+            Self::Constant(_) => "constant",
+            Self::Constructor(_) => "constructor",
+            Self::Enumeration(_) => "enumeration",
+            Self::Field(_) => "field",
+            Self::Function(_) => "function",
+            Self::FunctionInline(_) => "function-inline",
+            Self::FunctionMacro(_) => "function-macro",
+            Self::InstanceParameter(_) => "instance-parameter",
+            Self::Interface(_) => "interface",
+            Self::Member(_) => "member",
+            Self::Method(_) => "method",
+            Self::Parameter(_) => "parameter",
+            Self::Property(_) => "property",
+            Self::Record(_) => "record",
+            Self::ReturnValue(_) => "return-value",
+            Self::Signal(_) => "signal",
+            Self::Union(_) => "union",
+            Self::VirtualMethod(_) => "virtual-method",
+        }
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some(match self {
+            Self::Namespace(node) => node.name(),
+            Self::Alias(node) => node.name(),
+            Self::BitField(node) => node.name(),
+            Self::Boxed(node) => node.g_name(),
+            Self::Callback(node) => node.name(),
+            Self::Class(node) => node.name(),
+            Self::Constant(node) => node.name(),
+            Self::Constructor(node) | Self::Function(node) => node.name(),
+            Self::FunctionInline(node) => node.name(),
+            Self::FunctionMacro(node) => node.name(),
+            Self::Enumeration(node) => node.name(),
+            Self::Field(node) => node.name(),
+            Self::InstanceParameter(node) => node.name(),
+            Self::Interface(node) => node.name(),
+            Self::Member(node) => node.name(),
+            Self::Method(node) => node.name(),
+            Self::Parameter(node) => node.name(),
+            Self::Property(node) => node.name(),
+            Self::Record(node) => return node.name(),
+            Self::ReturnValue(_) => "return",
+            Self::Signal(node) => node.name(),
+            Self::Union(node) => return node.name(),
+            Self::VirtualMethod(node) => node.name(),
+        })
+    }
+
+    fn for_each_child(&mut self, f: &mut dyn FnMut(Node<'_>)) {
+        match self {
+            Self::Namespace(namespace) => {
+                each!(f, Alias, namespace.aliases_mut());
+                each!(f, Class, namespace.classes_mut());
+                each!(f, Interface, namespace.interfaces_mut());
+                each!(f, Record, namespace.records_mut());
+                each!(f, Union, namespace.unions_mut());
+                each!(f, Enumeration, namespace.enums_mut());
+                each!(f, BitField, namespace.flags_mut());
+                each!(f, Boxed, namespace.boxed_mut());
+                each!(f, Callback, namespace.callbacks_mut());
+                each!(f, Constant, namespace.constants_mut());
+                each!(f, Function, namespace.functions_mut());
+                each!(f, FunctionInline, namespace.inlined_functions_mut());
+                each!(f, FunctionMacro, namespace.macros_mut());
+            }
+            Self::Class(class) => {
+                callables(class.callables_mut(), f);
+                each!(f, VirtualMethod, class.virtual_methods_mut());
+                each!(f, Property, class.properties_mut());
+                each!(f, Signal, class.signals_mut());
+                each!(f, Constant, class.constants_mut());
+                compound_fields!(class.fields_mut(), ClassField, f);
+            }
+            Self::Interface(interface) => {
+                callables(interface.callables_mut(), f);
+                each!(f, VirtualMethod, interface.virtual_methods_mut());
+                each!(f, Property, interface.properties_mut());
+                each!(f, Signal, interface.signals_mut());
+                each!(f, Constant, interface.constants_mut());
+                compound_fields!(interface.fields_mut(), InterfaceField, f);
+            }
+            Self::Record(record) => {
+                callables(record.callables_mut(), f);
+                compound_fields!(record.fields_mut(), RecordField, f);
+            }
+            Self::Union(union) => {
+                callables(union.callables_mut(), f);
+                compound_fields!(union.fields_mut(), UnionField, f);
+            }
+            Self::Enumeration(enumeration) => {
+                each!(f, Member, enumeration.members_mut());
+                each!(f, Function, enumeration.functions_mut());
+            }
+            Self::BitField(bitfield) => {
+                each!(f, Member, bitfield.members_mut());
+                each!(f, Function, bitfield.functions_mut());
+            }
+            Self::Boxed(boxed) => {
+                each!(f, Function, boxed.functions_mut());
+            }
+            Self::Callback(callback) => callable_children!(callback, f),
+            Self::Constructor(function) | Self::Function(function) => {
+                callable_children!(function, f)
+            }
+            Self::FunctionInline(function) => callable_children!(function, f),
+            Self::FunctionMacro(function) => parameters(function.parameters_mut(), f),
+            Self::Method(method) => callable_children!(method, f),
+            Self::Signal(signal) => callable_children!(signal, f),
+            Self::VirtualMethod(method) => callable_children!(method, f),
+            Self::Alias(_)
+            | Self::Constant(_)
+            | Self::Field(_)
+            | Self::InstanceParameter(_)
+            | Self::Member(_)
+            | Self::Parameter(_)
+            | Self::Property(_)
+            | Self::ReturnValue(_) => {}
+        }
+    }
+
+    /// Apply `change` to the node, returning whether it applies to it.
+    fn apply(&mut self, change: &Change) -> bool {
+        match change {
+            Change::Name(name) => return self.rename(name),
+            Change::Skip(skip) => match self {
+                Self::Parameter(node) => *node.skip_mut() = Some(*skip),
+                Self::ReturnValue(node) => *node.skip_mut() = Some(*skip),
+                Self::InstanceParameter(_) | Self::Namespace(_) => return false,
+                node => with_info!(node, node => *node.introspectable_mut() = Some(!skip)),
+            },
+            Change::Introspectable(introspectable) => match self {
+                Self::Parameter(node) => *node.introspectable_mut() = Some(*introspectable),
+                Self::ReturnValue(node) => *node.introspectable_mut() = Some(*introspectable),
+                node => {
+                    with_info!(node, node => *node.introspectable_mut() = Some(*introspectable))
+                }
+            },
+            Change::Deprecated(deprecated) => {
+                with_info!(self, node => *node.deprecated_mut() = Some(*deprecated))
+            }
+            Change::DeprecatedVersion(version) => {
+                with_info!(self, node => *node.deprecated_version_mut() = Some(*version))
+            }
+            Change::Version(version) => {
+                with_info!(self, node => *node.version_mut() = Some(*version))
+            }
+            Change::Nullable(nullable) => match self {
+                Self::Parameter(node) => *node.nullable_mut() = Some(*nullable),
+                Self::InstanceParameter(node) => *node.nullable_mut() = Some(*nullable),
+                Self::ReturnValue(node) => *node.nullable_mut() = Some(*nullable),
+                _ => return false,
+            },
+            Change::TransferOwnership(transfer) => match self {
+                Self::Parameter(node) => *node.transfer_ownership_mut() = Some(*transfer),
+                Self::InstanceParameter(node) => *node.transfer_ownership_mut() = Some(*transfer),
+                Self::ReturnValue(node) => *node.transfer_ownership_mut() = Some(*transfer),
+                Self::Property(node) => *node.transfer_ownership_mut() = Some(*transfer),
+                _ => return false,
+            },
+            Change::Type(name) => return self.retype(name),
+            Change::Direction(direction) => match self {
+                Self::Parameter(node) => *node.direction_mut() = Some(*direction),
+                Self::InstanceParameter(node) => *node.direction_mut() = Some(*direction),
+                _ => return false,
+            },
+            Change::Optional(optional) => match self {
+                Self::Parameter(node) => *node.optional_mut() = Some(*optional),
+                _ => return false,
+            },
+            Change::CallerAllocates(caller_allocates) => match self {
+                Self::Parameter(node) => *node.caller_allocates_mut() = Some(*caller_allocates),
+                Self::InstanceParameter(node) => {
+                    *node.caller_allocates_mut() = Some(*caller_allocates)
+                }
+                _ => return false,
+            },
+            Change::Throws(throws) => match self {
+                Self::Callback(node) => *node.throws_mut() = Some(*throws),
+                Self::Constructor(node) | Self::Function(node) => {
+                    *node.throws_mut() = Some(*throws)
+                }
+                Self::FunctionInline(node) => *node.throws_mut() = Some(*throws),
+                Self::FunctionMacro(node) => *node.throws_mut() = Some(*throws),
+                Self::Method(node) => *node.throws_mut() = Some(*throws),
+                Self::VirtualMethod(node) => *node.throws_mut() = Some(*throws),
+                _ => return false,
+            },
+        }
+        true
+    }
+
+    fn rename(&mut self, name: &str) -> bool {
+        let name = name.to_owned();
+        match self {
+            Self::Namespace(node) => *node.name_mut() = name,
+            Self::Alias(node) => *node.name_mut() = name,
+            Self::BitField(node) => *node.name_mut() = name,
+            Self::Boxed(node) => *node.g_name_mut() = name,
+            Self::Callback(node) => *node.name_mut() = name,
+            Self::Class(node) => *node.name_mut() = name,
+            Self::Constant(node) => *node.name_mut() = name,
+            Self::Constructor(node) | Self::Function(node) => *node.name_mut() = name,
+            Self::FunctionInline(node) => *node.name_mut() = name,
+            Self::FunctionMacro(node) => *node.name_mut() = name,
+            Self::Enumeration(node) => *node.name_mut() = name,
+            Self::Field(node) => *node.name_mut() = name,
+            Self::InstanceParameter(node) => *node.name_mut() = name.into(),
+            Self::Interface(node) => *node.name_mut() = name,
+            Self::Member(node) => *node.name_mut() = name,
+            Self::Method(node) => *node.name_mut() = name,
+            Self::Parameter(node) => *node.name_mut() = name.into(),
+            Self::Property(node) => *node.name_mut() = name,
+            Self::Record(node) => *node.name_mut() = Some(name),
+            Self::ReturnValue(_) => return false,
+            Self::Signal(node) => *node.name_mut() = name,
+            Self::Union(node) => *node.name_mut() = Some(name),
+            Self::VirtualMethod(node) => *node.name_mut() = name,
+        }
+        true
+    }
+
+    /// Replace the type of the node by the type named `name`, or by an array
+    /// of it for `name[]`, returning whether it applies to the node.
+    fn retype(&mut self, name: &str) -> bool {
+        let ty = match self {
+            Self::Alias(node) => retyped(node.ty(), name).map(|ty| *node.ty_mut() = ty),
+            Self::Constant(node) => retyped(node.ty(), name).map(|ty| *node.ty_mut() = ty),
+            Self::Property(node) => retyped(node.ty(), name).map(|ty| *node.ty_mut() = ty),
+            Self::ReturnValue(node) => retyped(node.ty(), name).map(|ty| *node.ty_mut() = ty),
+            Self::Field(node) => {
+                let ty = match node.ty() {
+                    FieldType::Type(ty) => retyped(&ty.clone().into(), name),
+                    FieldType::Array(array) => retyped(&AnyType::Array(array.clone()), name),
+                    FieldType::Callback(_) => None,
+                };
+                ty.map(|ty| {
+                    *node.ty_mut() = match ty {
+                        AnyType::Type(ty) => FieldType::Type(ty),
+                        AnyType::Array(array) => FieldType::Array(array),
+                    }
+                })
+            }
+            Self::Parameter(node) => {
+                let ty = match node.ty() {
+                    Some(ParameterType::Type(ty)) => retyped(&ty.clone().into(), name),
+                    Some(ParameterType::Array(array)) => {
+                        retyped(&AnyType::Array(array.clone()), name)
+                    }
+                    Some(ParameterType::VarArgs) => None,
+                    None => retyped(&Type::builder().build().into(), name),
+                };
+                ty.map(|ty| *node.ty_mut() = Some(ty.into()))
+            }
+            Self::InstanceParameter(node) => {
+                let ty = node
+                    .ty()
+                    .cloned()
+                    .unwrap_or_else(|| Type::builder().build());
+                let Some(AnyType::Type(ty)) = retyped(&ty.into(), name) else {
+                    return false;
+                };
+                *node.ty_mut() = Some(ty);
+                Some(())
+            }
+            _ => None,
+        };
+        ty.is_some()
+    }
+}
+
+/// Replace `ty` by the type named `name`, or by an array of it for `name[]`,
+/// keeping its C type and, for arrays, their other attributes. Arrays aren't
+/// replaced by plain types, which would lose their length and elements.
+fn retyped(ty: &AnyType, name: &str) -> Option<AnyType> {
+    match (ty, name.strip_suffix("[]")) {
+        (AnyType::Type(ty), None) => {
+            let named = Type::builder().name(name);
+            Some(
+                match ty.c_type() {
+                    Some(c_type) => named.c_type(c_type),
+                    None => named,
+                }
+                .build()
+                .into(),
+            )
+        }
+        (AnyType::Type(ty), Some(element)) => {
+            let element = retyped(&Type::builder().build().into(), element)?;
+            let array = Array::builder(element);
+            Some(AnyType::Array(
+                match ty.c_type() {
+                    Some(c_type) => array.c_type(c_type),
+                    None => array,
+                }
+                .build(),
+            ))
+        }
+        (AnyType::Array(array), Some(element)) => {
+            let element = retyped(array.element(), element)?;
+            let mut array = array.clone();
+            *array.element_mut() = element;
+            Some(AnyType::Array(array))
+        }
+        (AnyType::Array(_), None) => None,
+    }
+}
+
+/// Pass the nodes under `node` matching `segments` to `apply`.
+fn visit(mut node: Node<'_>, segments: &[Segment], apply: &mut dyn FnMut(&mut Node<'_>)) {
+    let Some((segment, rest)) = segments.split_first() else {
+        return apply(&mut node);
+    };
+    node.for_each_child(&mut |child| {
+        if segment.matches(&child) {
+            visit(child, rest, apply);
+        }
+    });
+}
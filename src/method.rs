@@ -1,72 +1,52 @@
-use xmlserde_derives::XmlDeserialize;
-
 use crate::{
     attribute::Attribute,
     documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition},
+    extra::Extra,
     parameter::Parameters,
     prelude::*,
     return_value::ReturnValue,
+    span::NodeSpan,
+    tree::{FromNode, Node},
     version::Version,
+    writer::{Element, ToXml},
     Stability,
 };
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
-#[xmlserde(root = b"method")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Method {
-    #[xmlserde(name = b"glib:get-property", ty = "attr")]
     get_property: Option<String>,
-    #[xmlserde(name = b"glib:set-property", ty = "attr")]
     set_property: Option<String>,
 
     // Callable attributes
-    #[xmlserde(name = b"name", ty = "attr")]
     name: String,
-    #[xmlserde(name = b"c:identifier", ty = "attr")]
     c_identifier: Option<String>,
-    #[xmlserde(name = b"shadows", ty = "attr")]
     shadows: Option<String>,
-    #[xmlserde(name = b"shadowed-by", ty = "attr")]
     shadowed_by: Option<String>,
-    #[xmlserde(name = b"throws", ty = "attr")]
     throws: Option<bool>,
-    #[xmlserde(name = b"moved-to", ty = "attr")]
     moved_to: Option<String>,
-    #[xmlserde(name = b"glib:async-func", ty = "attr")]
     async_func: Option<String>,
-    #[xmlserde(name = b"glib:finish-func", ty = "attr")]
     finish_func: Option<String>,
-    #[xmlserde(name = b"glib:sync-func", ty = "attr")]
     sync_func: Option<String>,
     // Common attributes
-    #[xmlserde(name = b"introspectable", ty = "attr")]
     introspectable: Option<bool>,
-    #[xmlserde(name = b"deprecated", ty = "attr")]
     deprecated: Option<bool>,
-    #[xmlserde(name = b"version", ty = "attr")]
     version: Option<Version>,
-    #[xmlserde(name = b"deprecated-version", ty = "attr")]
     deprecated_version: Option<Version>,
-    #[xmlserde(name = b"stability", ty = "attr")]
     stability: Option<Stability>,
     // Documentation
-    #[xmlserde(name = b"doc", ty = "child")]
     doc: Option<Documentation>,
-    #[xmlserde(name = b"doc-deprecated", ty = "child")]
     doc_deprecated: Option<DocDeprecated>,
-    #[xmlserde(name = b"doc-stability", ty = "child")]
     doc_stability: Option<DocStability>,
-    #[xmlserde(name = b"doc-version", ty = "child")]
     doc_version: Option<DocVersion>,
-    #[xmlserde(name = b"source-position", ty = "child")]
     source_position: Option<SourcePosition>,
     // Attributes: 0 or more
-    #[xmlserde(name = b"attribute", ty = "child")]
     attributes: Vec<Attribute>,
-    #[xmlserde(name = b"return-value", ty = "child")]
     return_value: ReturnValue,
-    #[xmlserde(name = b"parameters", ty = "child", default = "Parameters::default")]
     parameters: Parameters,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Method {
@@ -85,59 +65,39 @@ impl_documentable!(Method);
 impl_callable!(Method);
 impl_function_like!(Method);
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
-#[xmlserde(root = b"method-inline")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MethodInline {
     // Callable attributes
-    #[xmlserde(name = b"name", ty = "attr")]
     name: String,
-    #[xmlserde(name = b"c:identifier", ty = "attr")]
     c_identifier: Option<String>,
-    #[xmlserde(name = b"shadows", ty = "attr")]
     shadows: Option<String>,
-    #[xmlserde(name = b"shadowed-by", ty = "attr")]
     shadowed_by: Option<String>,
-    #[xmlserde(name = b"throws", ty = "attr")]
     throws: Option<bool>,
-    #[xmlserde(name = b"moved-to", ty = "attr")]
     moved_to: Option<String>,
-    #[xmlserde(name = b"glib:async-func", ty = "attr")]
     async_func: Option<String>,
-    #[xmlserde(name = b"glib:finish-func", ty = "attr")]
     finish_func: Option<String>,
-    #[xmlserde(name = b"glib:sync-func", ty = "attr")]
     sync_func: Option<String>,
     // Common attributes
-    #[xmlserde(name = b"introspectable", ty = "attr")]
     introspectable: Option<bool>,
-    #[xmlserde(name = b"deprecated", ty = "attr")]
     deprecated: Option<bool>,
-    #[xmlserde(name = b"version", ty = "attr")]
     version: Option<Version>,
-    #[xmlserde(name = b"deprecated-version", ty = "attr")]
     deprecated_version: Option<Version>,
-    #[xmlserde(name = b"stability", ty = "attr")]
     stability: Option<Stability>,
     // Documentation
-    #[xmlserde(name = b"doc", ty = "child")]
     doc: Option<Documentation>,
-    #[xmlserde(name = b"doc-deprecated", ty = "child")]
     doc_deprecated: Option<DocDeprecated>,
-    #[xmlserde(name = b"doc-stability", ty = "child")]
     doc_stability: Option<DocStability>,
-    #[xmlserde(name = b"doc-version", ty = "child")]
     doc_version: Option<DocVersion>,
-    #[xmlserde(name = b"source-position", ty = "child")]
     source_position: Option<SourcePosition>,
     // Attributes: 0 or more
-    #[xmlserde(name = b"attribute", ty = "child")]
     attributes: Vec<Attribute>,
 
-    #[xmlserde(name = b"return-value", ty = "child")]
     return_value: ReturnValue,
-    #[xmlserde(name = b"parameters", ty = "child", default = "Parameters::default")]
     parameters: Parameters,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl_info!(MethodInline);
@@ -145,3 +105,175 @@ impl_attributable!(MethodInline);
 impl_documentable!(MethodInline);
 impl_callable!(MethodInline);
 impl_function_like!(MethodInline);
+
+impl_extensible!(Method);
+impl_spanned!(Method);
+impl_builder!(Method => MethodBuilder {
+    new {
+        name: String => name_mut;
+    }
+    optional {
+        get_property: String => get_property, get_property_mut;
+        set_property: String => set_property, set_property_mut;
+        c_identifier: String => c_identifier, c_identifier_mut;
+        shadows: String => shadows, shadows_mut;
+        shadowed_by: String => shadowed_by, shadowed_by_mut;
+        throws: bool => throws, throws_mut;
+        moved_to: String => moved_to, moved_to_mut;
+        async_func: String => async_func, async_func_mut;
+        finish_func: String => finish_func, finish_func_mut;
+        sync_func: String => sync_func, sync_func_mut;
+        introspectable: bool => introspectable, introspectable_mut;
+        deprecated: bool => deprecated, deprecated_mut;
+        version: Version => version, version_mut;
+        deprecated_version: Version => deprecated_version, deprecated_version_mut;
+        stability: Stability => stability, stability_mut;
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+    }
+    list {
+        attributes: Attribute => attribute, attributes_mut;
+    }
+    value {
+        return_value: ReturnValue => return_value, return_value_mut;
+        parameters: Parameters => parameters, parameters_mut;
+    }
+    hidden { span, extra }
+});
+impl_parameters_builder!(MethodBuilder);
+impl FromNode for Method {
+    fn from_node(node: Node) -> Self {
+        Self {
+            get_property: node.opt_attr("glib:get-property"),
+            set_property: node.opt_attr("glib:set-property"),
+            name: node.attr("name"),
+            c_identifier: node.opt_attr("c:identifier"),
+            shadows: node.opt_attr("shadows"),
+            shadowed_by: node.opt_attr("shadowed-by"),
+            throws: node.opt_attr("throws"),
+            moved_to: node.opt_attr("moved-to"),
+            async_func: node.opt_attr("glib:async-func"),
+            finish_func: node.opt_attr("glib:finish-func"),
+            sync_func: node.opt_attr("glib:sync-func"),
+            introspectable: node.opt_attr("introspectable"),
+            deprecated: node.opt_attr("deprecated"),
+            version: node.opt_attr("version"),
+            deprecated_version: node.opt_attr("deprecated-version"),
+            stability: node.opt_attr("stability"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            attributes: node.children("attribute"),
+            return_value: node.required("return-value"),
+            parameters: node.child("parameters").unwrap_or_default(),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Method {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .opt_attr("glib:get-property", &self.get_property)
+            .opt_attr("glib:set-property", &self.set_property)
+            .callable(self)
+            .info(self)
+            .documentation(self)
+            .attributes(self)
+            .child("return-value", &self.return_value)
+            .opt_child(
+                "parameters",
+                (!self.parameters.is_empty()).then_some(&self.parameters),
+            )
+            .extra(self)
+    }
+}
+
+impl_extensible!(MethodInline);
+impl_spanned!(MethodInline);
+impl_builder!(MethodInline => MethodInlineBuilder {
+    new {
+        name: String => name_mut;
+    }
+    optional {
+        c_identifier: String => c_identifier, c_identifier_mut;
+        shadows: String => shadows, shadows_mut;
+        shadowed_by: String => shadowed_by, shadowed_by_mut;
+        throws: bool => throws, throws_mut;
+        moved_to: String => moved_to, moved_to_mut;
+        async_func: String => async_func, async_func_mut;
+        finish_func: String => finish_func, finish_func_mut;
+        sync_func: String => sync_func, sync_func_mut;
+        introspectable: bool => introspectable, introspectable_mut;
+        deprecated: bool => deprecated, deprecated_mut;
+        version: Version => version, version_mut;
+        deprecated_version: Version => deprecated_version, deprecated_version_mut;
+        stability: Stability => stability, stability_mut;
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+    }
+    list {
+        attributes: Attribute => attribute, attributes_mut;
+    }
+    value {
+        return_value: ReturnValue => return_value, return_value_mut;
+        parameters: Parameters => parameters, parameters_mut;
+    }
+    hidden { span, extra }
+});
+impl_parameters_builder!(MethodInlineBuilder);
+impl FromNode for MethodInline {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            c_identifier: node.opt_attr("c:identifier"),
+            shadows: node.opt_attr("shadows"),
+            shadowed_by: node.opt_attr("shadowed-by"),
+            throws: node.opt_attr("throws"),
+            moved_to: node.opt_attr("moved-to"),
+            async_func: node.opt_attr("glib:async-func"),
+            finish_func: node.opt_attr("glib:finish-func"),
+            sync_func: node.opt_attr("glib:sync-func"),
+            introspectable: node.opt_attr("introspectable"),
+            deprecated: node.opt_attr("deprecated"),
+            version: node.opt_attr("version"),
+            deprecated_version: node.opt_attr("deprecated-version"),
+            stability: node.opt_attr("stability"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            attributes: node.children("attribute"),
+            return_value: node.required("return-value"),
+            parameters: node.child("parameters").unwrap_or_default(),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for MethodInline {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .callable(self)
+            .info(self)
+            .documentation(self)
+            .attributes(self)
+            .child("return-value", &self.return_value)
+            .opt_child(
+                "parameters",
+                (!self.parameters.is_empty()).then_some(&self.parameters),
+            )
+            .extra(self)
+    }
+}
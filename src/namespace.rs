@@ -1,5 +1,3 @@
-use xmlserde_derives::XmlDeserialize;
-
 use crate::{
     alias::Alias,
     attribute::Attribute,
@@ -8,64 +6,55 @@ use crate::{
     callback::Callback,
     class::Class,
     constant::Constant,
+    doc_section::DocSection,
     enums::Enumeration,
+    extra::Extra,
     function::{Function, FunctionInline},
     function_macro::FunctionMacro,
     interface::Interface,
     prelude::*,
     record::Record,
+    span::NodeSpan,
+    tree::{FromNode, Node},
     union::Union,
     version::Version,
+    writer::{Element, ToXml},
 };
 
-#[derive(Clone, Debug, XmlDeserialize)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(test, derive(Default))]
-#[xmlserde(root = b"namespace")]
-#[xmlserde(deny_unknown_fields)]
 pub struct Namespace {
-    #[xmlserde(name = b"name", ty = "attr")]
     name: String,
-    #[xmlserde(name = b"version", ty = "attr")]
     version: Version,
-    #[xmlserde(name = b"c:identifier-prefixes", ty = "attr")]
     c_identifier_prefixes: Option<String>,
     // Deprecated, backwards compatibility only
-    #[xmlserde(name = b"c:prefix", ty = "attr")]
     c_prefix: Option<String>,
-    #[xmlserde(name = b"c:symbol-prefixes", ty = "attr")]
     c_symbol_prefixes: Option<String>,
-    #[xmlserde(name = b"shared-library", ty = "attr")]
     shared_library: Option<String>,
 
-    #[xmlserde(name = b"alias", ty = "child")]
     aliases: Vec<Alias>,
-    #[xmlserde(name = b"class", ty = "child")]
     classes: Vec<Class>,
-    #[xmlserde(name = b"interface", ty = "child")]
     interfaces: Vec<Interface>,
-    #[xmlserde(name = b"record", ty = "child")]
     records: Vec<Record>,
-    #[xmlserde(name = b"enumeration", ty = "child")]
     enums: Vec<Enumeration>,
-    #[xmlserde(name = b"function", ty = "child")]
+    #[cfg_attr(feature = "serde", serde(rename = "functions"))]
     functions_global: Vec<Function>,
-    #[xmlserde(name = b"function-inline", ty = "child")]
+    #[cfg_attr(feature = "serde", serde(rename = "inlined_functions"))]
     inline_functions: Vec<FunctionInline>,
-    #[xmlserde(name = b"function-macro", ty = "child")]
+    #[cfg_attr(feature = "serde", serde(rename = "macros"))]
     functions_macro: Vec<FunctionMacro>,
-    #[xmlserde(name = b"union", ty = "child")]
     unions: Vec<Union>,
-    #[xmlserde(name = b"bitfield", ty = "child")]
     flags: Vec<BitField>,
-    #[xmlserde(name = b"callback", ty = "child")]
     callbacks: Vec<Callback>,
-    #[xmlserde(name = b"constant", ty = "child")]
     constants: Vec<Constant>,
     // Attributes: 0 or more
-    #[xmlserde(name = b"attribute", ty = "child")]
     attributes: Vec<Attribute>,
-    #[xmlserde(name = b"glib:boxed", ty = "child")]
     boxed: Vec<Boxed>,
+    doc_sections: Vec<DocSection>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Namespace {
@@ -136,6 +125,10 @@ impl Namespace {
         &self.boxed
     }
 
+    pub fn doc_sections(&self) -> &[DocSection] {
+        &self.doc_sections
+    }
+
     pub fn records(&self) -> &[Record] {
         &self.records
     }
@@ -175,6 +168,98 @@ impl Namespace {
 
 impl_attributable!(Namespace);
 
+impl_extensible!(Namespace);
+impl_spanned!(Namespace);
+impl_builder!(Namespace => NamespaceBuilder {
+    new {
+        name: String => name_mut;
+        version: Version => version_mut;
+    }
+    optional {
+        c_identifier_prefixes: String => c_identifier_prefixes, c_identifier_prefixes_mut;
+        c_prefix: String => c_prefix, c_prefix_mut;
+        c_symbol_prefixes: String => c_symbol_prefixes, c_symbol_prefixes_mut;
+        shared_library: String => shared_library, shared_library_mut;
+    }
+    list {
+        aliases: Alias => alias, aliases_mut;
+        classes: Class => class, classes_mut;
+        interfaces: Interface => interface, interfaces_mut;
+        records: Record => record, records_mut;
+        enums: Enumeration => enumeration, enums_mut;
+        functions_global: Function => function, functions_mut;
+        inline_functions: FunctionInline => inlined_function, inlined_functions_mut;
+        functions_macro: FunctionMacro => function_macro, macros_mut;
+        unions: Union => union, unions_mut;
+        flags: BitField => bitfield, flags_mut;
+        callbacks: Callback => callback, callbacks_mut;
+        constants: Constant => constant, constants_mut;
+        attributes: Attribute => attribute, attributes_mut;
+        boxed: Boxed => boxed, boxed_mut;
+        doc_sections: DocSection => doc_section, doc_sections_mut;
+    }
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for Namespace {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            version: node.attr("version"),
+            c_identifier_prefixes: node.opt_attr("c:identifier-prefixes"),
+            c_prefix: node.opt_attr("c:prefix"),
+            c_symbol_prefixes: node.opt_attr("c:symbol-prefixes"),
+            shared_library: node.opt_attr("shared-library"),
+            aliases: node.children("alias"),
+            classes: node.children("class"),
+            interfaces: node.children("interface"),
+            records: node.children("record"),
+            enums: node.children("enumeration"),
+            functions_global: node.children("function"),
+            inline_functions: node.children("function-inline"),
+            functions_macro: node.children("function-macro"),
+            unions: node.children("union"),
+            flags: node.children("bitfield"),
+            callbacks: node.children("callback"),
+            constants: node.children("constant"),
+            attributes: node.children("attribute"),
+            boxed: node.children("glib:boxed"),
+            doc_sections: node.children("docsection"),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Namespace {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .attr("name", &self.name)
+            .attr("version", &self.version)
+            .opt_attr("c:identifier-prefixes", &self.c_identifier_prefixes)
+            .opt_attr("c:prefix", &self.c_prefix)
+            .opt_attr("c:symbol-prefixes", &self.c_symbol_prefixes)
+            .opt_attr("shared-library", &self.shared_library)
+            .attributes(self)
+            .children("alias", &self.aliases)
+            .children("class", &self.classes)
+            .children("interface", &self.interfaces)
+            .children("record", &self.records)
+            .children("enumeration", &self.enums)
+            .children("function", &self.functions_global)
+            .children("function-inline", &self.inline_functions)
+            .children("function-macro", &self.functions_macro)
+            .children("union", &self.unions)
+            .children("bitfield", &self.flags)
+            .children("callback", &self.callbacks)
+            .children("constant", &self.constants)
+            .children("glib:boxed", &self.boxed)
+            .children("docsection", &self.doc_sections)
+            .extra(self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
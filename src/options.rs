@@ -0,0 +1,54 @@
+use crate::Interner;
+
+/// How GIR documents are parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Fail on attributes this crate doesn't know about, or with an invalid
+    /// value. When disabled, those attributes are reported as warnings along
+    /// with the unknown elements, so files produced by a newer
+    /// gobject-introspection can still be parsed. Unknown attributes are kept
+    /// in the [`Extensible::extra_attributes`] of their node, while invalid
+    /// values are skipped. In both modes, unknown elements are kept raw, along
+    /// with their content, in the [`Extensible::extra_children`] of their
+    /// parent.
+    ///
+    /// [`Extensible::extra_children`]: crate::prelude::Extensible::extra_children
+    /// [`Extensible::extra_attributes`]: crate::prelude::Extensible::extra_attributes
+    pub strict: bool,
+    /// Deduplicate the type names, C types and other repeated names through
+    /// an [`Interner`], which can be shared by all the files of a load.
+    pub interner: Option<Interner>,
+    /// Skip the documentation elements, along with the source positions,
+    /// while parsing, for tools which never read them.
+    /// [`Documentable`] then returns `None` for all of them.
+    ///
+    /// [`Documentable`]: crate::prelude::Documentable
+    pub skip_docs: bool,
+    /// Record where each node was found in the document, returned by
+    /// [`Spanned::span`]. Enabled by default, it can be disabled to save the
+    /// time spent computing them, in which case nodes have no span. Spans
+    /// are left out when comparing and hashing nodes.
+    ///
+    /// [`Spanned::span`]: crate::prelude::Spanned::span
+    pub spans: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            strict: true,
+            interner: None,
+            skip_docs: false,
+            spans: true,
+        }
+    }
+}
+
+impl ParseOptions {
+    pub fn lenient() -> Self {
+        Self {
+            strict: false,
+            ..Self::default()
+        }
+    }
+}
@@ -1,12 +1,16 @@
 use xmlserde::xml_serde_enum;
-use xmlserde_derives::XmlDeserialize;
 
 use crate::{
     array::Array,
     attribute::Attribute,
     documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition},
+    extra::Extra,
+    interner::Atom,
     prelude::*,
     r#type::Type,
+    span::NodeSpan,
+    tree::{FromNode, FromTag, Node},
+    writer::{Element, ToXml},
     FunctionScope, TransferOwnership,
 };
 
@@ -29,13 +33,16 @@ impl Direction {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+#[allow(clippy::large_enum_variant)]
 pub enum ParameterType {
-    #[xmlserde(name = b"type")]
     Type(Type),
-    #[xmlserde(name = b"array")]
     Array(Array),
-    #[xmlserde(name = b"varargs")]
     VarArgs,
 }
 
@@ -127,14 +134,16 @@ impl<'a> AnyParameter<'a> {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq, XmlDeserialize)]
-#[xmlserde(root = b"parameters")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Parameters {
-    #[xmlserde(name = b"instance-parameter", ty = "child")]
+    #[cfg_attr(feature = "serde", serde(rename = "instance_parameter"))]
     instance_parameter: Option<InstanceParameter>,
-    #[xmlserde(name = b"parameter", ty = "child")]
+    #[cfg_attr(feature = "serde", serde(rename = "parameters"))]
     parameter: Vec<Parameter>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Parameters {
@@ -199,50 +208,35 @@ impl std::ops::Index<usize> for Parameters {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
-#[xmlserde(root = b"parameter")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Parameter {
-    #[xmlserde(name = b"name", ty = "attr")]
-    name: String,
-    #[xmlserde(name = b"transfer-ownership", ty = "attr")]
+    name: Atom,
+    #[cfg_attr(feature = "serde", serde(rename = "transfer_ownership"))]
     transfer: Option<TransferOwnership>,
-    #[xmlserde(name = b"nullable", ty = "attr")]
     nullable: Option<bool>,
-    #[xmlserde(name = b"allow-none", ty = "attr")]
     allow_none: Option<bool>,
-    #[xmlserde(name = b"introspectable", ty = "attr")]
     introspectable: Option<bool>,
-    #[xmlserde(name = b"scope", ty = "attr")]
     scope: Option<FunctionScope>,
-    #[xmlserde(name = b"closure", ty = "attr")]
     closure: Option<usize>,
-    #[xmlserde(name = b"destroy", ty = "attr")]
     destroy: Option<usize>,
-    #[xmlserde(name = b"direction", ty = "attr")]
     direction: Option<Direction>,
-    #[xmlserde(name = b"caller-allocates", ty = "attr")]
     caller_allocates: Option<bool>,
-    #[xmlserde(name = b"optional", ty = "attr")]
     optional: Option<bool>,
-    #[xmlserde(name = b"skip", ty = "attr")]
     skip: Option<bool>,
     // Documentation
-    #[xmlserde(name = b"doc", ty = "child")]
     doc: Option<Documentation>,
-    #[xmlserde(name = b"doc-deprecated", ty = "child")]
     doc_deprecated: Option<DocDeprecated>,
-    #[xmlserde(name = b"doc-stability", ty = "child")]
     doc_stability: Option<DocStability>,
-    #[xmlserde(name = b"doc-version", ty = "child")]
     doc_version: Option<DocVersion>,
-    #[xmlserde(name = b"source-position", ty = "child")]
     source_position: Option<SourcePosition>,
     // Attributes: 0 or more
-    #[xmlserde(name = b"attribute", ty = "child")]
     attributes: Vec<Attribute>,
-    #[xmlserde(ty = "untag")]
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
     type_: Option<ParameterType>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Parameter {
@@ -314,35 +308,27 @@ impl Parameter {
 impl_attributable!(Parameter);
 impl_documentable!(Parameter);
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
-#[xmlserde(root = b"instance-parameter")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InstanceParameter {
-    #[xmlserde(name = b"name", ty = "attr")]
-    name: String,
-    #[xmlserde(name = b"transfer-ownership", ty = "attr")]
+    name: Atom,
+    #[cfg_attr(feature = "serde", serde(rename = "transfer_ownership"))]
     transfer: Option<TransferOwnership>,
-    #[xmlserde(name = b"nullable", ty = "attr")]
     nullable: Option<bool>,
-    #[xmlserde(name = b"allow-none", ty = "attr")]
     allow_none: Option<bool>,
-    #[xmlserde(name = b"direction", ty = "attr")]
     direction: Option<Direction>,
-    #[xmlserde(name = b"caller-allocates", ty = "attr")]
     caller_allocates: Option<bool>,
     // Documentation
-    #[xmlserde(name = b"doc", ty = "child")]
     doc: Option<Documentation>,
-    #[xmlserde(name = b"doc-deprecated", ty = "child")]
     doc_deprecated: Option<DocDeprecated>,
-    #[xmlserde(name = b"doc-stability", ty = "child")]
     doc_stability: Option<DocStability>,
-    #[xmlserde(name = b"doc-version", ty = "child")]
     doc_version: Option<DocVersion>,
-    #[xmlserde(name = b"source-position", ty = "child")]
     source_position: Option<SourcePosition>,
-    #[xmlserde(name = b"type", ty = "child")]
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
     type_: Option<Type>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl InstanceParameter {
@@ -376,3 +362,200 @@ impl InstanceParameter {
 }
 
 impl_documentable!(InstanceParameter);
+
+impl FromTag for ParameterType {
+    fn from_tag(node: Node) -> Option<Self> {
+        match node.tag() {
+            "type" => Some(Self::Type(Type::from_node(node))),
+            "array" => Some(Self::Array(Array::from_node(node))),
+            "varargs" => Some(Self::VarArgs),
+            _ => None,
+        }
+    }
+}
+
+impl ToXml for ParameterType {
+    fn to_xml(&self, _tag: &str) -> Element {
+        match self {
+            Self::Type(ty) => ty.to_xml("type"),
+            Self::Array(array) => array.to_xml("array"),
+            Self::VarArgs => Element::new("varargs"),
+        }
+    }
+}
+
+impl_extensible!(Parameters);
+impl_spanned!(Parameters);
+impl_builder!(Parameters => ParametersBuilder {
+    new {}
+    optional {
+        instance_parameter: InstanceParameter => instance, instance_mut;
+    }
+    list {
+        parameter: Parameter => parameter, inner_mut;
+    }
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for Parameters {
+    fn from_node(node: Node) -> Self {
+        Self {
+            instance_parameter: node.child("instance-parameter"),
+            parameter: node.children("parameter"),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Parameters {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .opt_child("instance-parameter", self.instance_parameter.as_ref())
+            .children("parameter", &self.parameter)
+            .extra(self)
+    }
+}
+
+impl_extensible!(Parameter);
+impl_spanned!(Parameter);
+impl_builder!(Parameter => ParameterBuilder {
+    new {
+        name: Atom => name_mut;
+    }
+    optional {
+        transfer: TransferOwnership => transfer_ownership, transfer_ownership_mut;
+        nullable: bool => nullable, nullable_mut;
+        allow_none: bool => allow_none, allow_none_mut;
+        introspectable: bool => introspectable, introspectable_mut;
+        scope: FunctionScope => scope, scope_mut;
+        closure: usize => closure, closure_mut;
+        destroy: usize => destroy, destroy_mut;
+        direction: Direction => direction, direction_mut;
+        caller_allocates: bool => caller_allocates, caller_allocates_mut;
+        optional: bool => optional, optional_mut;
+        skip: bool => skip, skip_mut;
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+        type_: ParameterType => ty, ty_mut;
+    }
+    list {
+        attributes: Attribute => attribute, attributes_mut;
+    }
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for Parameter {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            transfer: node.opt_attr("transfer-ownership"),
+            nullable: node.opt_attr("nullable"),
+            allow_none: node.opt_attr("allow-none"),
+            introspectable: node.opt_attr("introspectable"),
+            scope: node.opt_attr("scope"),
+            closure: node.opt_attr("closure"),
+            destroy: node.opt_attr("destroy"),
+            direction: node.opt_attr("direction"),
+            caller_allocates: node.opt_attr("caller-allocates"),
+            optional: node.opt_attr("optional"),
+            skip: node.opt_attr("skip"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            attributes: node.children("attribute"),
+            type_: node.opt_one_of(),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Parameter {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .attr("name", &self.name)
+            .opt_attr("transfer-ownership", &self.transfer)
+            .opt_attr("nullable", &self.nullable)
+            .opt_attr("allow-none", &self.allow_none)
+            .opt_attr("introspectable", &self.introspectable)
+            .opt_attr("scope", &self.scope)
+            .opt_attr("closure", &self.closure)
+            .opt_attr("destroy", &self.destroy)
+            .opt_attr("direction", &self.direction)
+            .opt_attr("caller-allocates", &self.caller_allocates)
+            .opt_attr("optional", &self.optional)
+            .opt_attr("skip", &self.skip)
+            .documentation(self)
+            .attributes(self)
+            .untagged(&self.type_)
+            .extra(self)
+    }
+}
+
+impl_extensible!(InstanceParameter);
+impl_spanned!(InstanceParameter);
+impl_builder!(InstanceParameter => InstanceParameterBuilder {
+    new {
+        name: Atom => name_mut;
+    }
+    optional {
+        transfer: TransferOwnership => transfer_ownership, transfer_ownership_mut;
+        nullable: bool => nullable, nullable_mut;
+        allow_none: bool => allow_none, allow_none_mut;
+        direction: Direction => direction, direction_mut;
+        caller_allocates: bool => caller_allocates, caller_allocates_mut;
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+        type_: Type => ty, ty_mut;
+    }
+    list {}
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for InstanceParameter {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            transfer: node.opt_attr("transfer-ownership"),
+            nullable: node.opt_attr("nullable"),
+            allow_none: node.opt_attr("allow-none"),
+            direction: node.opt_attr("direction"),
+            caller_allocates: node.opt_attr("caller-allocates"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            type_: node.child("type"),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for InstanceParameter {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .attr("name", &self.name)
+            .opt_attr("transfer-ownership", &self.transfer)
+            .opt_attr("nullable", &self.nullable)
+            .opt_attr("allow-none", &self.allow_none)
+            .opt_attr("direction", &self.direction)
+            .opt_attr("caller-allocates", &self.caller_allocates)
+            .documentation(self)
+            .opt_child("type", self.type_.as_ref())
+            .extra(self)
+    }
+}
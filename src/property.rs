@@ -1,62 +1,46 @@
-use xmlserde_derives::XmlDeserialize;
-
 use crate::{
     attribute::Attribute,
     documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition},
+    extra::Extra,
     prelude::*,
+    span::NodeSpan,
+    tree::{FromNode, Node},
     version::Version,
+    writer::{Element, ToXml},
     AnyType, Stability, TransferOwnership,
 };
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
-#[xmlserde(root = b"property")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Property {
-    #[xmlserde(name = b"name", ty = "attr")]
     name: String,
-    #[xmlserde(name = b"readable", ty = "attr")]
     readable: Option<bool>,
-    #[xmlserde(name = b"writable", ty = "attr")]
     writable: Option<bool>,
-    #[xmlserde(name = b"construct", ty = "attr")]
     construct: Option<bool>,
-    #[xmlserde(name = b"construct-only", ty = "attr")]
     construct_only: Option<bool>,
-    #[xmlserde(name = b"setter", ty = "attr")]
     setter: Option<String>,
-    #[xmlserde(name = b"getter", ty = "attr")]
     getter: Option<String>,
-    #[xmlserde(name = b"default-value", ty = "attr")]
     default_value: Option<String>,
-    #[xmlserde(name = b"transfer-ownership", ty = "attr")]
     transfer_ownership: Option<TransferOwnership>,
     // Common attributes
-    #[xmlserde(name = b"introspectable", ty = "attr")]
     introspectable: Option<bool>,
-    #[xmlserde(name = b"deprecated", ty = "attr")]
     deprecated: Option<bool>,
-    #[xmlserde(name = b"version", ty = "attr")]
     version: Option<Version>,
-    #[xmlserde(name = b"deprecated-version", ty = "attr")]
     deprecated_version: Option<Version>,
-    #[xmlserde(name = b"stability", ty = "attr")]
     stability: Option<Stability>,
     // Documentation
-    #[xmlserde(name = b"doc", ty = "child")]
     doc: Option<Documentation>,
-    #[xmlserde(name = b"doc-deprecated", ty = "child")]
     doc_deprecated: Option<DocDeprecated>,
-    #[xmlserde(name = b"doc-stability", ty = "child")]
     doc_stability: Option<DocStability>,
-    #[xmlserde(name = b"doc-version", ty = "child")]
     doc_version: Option<DocVersion>,
-    #[xmlserde(name = b"source-position", ty = "child")]
     source_position: Option<SourcePosition>,
     // Attributes: 0 or more
-    #[xmlserde(name = b"attribute", ty = "child")]
     attributes: Vec<Attribute>,
-    #[xmlserde(ty = "untag")]
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
     type_: AnyType,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Property {
@@ -104,3 +88,87 @@ impl Property {
 impl_info!(Property);
 impl_documentable!(Property);
 impl_attributable!(Property);
+
+impl_extensible!(Property);
+impl_spanned!(Property);
+impl_builder!(Property => PropertyBuilder {
+    new {
+        name: String => name_mut;
+        type_: AnyType => ty_mut;
+    }
+    optional {
+        readable: bool => readable, readable_mut;
+        writable: bool => writable, writable_mut;
+        construct: bool => construct, construct_mut;
+        construct_only: bool => construct_only, construct_only_mut;
+        setter: String => setter, setter_mut;
+        getter: String => getter, getter_mut;
+        default_value: String => default_value, default_value_mut;
+        transfer_ownership: TransferOwnership => transfer_ownership, transfer_ownership_mut;
+        introspectable: bool => introspectable, introspectable_mut;
+        deprecated: bool => deprecated, deprecated_mut;
+        version: Version => version, version_mut;
+        deprecated_version: Version => deprecated_version, deprecated_version_mut;
+        stability: Stability => stability, stability_mut;
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+    }
+    list {
+        attributes: Attribute => attribute, attributes_mut;
+    }
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for Property {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            readable: node.opt_attr("readable"),
+            writable: node.opt_attr("writable"),
+            construct: node.opt_attr("construct"),
+            construct_only: node.opt_attr("construct-only"),
+            setter: node.opt_attr("setter"),
+            getter: node.opt_attr("getter"),
+            default_value: node.opt_attr("default-value"),
+            transfer_ownership: node.opt_attr("transfer-ownership"),
+            introspectable: node.opt_attr("introspectable"),
+            deprecated: node.opt_attr("deprecated"),
+            version: node.opt_attr("version"),
+            deprecated_version: node.opt_attr("deprecated-version"),
+            stability: node.opt_attr("stability"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            attributes: node.children("attribute"),
+            type_: node.one_of(),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Property {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .attr("name", &self.name)
+            .opt_attr("readable", &self.readable)
+            .opt_attr("writable", &self.writable)
+            .opt_attr("construct", &self.construct)
+            .opt_attr("construct-only", &self.construct_only)
+            .opt_attr("setter", &self.setter)
+            .opt_attr("getter", &self.getter)
+            .opt_attr("default-value", &self.default_value)
+            .opt_attr("transfer-ownership", &self.transfer_ownership)
+            .info(self)
+            .documentation(self)
+            .attributes(self)
+            .untagged([&self.type_])
+            .extra(self)
+    }
+}
@@ -1,99 +1,79 @@
-use xmlserde_derives::XmlDeserialize;
-
 use crate::{
     attribute::Attribute,
     callable::Callable,
     documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition},
+    extra::Extra,
     field::Field,
     function::{Function, FunctionInline},
     method::{Method, MethodInline},
     prelude::*,
+    span::NodeSpan,
+    tree::{FromNode, FromTag, Node},
     union::Union,
     version::Version,
+    writer::{Element, ToXml},
     Callback, Stability,
 };
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 // FIXME: The `Type` / `AnyType` fields are quite huge and some boxing would
-// probably be useful here but `xmlserde` does not seem to support that.
+// probably be useful here.
 #[allow(clippy::large_enum_variant)]
 pub enum RecordField {
-    #[xmlserde(name = b"field")]
     Field(Field),
-    #[xmlserde(name = b"union")]
     Union(Union),
-    #[xmlserde(name = b"record")]
     Record(Record),
-    #[xmlserde(name = b"callback")]
     Callback(Callback),
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
-#[xmlserde(root = b"record")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Record {
-    #[xmlserde(name = b"name", ty = "attr")]
     name: Option<String>,
-    #[xmlserde(name = b"c:type", ty = "attr")]
     c_type: Option<String>,
     /// Deprecated and replaced by `opaque` & `pointer`
-    #[xmlserde(name = b"disguised", ty = "attr")]
     disguised: Option<bool>,
-    #[xmlserde(name = b"pointer", ty = "attr")]
     pointer: Option<bool>,
-    #[xmlserde(name = b"opaque", ty = "attr")]
     opaque: Option<bool>,
-    #[xmlserde(name = b"foreign", ty = "attr")]
     foreign: Option<bool>,
-    #[xmlserde(name = b"glib:is-gtype-struct-for", ty = "attr")]
     g_is_gtype_struct_for: Option<String>,
-    #[xmlserde(name = b"glib:type-name", ty = "attr")]
     g_type_name: Option<String>,
-    #[xmlserde(name = b"glib:get-type", ty = "attr")]
     g_get_type: Option<String>,
-    #[xmlserde(name = b"c:symbol-prefix", ty = "attr")]
     symbol_prefix: Option<String>,
-    #[xmlserde(name = b"copy-function", ty = "attr")]
     copy_function: Option<String>,
-    #[xmlserde(name = b"free-function", ty = "attr")]
     free_function: Option<String>,
 
     // Common attributes
-    #[xmlserde(name = b"introspectable", ty = "attr")]
     introspectable: Option<bool>,
-    #[xmlserde(name = b"deprecated", ty = "attr")]
     deprecated: Option<bool>,
-    #[xmlserde(name = b"version", ty = "attr")]
     version: Option<Version>,
-    #[xmlserde(name = b"deprecated-version", ty = "attr")]
     deprecated_version: Option<Version>,
-    #[xmlserde(name = b"stability", ty = "attr")]
     stability: Option<Stability>,
     // Documentation
-    #[xmlserde(name = b"doc", ty = "child")]
     doc: Option<Documentation>,
-    #[xmlserde(name = b"doc-deprecated", ty = "child")]
     doc_deprecated: Option<DocDeprecated>,
-    #[xmlserde(name = b"doc-stability", ty = "child")]
     doc_stability: Option<DocStability>,
-    #[xmlserde(name = b"doc-version", ty = "child")]
     doc_version: Option<DocVersion>,
-    #[xmlserde(name = b"source-position", ty = "child")]
     source_position: Option<SourcePosition>,
     // Attributes: 0 or more
-    #[xmlserde(name = b"attribute", ty = "child")]
     attributes: Vec<Attribute>,
 
-    #[xmlserde(name = b"function-inline", ty = "child")]
+    #[cfg_attr(feature = "serde", serde(rename = "inlined_functions"))]
     inline_functions: Vec<FunctionInline>,
 
-    #[xmlserde(name = b"inline-methods", ty = "child")]
+    #[cfg_attr(feature = "serde", serde(rename = "inlined_methods"))]
     inline_methods: Vec<MethodInline>,
 
-    #[xmlserde(ty = "untag")]
     fields: Vec<RecordField>,
-    #[xmlserde(ty = "untag")]
     callables: Vec<Callable>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Record {
@@ -186,3 +166,127 @@ impl Record {
 impl_info!(Record);
 impl_attributable!(Record);
 impl_documentable!(Record);
+
+impl FromTag for RecordField {
+    fn from_tag(node: Node) -> Option<Self> {
+        match node.tag() {
+            "field" => Some(Self::Field(Field::from_node(node))),
+            "union" => Some(Self::Union(Union::from_node(node))),
+            "record" => Some(Self::Record(Record::from_node(node))),
+            "callback" => Some(Self::Callback(Callback::from_node(node))),
+            _ => None,
+        }
+    }
+}
+
+impl ToXml for RecordField {
+    fn to_xml(&self, _tag: &str) -> Element {
+        match self {
+            Self::Field(f) => f.to_xml("field"),
+            Self::Union(u) => u.to_xml("union"),
+            Self::Record(r) => r.to_xml("record"),
+            Self::Callback(c) => c.to_xml("callback"),
+        }
+    }
+}
+
+impl_extensible!(Record);
+impl_spanned!(Record);
+impl_builder!(Record => RecordBuilder {
+    new {}
+    optional {
+        name: String => name, name_mut;
+        c_type: String => c_type, c_type_mut;
+        disguised: bool => disguised, disguised_mut;
+        pointer: bool => pointer, pointer_mut;
+        opaque: bool => opaque, opaque_mut;
+        foreign: bool => foreign, foreign_mut;
+        g_is_gtype_struct_for: String => g_is_gtype_struct_for, g_is_gtype_struct_for_mut;
+        g_type_name: String => g_type_name, g_type_name_mut;
+        g_get_type: String => g_get_type, g_get_type_mut;
+        symbol_prefix: String => symbol_prefix, symbol_prefix_mut;
+        copy_function: String => copy_function, copy_function_mut;
+        free_function: String => free_function, free_function_mut;
+        introspectable: bool => introspectable, introspectable_mut;
+        deprecated: bool => deprecated, deprecated_mut;
+        version: Version => version, version_mut;
+        deprecated_version: Version => deprecated_version, deprecated_version_mut;
+        stability: Stability => stability, stability_mut;
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+    }
+    list {
+        attributes: Attribute => attribute, attributes_mut;
+        inline_functions: FunctionInline => inlined_function, inlined_functions_mut;
+        inline_methods: MethodInline => inlined_method, inlined_methods_mut;
+        fields: RecordField => field, fields_mut;
+        callables: Callable => callable, callables_mut;
+    }
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for Record {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.opt_attr("name"),
+            c_type: node.opt_attr("c:type"),
+            disguised: node.opt_attr("disguised"),
+            pointer: node.opt_attr("pointer"),
+            opaque: node.opt_attr("opaque"),
+            foreign: node.opt_attr("foreign"),
+            g_is_gtype_struct_for: node.opt_attr("glib:is-gtype-struct-for"),
+            g_type_name: node.opt_attr("glib:type-name"),
+            g_get_type: node.opt_attr("glib:get-type"),
+            symbol_prefix: node.opt_attr("c:symbol-prefix"),
+            copy_function: node.opt_attr("copy-function"),
+            free_function: node.opt_attr("free-function"),
+            introspectable: node.opt_attr("introspectable"),
+            deprecated: node.opt_attr("deprecated"),
+            version: node.opt_attr("version"),
+            deprecated_version: node.opt_attr("deprecated-version"),
+            stability: node.opt_attr("stability"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            attributes: node.children("attribute"),
+            inline_functions: node.children("function-inline"),
+            inline_methods: node.children("method-inline"),
+            fields: node.all_of(),
+            callables: node.all_of(),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Record {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .opt_attr("name", &self.name)
+            .opt_attr("c:type", &self.c_type)
+            .opt_attr("disguised", &self.disguised)
+            .opt_attr("pointer", &self.pointer)
+            .opt_attr("opaque", &self.opaque)
+            .opt_attr("foreign", &self.foreign)
+            .opt_attr("glib:is-gtype-struct-for", &self.g_is_gtype_struct_for)
+            .opt_attr("glib:type-name", &self.g_type_name)
+            .opt_attr("glib:get-type", &self.g_get_type)
+            .opt_attr("c:symbol-prefix", &self.symbol_prefix)
+            .opt_attr("copy-function", &self.copy_function)
+            .opt_attr("free-function", &self.free_function)
+            .info(self)
+            .documentation(self)
+            .attributes(self)
+            .untagged(&self.fields)
+            .untagged(&self.callables)
+            .children("function-inline", &self.inline_functions)
+            .children("method-inline", &self.inline_methods)
+            .extra(self)
+    }
+}
@@ -1,17 +1,31 @@
-use std::{collections::HashMap, path::Path, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
-use xmlserde_derives::XmlDeserialize;
+use crate::{
+    compression,
+    extra::Extra,
+    namespace::Namespace,
+    prelude::*,
+    schema::Kind,
+    span::NodeSpan,
+    tree::{FromNode, Node, Origin, Tree},
+    version::Version,
+    writer::{Element, ToXml},
+    GirSearchPath, ParseOptions, ParseWarning, ParserError,
+};
 
-use crate::{namespace::Namespace, version::Version, ParserError};
-
-#[derive(Clone, Debug, PartialEq, Eq, Hash, XmlDeserialize)]
-#[xmlserde(root = b"include")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NamespaceInclude {
-    #[xmlserde(name = b"name", ty = "attr")]
     name: String,
-    #[xmlserde(name = b"version", ty = "attr")]
     version: Version,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl NamespaceInclude {
@@ -32,12 +46,13 @@ impl NamespaceInclude {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, XmlDeserialize)]
-#[xmlserde(root = b"c:include")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HeaderInclude {
-    #[xmlserde(name = b"name", ty = "attr")]
     name: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl HeaderInclude {
@@ -46,12 +61,13 @@ impl HeaderInclude {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, XmlDeserialize)]
-#[xmlserde(root = b"package")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Package {
-    #[xmlserde(name = b"name", ty = "attr")]
     name: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Package {
@@ -60,14 +76,22 @@ impl Package {
     }
 }
 
-#[derive(Clone, Debug, XmlDeserialize, Copy, Default)]
-#[xmlserde(root = b"doc:format")]
-#[xmlserde(deny_unknown_fields)]
-struct DocFormatChild {
-    #[xmlserde(name = b"name", ty = "attr")]
+#[derive(Clone, Debug, PartialEq, Eq, Copy, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub(crate) struct DocFormatChild {
     format: DocFormat,
 }
 
+impl DocFormatChild {
+    pub(crate) fn format(&self) -> DocFormat {
+        self.format
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Default, Copy)]
 pub enum DocFormat {
     GtkDocMarkdown,
@@ -102,34 +126,43 @@ impl xmlserde::XmlValue for DocFormat {
     }
 }
 
-#[derive(Clone, Debug, XmlDeserialize)]
-#[xmlserde(root = b"repository")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Repository {
-    #[xmlserde(name = b"version", ty = "attr")]
     version: Option<Version>,
-    #[xmlserde(name = b"c:identifier-prefixes", ty = "attr")]
     c_identifier_prefixes: Option<String>,
-    #[xmlserde(name = b"c:symbol-prefixes", ty = "attr")]
     c_symbol_prefixes: Option<String>,
-    #[xmlserde(name = b"xmlns", ty = "attr")]
+    #[cfg_attr(feature = "serde", serde(rename = "xmlns"))]
     _xmlns: Option<String>,
-    #[xmlserde(name = b"xmlns:c", ty = "attr")]
+    #[cfg_attr(feature = "serde", serde(rename = "xmlns_c"))]
     _xmlns_c: Option<String>,
-    #[xmlserde(name = b"xmlns:glib", ty = "attr")]
+    #[cfg_attr(feature = "serde", serde(rename = "xmlns_glib"))]
     _xmlns_glib: Option<String>,
-    #[xmlserde(name = b"xmlns:doc", ty = "attr")]
+    #[cfg_attr(feature = "serde", serde(rename = "xmlns_doc"))]
     _xmlns_doc: Option<String>,
-    #[xmlserde(name = b"include", ty = "child")]
+    #[cfg_attr(feature = "serde", serde(rename = "namespace_includes"))]
     includes: Vec<NamespaceInclude>,
-    #[xmlserde(name = b"c:include", ty = "child")]
+    #[cfg_attr(feature = "serde", serde(rename = "header_includes"))]
     c_includes: Vec<HeaderInclude>,
-    #[xmlserde(name = b"package", ty = "child")]
     packages: Vec<Package>,
-    #[xmlserde(name = b"namespace", ty = "child")]
+    // Only missing from the headers read by a `GirStream`, the checker
+    // requires it otherwise.
     namespace: Namespace,
-    #[xmlserde(name = b"doc:format", ty = "child")]
+    #[cfg_attr(feature = "serde", serde(rename = "doc_format"))]
     doc_format_child: Option<DocFormatChild>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
+}
+
+/// The XML namespaces declared by the repositories made with a builder.
+const XMLNS: &str = "http://www.gtk.org/introspection/core/1.0";
+const XMLNS_C: &str = "http://www.gtk.org/introspection/c/1.0";
+const XMLNS_GLIB: &str = "http://www.gtk.org/introspection/glib/1.0";
+const XMLNS_DOC: &str = "http://www.gtk.org/introspection/doc/1.0";
+
+fn missing_namespace() -> Namespace {
+    Namespace::builder(String::new(), Version::default()).build()
 }
 
 impl Repository {
@@ -138,18 +171,36 @@ impl Repository {
         package_file: &str,
         girs_dirs: impl AsRef<Path>,
     ) -> Result<(), ParserError> {
-        let repo = Self::from_path(girs_dirs.as_ref().join(package_file))?;
-        if cache.contains_key(package_file) {
+        let locate = |package_file: &str| Ok(girs_dirs.as_ref().join(package_file));
+        Self::follow_namespaces(cache, package_file, &locate, &mut HashSet::new())
+    }
+
+    /// Parse a GIR file along with the files of the namespaces it includes,
+    /// recursively, looking for all of them in a search path.
+    pub fn from_search_path_follow_namespaces(
+        package_file: &str,
+        search_path: &GirSearchPath,
+    ) -> Result<HashMap<String, Self>, ParserError> {
+        let mut output = HashMap::new();
+        let locate = |package_file: &str| search_path.locate(package_file);
+        Self::follow_namespaces(&mut output, package_file, &locate, &mut HashSet::new())?;
+        Ok(output)
+    }
+
+    /// Load a file and the files it includes, unless it's already loaded or
+    /// `loading`, which is the case when files include each other.
+    fn follow_namespaces(
+        cache: &mut HashMap<String, Self>,
+        package_file: &str,
+        locate: &dyn Fn(&str) -> Result<PathBuf, ParserError>,
+        loading: &mut HashSet<String>,
+    ) -> Result<(), ParserError> {
+        if cache.contains_key(package_file) || !loading.insert(package_file.to_owned()) {
             return Ok(());
         }
+        let repo = Self::from_path(locate(package_file)?)?;
         for namespace in repo.namespace_includes() {
-            if !cache.contains_key(&namespace.as_package_file()) {
-                Self::from_path_follow_namespaces_and_cache(
-                    cache,
-                    &namespace.as_package_file(),
-                    girs_dirs.as_ref(),
-                )?;
-            }
+            Self::follow_namespaces(cache, &namespace.as_package_file(), locate, loading)?;
         }
         debug_assert_eq!(
             package_file,
@@ -173,11 +224,68 @@ impl Repository {
     }
 
     pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ParserError> {
-        let content = std::fs::read_to_string(path)?;
-        let repository = xmlserde::xml_deserialize_from_str(&content).map_err(ParserError::Xml)?;
+        let (repository, _) = Self::from_path_with_options(path, &ParseOptions::default())?;
+        Ok(repository)
+    }
+
+    /// Parse a GIR file, along with the warnings about the parts of it that
+    /// were skipped in lenient mode.
+    pub fn from_path_with_options(
+        path: impl AsRef<Path>,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        let content = compression::read_path(path.as_ref())?;
+        Self::parse(&content, Some(path.as_ref()), options)
+    }
+
+    /// Parse a GIR document from a reader, decompressing it first if it is
+    /// gzip or xz compressed and the matching feature is enabled.
+    pub fn from_reader(reader: impl BufRead) -> Result<Self, ParserError> {
+        let (repository, _) = Self::from_reader_with_options(reader, &ParseOptions::default())?;
         Ok(repository)
     }
 
+    pub fn from_reader_with_options(
+        reader: impl BufRead,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        let content = compression::read_to_string(reader)?;
+        Self::parse(&content, None, options)
+    }
+
+    /// Parse a GIR document, along with the warnings about the parts of it
+    /// that were skipped in lenient mode.
+    pub fn from_str_with_options(
+        content: &str,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        Self::parse(content, None, options)
+    }
+
+    pub(crate) fn parse(
+        content: &str,
+        path: Option<&Path>,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        let (tree, warnings) = Tree::parse(content, Kind::ROOT, path, options, Origin::START)?;
+        let build = || Self::from_node(tree.root());
+        let repository = match &options.interner {
+            Some(interner) => interner.scope(build),
+            None => build(),
+        };
+        Ok((repository, warnings))
+    }
+
+    /// Serialize the repository back to a GIR document.
+    pub fn to_xml(&self) -> String {
+        ToXml::to_xml(self, "repository").to_document()
+    }
+
+    pub fn write_to(&self, mut writer: impl Write) -> Result<(), ParserError> {
+        writer.write_all(self.to_xml().as_bytes())?;
+        Ok(())
+    }
+
     pub fn version(&self) -> Option<&Version> {
         self.version.as_ref()
     }
@@ -217,7 +325,9 @@ impl Repository {
     }
 
     pub fn doc_format(&self) -> DocFormat {
-        self.doc_format_child.map(|c| c.format).unwrap_or_default()
+        self.doc_format_child
+            .map(|c| c.format())
+            .unwrap_or_default()
     }
 }
 
@@ -225,7 +335,186 @@ impl FromStr for Repository {
     type Err = ParserError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let repository = xmlserde::xml_deserialize_from_str(s).map_err(ParserError::Xml)?;
+        let (repository, _) = Self::from_str_with_options(s, &ParseOptions::default())?;
         Ok(repository)
     }
 }
+
+impl_extensible!(NamespaceInclude);
+impl_spanned!(NamespaceInclude);
+impl_builder!(NamespaceInclude => NamespaceIncludeBuilder {
+    new {
+        name: String => name_mut;
+        version: Version => version_mut;
+    }
+    optional {}
+    list {}
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for NamespaceInclude {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            version: node.attr("version"),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for NamespaceInclude {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .attr("name", &self.name)
+            .attr("version", &self.version)
+            .extra(self)
+    }
+}
+
+impl_extensible!(HeaderInclude);
+impl_spanned!(HeaderInclude);
+impl_builder!(HeaderInclude => HeaderIncludeBuilder {
+    new {
+        name: String => name_mut;
+    }
+    optional {}
+    list {}
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for HeaderInclude {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for HeaderInclude {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag).attr("name", &self.name).extra(self)
+    }
+}
+
+impl_extensible!(Package);
+impl_spanned!(Package);
+impl_builder!(Package => PackageBuilder {
+    new {
+        name: String => name_mut;
+    }
+    optional {}
+    list {}
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for Package {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Package {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag).attr("name", &self.name).extra(self)
+    }
+}
+
+impl FromNode for DocFormatChild {
+    fn from_node(node: Node) -> Self {
+        Self {
+            format: node.attr("name"),
+        }
+    }
+}
+
+impl ToXml for DocFormatChild {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag).attr("name", &self.format)
+    }
+}
+
+impl_extensible!(Repository);
+impl_spanned!(Repository);
+impl_builder!(Repository => RepositoryBuilder {
+    new {
+        namespace: Namespace => namespace_mut;
+    }
+    optional {
+        version: Version => version, version_mut;
+        c_identifier_prefixes: String => c_identifier_prefixes, c_identifier_prefixes_mut;
+        c_symbol_prefixes: String => c_symbol_prefixes, c_symbol_prefixes_mut;
+    }
+    list {
+        includes: NamespaceInclude => namespace_include, namespace_includes_mut;
+        c_includes: HeaderInclude => header_include, header_includes_mut;
+        packages: Package => package, packages_mut;
+    }
+    value {}
+    hidden {
+        _xmlns = Some(XMLNS.to_owned()),
+        _xmlns_c = Some(XMLNS_C.to_owned()),
+        _xmlns_glib = Some(XMLNS_GLIB.to_owned()),
+        _xmlns_doc = Some(XMLNS_DOC.to_owned()),
+        doc_format_child,
+        span,
+        extra
+    }
+});
+
+impl RepositoryBuilder {
+    /// The format of the documentation, written as the `doc:format` element.
+    pub fn doc_format(mut self, format: DocFormat) -> Self {
+        self.0.doc_format_child = Some(DocFormatChild { format });
+        self
+    }
+}
+
+impl FromNode for Repository {
+    fn from_node(node: Node) -> Self {
+        Self {
+            version: node.opt_attr("version"),
+            c_identifier_prefixes: node.opt_attr("c:identifier-prefixes"),
+            c_symbol_prefixes: node.opt_attr("c:symbol-prefixes"),
+            _xmlns: node.opt_attr("xmlns"),
+            _xmlns_c: node.opt_attr("xmlns:c"),
+            _xmlns_glib: node.opt_attr("xmlns:glib"),
+            _xmlns_doc: node.opt_attr("xmlns:doc"),
+            includes: node.children("include"),
+            c_includes: node.children("c:include"),
+            packages: node.children("package"),
+            namespace: node.child("namespace").unwrap_or_else(missing_namespace),
+            doc_format_child: node.child("doc:format"),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Repository {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .opt_attr("version", &self.version)
+            .opt_attr("c:identifier-prefixes", &self.c_identifier_prefixes)
+            .opt_attr("c:symbol-prefixes", &self.c_symbol_prefixes)
+            .opt_attr("xmlns", &self._xmlns)
+            .opt_attr("xmlns:c", &self._xmlns_c)
+            .opt_attr("xmlns:glib", &self._xmlns_glib)
+            .opt_attr("xmlns:doc", &self._xmlns_doc)
+            .children("include", &self.includes)
+            .children("c:include", &self.c_includes)
+            .children("package", &self.packages)
+            .opt_child("doc:format", self.doc_format_child.as_ref())
+            .child("namespace", &self.namespace)
+            .extra(self)
+    }
+}
@@ -0,0 +1,181 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Read},
+    path::Path,
+    str::FromStr,
+};
+
+use xmlserde::quick_xml::{events::Event, Reader};
+
+use crate::{
+    compression,
+    namespace::Namespace,
+    repository::{HeaderInclude, NamespaceInclude, Package, Repository},
+    version::Version,
+    DocFormat, ParseOptions, ParseWarning, ParserError,
+};
+
+const CHUNK_SIZE: usize = 16 * 1024;
+
+/// The metadata at the top of a GIR file: the includes, the packages and the
+/// attributes of its namespace.
+///
+/// Reading a header stops at the first child of the `<namespace>` element,
+/// which makes it a lot cheaper than parsing the whole repository when only
+/// the dependencies of a file are needed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RepositoryHeader {
+    repository: Repository,
+}
+
+impl RepositoryHeader {
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ParserError> {
+        let (header, _) = Self::from_path_with_options(path, &ParseOptions::default())?;
+        Ok(header)
+    }
+
+    /// Read the header of a GIR file, along with the warnings about the parts
+    /// of it that were skipped in lenient mode.
+    pub fn from_path_with_options(
+        path: impl AsRef<Path>,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        let path = path.as_ref();
+        let file = BufReader::new(File::open(path)?);
+        Self::read(file, Some(path), options)
+    }
+
+    /// Read the header of a GIR document from a reader, decompressing it
+    /// first if it is gzip or xz compressed and the matching feature is
+    /// enabled.
+    pub fn from_reader(reader: impl BufRead) -> Result<Self, ParserError> {
+        let (header, _) = Self::from_reader_with_options(reader, &ParseOptions::default())?;
+        Ok(header)
+    }
+
+    pub fn from_reader_with_options(
+        reader: impl BufRead,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        Self::read(reader, None, options)
+    }
+
+    fn read(
+        reader: impl BufRead,
+        path: Option<&Path>,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        let mut reader = compression::decode(reader)?;
+        let mut bytes = Vec::new();
+        loop {
+            let read = (&mut reader)
+                .take(bytes.len().max(CHUNK_SIZE) as u64)
+                .read_to_end(&mut bytes)?;
+            let content = match std::str::from_utf8(&bytes) {
+                Ok(content) => content,
+                // The chunk may end in the middle of a character.
+                Err(e) if read != 0 && e.error_len().is_none() => continue,
+                Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e).into()),
+            };
+            if let Some(header) = Self::truncate(content) {
+                return Self::parse(&header, path, options);
+            }
+            if read == 0 {
+                return Self::parse(content, path, options);
+            }
+        }
+    }
+
+    /// Read the header of a GIR document, along with the warnings about the
+    /// parts of it that were skipped in lenient mode.
+    pub fn from_str_with_options(
+        content: &str,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        match Self::truncate(content) {
+            Some(header) => Self::parse(&header, None, options),
+            None => Self::parse(content, None, options),
+        }
+    }
+
+    fn parse(
+        content: &str,
+        path: Option<&Path>,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        let (repository, warnings) = Repository::parse(content, path, options)?;
+        Ok((Self { repository }, warnings))
+    }
+
+    /// Cut the document before the first child of its namespace and close the
+    /// elements left open, or `None` if no such child was found in `content`.
+    ///
+    /// The offsets in the truncated document are the ones of the original
+    /// one, so errors point to the right place.
+    fn truncate(content: &str) -> Option<String> {
+        let mut reader = Reader::from_str(content);
+        let mut open = Vec::<Vec<u8>>::new();
+        loop {
+            let offset = reader.buffer_position() as usize;
+            match reader.read_event().ok()? {
+                Event::Start(_) | Event::Empty(_) if open.len() == 2 && open[1] == b"namespace" => {
+                    let mut header = content[..offset].to_owned();
+                    for tag in open.iter().rev() {
+                        header.push_str("</");
+                        header.push_str(std::str::from_utf8(tag).ok()?);
+                        header.push('>');
+                    }
+                    return Some(header);
+                }
+                Event::Start(e) => open.push(e.name().as_ref().to_vec()),
+                Event::End(_) => {
+                    open.pop();
+                }
+                Event::Eof => return None,
+                _ => (),
+            }
+        }
+    }
+
+    pub fn version(&self) -> Option<&Version> {
+        self.repository.version()
+    }
+
+    pub fn c_identifier_prefixes(&self) -> impl Iterator<Item = &str> {
+        self.repository.c_identifier_prefixes()
+    }
+
+    pub fn c_symbol_prefixes(&self) -> impl Iterator<Item = &str> {
+        self.repository.c_symbol_prefixes()
+    }
+
+    pub fn namespace_includes(&self) -> &[NamespaceInclude] {
+        self.repository.namespace_includes()
+    }
+
+    pub fn header_includes(&self) -> &[HeaderInclude] {
+        self.repository.header_includes()
+    }
+
+    pub fn packages(&self) -> &[Package] {
+        self.repository.packages()
+    }
+
+    /// The namespace, with its attributes only.
+    pub fn namespace(&self) -> &Namespace {
+        self.repository.namespace()
+    }
+
+    pub fn doc_format(&self) -> DocFormat {
+        self.repository.doc_format()
+    }
+}
+
+impl FromStr for RepositoryHeader {
+    type Err = ParserError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (header, _) = Self::from_str_with_options(s, &ParseOptions::default())?;
+        Ok(header)
+    }
+}
@@ -1,52 +1,43 @@
-use xmlserde_derives::XmlDeserialize;
-
 use crate::{
     attribute::Attribute,
     documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition},
+    extra::Extra,
     prelude::*,
-    r#type::AnyType,
+    r#type::{AnyType, Type},
+    span::NodeSpan,
+    tree::{FromNode, Node},
+    writer::{Element, ToXml},
     FunctionScope, TransferOwnership,
 };
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
-#[xmlserde(root = b"return-value")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReturnValue {
-    #[xmlserde(name = b"introspectable", ty = "attr")]
     introspectable: Option<bool>,
-    #[xmlserde(name = b"nullable", ty = "attr")]
     nullable: Option<bool>,
-    #[xmlserde(name = b"closure", ty = "attr")]
     closure: Option<usize>,
-    #[xmlserde(name = b"scope", ty = "attr")]
     scope: Option<FunctionScope>,
-    #[xmlserde(name = b"destroy", ty = "attr")]
     destroy: Option<usize>,
-    #[xmlserde(name = b"skip", ty = "attr")]
     skip: Option<bool>,
-    #[xmlserde(name = b"allow-none", ty = "attr")]
     allow_none: Option<bool>,
 
-    #[xmlserde(name = b"transfer-ownership", ty = "attr")]
+    #[cfg_attr(feature = "serde", serde(rename = "transfer_ownership"))]
     transfer: Option<TransferOwnership>,
 
     // Documentation
-    #[xmlserde(name = b"doc", ty = "child")]
     doc: Option<Documentation>,
-    #[xmlserde(name = b"doc-deprecated", ty = "child")]
     doc_deprecated: Option<DocDeprecated>,
-    #[xmlserde(name = b"doc-stability", ty = "child")]
     doc_stability: Option<DocStability>,
-    #[xmlserde(name = b"doc-version", ty = "child")]
     doc_version: Option<DocVersion>,
-    #[xmlserde(name = b"source-position", ty = "child")]
     source_position: Option<SourcePosition>,
     // Attributes: 0 or more
-    #[xmlserde(name = b"attribute", ty = "child")]
     attributes: Vec<Attribute>,
 
-    #[xmlserde(ty = "untag")]
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
     type_: AnyType,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl ReturnValue {
@@ -87,5 +78,82 @@ impl ReturnValue {
     }
 }
 
+/// A return value of type `none`, as for functions returning `void`.
+impl Default for ReturnValue {
+    fn default() -> Self {
+        Self::builder(Type::builder().name("none").c_type("void").build()).build()
+    }
+}
+
 impl_documentable!(ReturnValue);
 impl_attributable!(ReturnValue);
+
+impl_extensible!(ReturnValue);
+impl_spanned!(ReturnValue);
+impl_builder!(ReturnValue => ReturnValueBuilder {
+    new {
+        type_: AnyType => ty_mut;
+    }
+    optional {
+        introspectable: bool => introspectable, introspectable_mut;
+        nullable: bool => nullable, nullable_mut;
+        closure: usize => closure, closure_mut;
+        scope: FunctionScope => scope, scope_mut;
+        destroy: usize => destroy, destroy_mut;
+        skip: bool => skip, skip_mut;
+        allow_none: bool => allow_none, allow_none_mut;
+        transfer: TransferOwnership => transfer_ownership, transfer_ownership_mut;
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+    }
+    list {
+        attributes: Attribute => attribute, attributes_mut;
+    }
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for ReturnValue {
+    fn from_node(node: Node) -> Self {
+        Self {
+            introspectable: node.opt_attr("introspectable"),
+            nullable: node.opt_attr("nullable"),
+            closure: node.opt_attr("closure"),
+            scope: node.opt_attr("scope"),
+            destroy: node.opt_attr("destroy"),
+            skip: node.opt_attr("skip"),
+            allow_none: node.opt_attr("allow-none"),
+            transfer: node.opt_attr("transfer-ownership"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            attributes: node.children("attribute"),
+            type_: node.one_of(),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for ReturnValue {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .opt_attr("introspectable", &self.introspectable)
+            .opt_attr("nullable", &self.nullable)
+            .opt_attr("closure", &self.closure)
+            .opt_attr("scope", &self.scope)
+            .opt_attr("destroy", &self.destroy)
+            .opt_attr("skip", &self.skip)
+            .opt_attr("allow-none", &self.allow_none)
+            .opt_attr("transfer-ownership", &self.transfer)
+            .documentation(self)
+            .attributes(self)
+            .untagged([&self.type_])
+            .extra(self)
+    }
+}
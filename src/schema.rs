@@ -0,0 +1,1172 @@
+//! The elements and attributes each GIR element accepts, mirroring the
+//! fields of the types built from them. The tests check both agree by
+//! parsing and writing back a document using every declaration.
+
+use std::{any::TypeId, sync::OnceLock};
+
+use xmlserde::XmlValue;
+
+use crate::{
+    parameter::Direction, repository::DocFormat, version::Version, FunctionScope, SignalEmission,
+    Stability, TransferOwnership,
+};
+
+type Check = fn(&str) -> Result<(), String>;
+
+fn check<T: XmlValue + 'static>(value: &str) -> Result<(), String> {
+    // Any text is a valid string, there is no need to allocate one.
+    if TypeId::of::<T>() == TypeId::of::<String>() {
+        return Ok(());
+    }
+    T::deserialize(value).map(|_| ())
+}
+
+/// A group of elements, such as those of which one has to be present.
+type Tags = &'static [&'static str];
+
+#[derive(Debug)]
+pub(crate) struct Attr {
+    pub(crate) name: &'static str,
+    pub(crate) required: bool,
+    /// Whether `gir-1.2.rnc` requires the attribute, although deserializing
+    /// doesn't.
+    pub(crate) spec_required: bool,
+    /// The values `gir-1.2.rnc` allows, when narrower than what deserializing
+    /// accepts.
+    pub(crate) spec_values: Option<Tags>,
+    /// Whether the attribute isn't part of `gir-1.2.rnc`.
+    pub(crate) extension: bool,
+    check: Check,
+}
+
+impl Attr {
+    const fn optional<T: XmlValue + 'static>(name: &'static str) -> Self {
+        Self {
+            name,
+            required: false,
+            spec_required: false,
+            spec_values: None,
+            extension: false,
+            check: check::<T>,
+        }
+    }
+
+    const fn required<T: XmlValue + 'static>(name: &'static str) -> Self {
+        Self {
+            required: true,
+            ..Self::optional::<T>(name)
+        }
+    }
+
+    const fn spec_required(self) -> Self {
+        Self {
+            spec_required: true,
+            ..self
+        }
+    }
+
+    const fn spec_values(self, values: Tags) -> Self {
+        Self {
+            spec_values: Some(values),
+            ..self
+        }
+    }
+
+    const fn extension(self) -> Self {
+        Self {
+            extension: true,
+            ..self
+        }
+    }
+
+    pub(crate) fn check(&self, value: &str) -> Result<(), String> {
+        (self.check)(value)
+    }
+}
+
+/// The rules of `gir-1.2.rnc` on child elements which are stricter than
+/// deserializing.
+#[derive(Debug)]
+pub(crate) struct Spec {
+    /// Elements which aren't part of `gir-1.2.rnc`.
+    pub(crate) extensions: Tags,
+    pub(crate) required_children: &'static [Tags],
+    pub(crate) single_children: &'static [Tags],
+}
+
+impl Spec {
+    const NONE: Self = Self {
+        extensions: &[],
+        required_children: &[],
+        single_children: &[],
+    };
+}
+
+#[derive(Debug)]
+pub(crate) struct Schema {
+    attributes: &'static [&'static [Attr]],
+    children: &'static [&'static [(&'static str, Kind)]],
+    /// Groups of elements of which one has to be present.
+    required_children: &'static [Tags],
+    /// Groups of elements of which at most one can be present.
+    single_children: &'static [&'static [Tags]],
+    spec: Spec,
+}
+
+impl Schema {
+    pub(crate) fn attributes(&self) -> impl Iterator<Item = &'static Attr> {
+        self.attributes.iter().flat_map(|group| group.iter())
+    }
+
+    /// The child elements, along with their kinds.
+    pub(crate) fn children(&self) -> impl Iterator<Item = (&'static str, Kind)> {
+        self.children.iter().flat_map(|group| group.iter()).copied()
+    }
+
+    pub(crate) fn required_children(&self) -> &'static [Tags] {
+        self.required_children
+    }
+
+    pub(crate) fn single_children(&self) -> impl Iterator<Item = Tags> {
+        self.single_children
+            .iter()
+            .flat_map(|group| group.iter())
+            .copied()
+    }
+
+    pub(crate) fn spec(&self) -> &Spec {
+        &self.spec
+    }
+}
+
+/// The attributes and children of a schema sorted by name, as they are
+/// looked up for every attribute and element of the documents.
+struct Lookup {
+    attributes: Vec<&'static Attr>,
+    required_attributes: Vec<&'static Attr>,
+    children: Vec<(&'static str, Kind)>,
+}
+
+impl Lookup {
+    fn new(schema: &'static Schema) -> Self {
+        let mut attributes = schema.attributes().collect::<Vec<_>>();
+        attributes.sort_unstable_by_key(|attr| attr.name);
+        let required_attributes = schema.attributes().filter(|attr| attr.required).collect();
+        let mut children = schema.children().collect::<Vec<_>>();
+        children.sort_unstable_by_key(|(tag, _)| *tag);
+        Self {
+            attributes,
+            required_attributes,
+            children,
+        }
+    }
+}
+
+const KINDS: usize = Kind::VirtualMethod as usize + 1;
+
+static LOOKUPS: [OnceLock<Lookup>; KINDS] = [const { OnceLock::new() }; KINDS];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Alias,
+    Array,
+    Attribute,
+    BitField,
+    Boxed,
+    Callback,
+    Class,
+    Constant,
+    DocFormat,
+    DocSection,
+    DocText,
+    Documentation,
+    Enumeration,
+    Field,
+    Function,
+    FunctionMacro,
+    HeaderInclude,
+    Implements,
+    InstanceParameter,
+    Interface,
+    Member,
+    Method,
+    Namespace,
+    NamespaceInclude,
+    Package,
+    Parameter,
+    Parameters,
+    Prerequisite,
+    Property,
+    Record,
+    Repository,
+    ReturnValue,
+    Signal,
+    SourcePosition,
+    Type,
+    Union,
+    VarArgs,
+    VirtualMethod,
+}
+
+impl Kind {
+    pub(crate) const ROOT: (&'static str, Self) = ("repository", Self::Repository);
+
+    /// Whether the node keeps the content that isn't part of its schema,
+    /// which excludes the text-only documentation elements.
+    pub(crate) fn keeps_extra(self) -> bool {
+        !matches!(
+            self,
+            Self::DocFormat | Self::DocText | Self::Documentation | Self::VarArgs
+        )
+    }
+
+    /// The elements [`Documentable`](crate::prelude::Documentable) exposes.
+    pub(crate) fn is_documentation(self) -> bool {
+        matches!(
+            self,
+            Self::Documentation | Self::DocText | Self::SourcePosition
+        )
+    }
+
+    /// Whether the node keeps the place it was parsed from, which excludes
+    /// the elements that aren't deserialized to a node of their own.
+    pub(crate) fn is_spanned(self) -> bool {
+        !matches!(self, Self::DocFormat | Self::VarArgs)
+    }
+
+    fn lookup(self) -> &'static Lookup {
+        LOOKUPS[self as usize].get_or_init(|| Lookup::new(self.schema()))
+    }
+
+    pub(crate) fn attribute(self, name: &str) -> Option<&'static Attr> {
+        let attributes = &self.lookup().attributes;
+        let i = attributes
+            .binary_search_by_key(&name, |attr| attr.name)
+            .ok()?;
+        Some(attributes[i])
+    }
+
+    pub(crate) fn required_attributes(self) -> &'static [&'static Attr] {
+        &self.lookup().required_attributes
+    }
+
+    /// The kind of a child element, along with its tag.
+    pub(crate) fn child(self, name: &str) -> Option<(&'static str, Kind)> {
+        let children = &self.lookup().children;
+        let i = children.binary_search_by_key(&name, |(tag, _)| *tag).ok()?;
+        Some(children[i])
+    }
+
+    pub(crate) fn schema(self) -> &'static Schema {
+        match self {
+            Self::Alias => &ALIAS,
+            Self::Array => &ARRAY,
+            Self::Attribute => &ATTRIBUTE,
+            Self::BitField => &BITFIELD,
+            Self::Boxed => &BOXED,
+            Self::Callback => &CALLBACK,
+            Self::Class => &CLASS,
+            Self::Constant => &CONSTANT,
+            Self::DocFormat => &DOC_FORMAT,
+            Self::DocSection => &DOC_SECTION,
+            Self::DocText => &DOC_TEXT,
+            Self::Documentation => &DOCUMENTATION,
+            Self::Enumeration => &ENUMERATION,
+            Self::Field => &FIELD,
+            Self::Function => &FUNCTION,
+            Self::FunctionMacro => &FUNCTION_MACRO,
+            Self::HeaderInclude => &NAMED,
+            Self::Implements => &NAMED,
+            Self::InstanceParameter => &INSTANCE_PARAMETER,
+            Self::Interface => &INTERFACE,
+            Self::Member => &MEMBER,
+            Self::Method => &METHOD,
+            Self::Namespace => &NAMESPACE,
+            Self::NamespaceInclude => &NAMESPACE_INCLUDE,
+            Self::Package => &NAMED,
+            Self::Parameter => &PARAMETER,
+            Self::Parameters => &PARAMETERS,
+            Self::Prerequisite => &NAMED,
+            Self::Property => &PROPERTY,
+            Self::Record => &RECORD,
+            Self::Repository => &REPOSITORY,
+            Self::ReturnValue => &RETURN_VALUE,
+            Self::Signal => &SIGNAL,
+            Self::SourcePosition => &SOURCE_POSITION,
+            Self::Type => &TYPE,
+            Self::Union => &UNION,
+            Self::VarArgs => &EMPTY,
+            Self::VirtualMethod => &VIRTUAL_METHOD,
+        }
+    }
+}
+
+const INFO_ATTRS: &[Attr] = &[
+    Attr::optional::<bool>("introspectable"),
+    Attr::optional::<bool>("deprecated"),
+    Attr::optional::<Version>("version"),
+    Attr::optional::<Version>("deprecated-version"),
+    Attr::optional::<Stability>("stability"),
+];
+
+const CALLABLE_ATTRS: &[Attr] = &[
+    Attr::required::<String>("name"),
+    Attr::optional::<String>("c:identifier"),
+    Attr::optional::<String>("shadows"),
+    Attr::optional::<String>("shadowed-by"),
+    Attr::optional::<bool>("throws"),
+    Attr::optional::<String>("moved-to"),
+    Attr::optional::<String>("glib:async-func"),
+    Attr::optional::<String>("glib:finish-func"),
+    Attr::optional::<String>("glib:sync-func"),
+];
+
+const DOC_TEXT_ATTRS: &[Attr] = &[
+    Attr::optional::<String>("xml:space").spec_values(&["preserve"]),
+    Attr::optional::<String>("xml:whitespace").spec_values(&["preserve"]),
+];
+
+const DOC_CHILDREN: &[(&str, Kind)] = &[
+    ("doc", Kind::Documentation),
+    ("doc-deprecated", Kind::DocText),
+    ("doc-stability", Kind::DocText),
+    ("doc-version", Kind::DocText),
+    ("source-position", Kind::SourcePosition),
+];
+
+const ATTRIBUTE_CHILDREN: &[(&str, Kind)] = &[("attribute", Kind::Attribute)];
+
+const ANY_TYPE_CHILDREN: &[(&str, Kind)] = &[("type", Kind::Type), ("array", Kind::Array)];
+
+const CALLABLE_CHILDREN: &[(&str, Kind)] = &[
+    ("return-value", Kind::ReturnValue),
+    ("parameters", Kind::Parameters),
+];
+
+const CALLABLES: &[(&str, Kind)] = &[
+    ("constructor", Kind::Function),
+    ("method", Kind::Method),
+    ("function", Kind::Function),
+];
+
+const COMPOUND_FIELDS: &[(&str, Kind)] = &[
+    ("field", Kind::Field),
+    ("union", Kind::Union),
+    ("record", Kind::Record),
+    ("callback", Kind::Callback),
+];
+
+const ANY_TYPE: &[Tags] = &[&["type", "array"]];
+
+const SINGLE_DOC_CHILDREN: &[Tags] = &[
+    &["doc"],
+    &["doc-deprecated"],
+    &["doc-stability"],
+    &["doc-version"],
+    &["source-position"],
+];
+
+const SINGLE_CALLABLE_CHILDREN: &[Tags] = &[&["return-value"], &["parameters"]];
+
+const EMPTY: Schema = Schema {
+    attributes: &[],
+    children: &[],
+    required_children: &[],
+    single_children: &[],
+    spec: Spec::NONE,
+};
+
+const NAMED: Schema = Schema {
+    attributes: &[&[Attr::required::<String>("name")]],
+    children: &[],
+    required_children: &[],
+    single_children: &[],
+    spec: Spec::NONE,
+};
+
+const REPOSITORY: Schema = Schema {
+    attributes: &[&[
+        Attr::optional::<Version>("version"),
+        Attr::optional::<String>("c:identifier-prefixes"),
+        Attr::optional::<String>("c:symbol-prefixes"),
+        Attr::optional::<String>("xmlns"),
+        Attr::optional::<String>("xmlns:c"),
+        Attr::optional::<String>("xmlns:glib"),
+        Attr::optional::<String>("xmlns:doc"),
+    ]],
+    children: &[&[
+        ("include", Kind::NamespaceInclude),
+        ("c:include", Kind::HeaderInclude),
+        ("package", Kind::Package),
+        ("namespace", Kind::Namespace),
+        ("doc:format", Kind::DocFormat),
+    ]],
+    required_children: &[&["namespace"]],
+    single_children: &[&[&["namespace"], &["doc:format"]]],
+    spec: Spec::NONE,
+};
+
+const NAMESPACE_INCLUDE: Schema = Schema {
+    attributes: &[&[
+        Attr::required::<String>("name"),
+        Attr::required::<Version>("version"),
+    ]],
+    children: &[],
+    required_children: &[],
+    single_children: &[],
+    spec: Spec::NONE,
+};
+
+const DOC_FORMAT: Schema = Schema {
+    attributes: &[&[Attr::required::<DocFormat>("name")]],
+    children: &[],
+    required_children: &[],
+    single_children: &[],
+    spec: Spec::NONE,
+};
+
+const NAMESPACE: Schema = Schema {
+    attributes: &[&[
+        Attr::required::<String>("name"),
+        Attr::required::<Version>("version"),
+        Attr::optional::<String>("c:identifier-prefixes"),
+        Attr::optional::<String>("c:prefix"),
+        Attr::optional::<String>("c:symbol-prefixes"),
+        Attr::optional::<String>("shared-library"),
+    ]],
+    children: &[
+        &[
+            ("alias", Kind::Alias),
+            ("class", Kind::Class),
+            ("interface", Kind::Interface),
+            ("record", Kind::Record),
+            ("enumeration", Kind::Enumeration),
+            ("function", Kind::Function),
+            ("function-inline", Kind::Function),
+            ("function-macro", Kind::FunctionMacro),
+            ("union", Kind::Union),
+            ("bitfield", Kind::BitField),
+            ("callback", Kind::Callback),
+            ("constant", Kind::Constant),
+            ("glib:boxed", Kind::Boxed),
+            ("docsection", Kind::DocSection),
+        ],
+        ATTRIBUTE_CHILDREN,
+    ],
+    required_children: &[],
+    single_children: &[],
+    spec: Spec::NONE,
+};
+
+const DOC_SECTION: Schema = Schema {
+    attributes: &[&[Attr::required::<String>("name")]],
+    children: &[DOC_CHILDREN],
+    required_children: &[],
+    single_children: &[SINGLE_DOC_CHILDREN],
+    spec: Spec::NONE,
+};
+
+const DOC_TEXT: Schema = Schema {
+    attributes: &[DOC_TEXT_ATTRS],
+    children: &[],
+    required_children: &[],
+    single_children: &[],
+    spec: Spec::NONE,
+};
+
+const DOCUMENTATION: Schema = Schema {
+    attributes: &[
+        DOC_TEXT_ATTRS,
+        &[
+            Attr::optional::<String>("filename").spec_required(),
+            Attr::optional::<String>("line").spec_required(),
+            Attr::optional::<String>("column"),
+        ],
+    ],
+    children: &[],
+    required_children: &[],
+    single_children: &[],
+    spec: Spec::NONE,
+};
+
+const SOURCE_POSITION: Schema = Schema {
+    attributes: &[&[
+        Attr::required::<String>("filename"),
+        Attr::required::<String>("line"),
+        Attr::optional::<String>("column"),
+    ]],
+    children: &[],
+    required_children: &[],
+    single_children: &[],
+    spec: Spec::NONE,
+};
+
+const ATTRIBUTE: Schema = Schema {
+    attributes: &[&[
+        Attr::required::<String>("name"),
+        Attr::required::<String>("value"),
+    ]],
+    children: &[],
+    required_children: &[],
+    single_children: &[],
+    spec: Spec::NONE,
+};
+
+const ALIAS: Schema = Schema {
+    attributes: &[
+        &[
+            Attr::required::<String>("name"),
+            Attr::required::<String>("c:type"),
+        ],
+        INFO_ATTRS,
+    ],
+    children: &[DOC_CHILDREN, ATTRIBUTE_CHILDREN, ANY_TYPE_CHILDREN],
+    required_children: ANY_TYPE,
+    single_children: &[SINGLE_DOC_CHILDREN, ANY_TYPE],
+    spec: Spec::NONE,
+};
+
+const ARRAY: Schema = Schema {
+    attributes: &[&[
+        Attr::optional::<String>("name"),
+        Attr::optional::<bool>("zero-terminated"),
+        Attr::optional::<u16>("fixed-size"),
+        Attr::optional::<bool>("introspectable"),
+        Attr::optional::<u32>("length"),
+        Attr::optional::<String>("c:type"),
+    ]],
+    children: &[ANY_TYPE_CHILDREN],
+    required_children: ANY_TYPE,
+    single_children: &[ANY_TYPE],
+    spec: Spec::NONE,
+};
+
+const TYPE: Schema = Schema {
+    attributes: &[&[
+        Attr::optional::<String>("name"),
+        Attr::optional::<String>("c:type"),
+        Attr::optional::<bool>("introspectable"),
+    ]],
+    children: &[DOC_CHILDREN, ANY_TYPE_CHILDREN],
+    required_children: &[],
+    single_children: &[SINGLE_DOC_CHILDREN],
+    spec: Spec::NONE,
+};
+
+const CONSTANT: Schema = Schema {
+    attributes: &[
+        &[
+            Attr::required::<String>("name"),
+            Attr::required::<String>("value"),
+            Attr::optional::<String>("c:type"),
+            Attr::optional::<String>("c:identifier"),
+        ],
+        INFO_ATTRS,
+    ],
+    children: &[DOC_CHILDREN, ATTRIBUTE_CHILDREN, ANY_TYPE_CHILDREN],
+    required_children: ANY_TYPE,
+    single_children: &[SINGLE_DOC_CHILDREN, ANY_TYPE],
+    spec: Spec::NONE,
+};
+
+const ENUMERATION: Schema = Schema {
+    attributes: &[
+        &[
+            Attr::required::<String>("name"),
+            Attr::required::<String>("c:type"),
+            Attr::optional::<String>("glib:type-name"),
+            Attr::optional::<String>("glib:get-type"),
+            Attr::optional::<String>("glib:error-domain"),
+        ],
+        INFO_ATTRS,
+    ],
+    children: &[
+        DOC_CHILDREN,
+        ATTRIBUTE_CHILDREN,
+        &[
+            ("member", Kind::Member),
+            ("function", Kind::Function),
+            ("function-inline", Kind::Function),
+        ],
+    ],
+    required_children: &[],
+    single_children: &[SINGLE_DOC_CHILDREN],
+    spec: Spec::NONE,
+};
+
+const BITFIELD: Schema = Schema {
+    attributes: &[
+        &[
+            Attr::required::<String>("name"),
+            Attr::required::<String>("c:type"),
+            Attr::optional::<String>("glib:type-name"),
+            Attr::optional::<String>("glib:get-type"),
+        ],
+        INFO_ATTRS,
+    ],
+    children: ENUMERATION.children,
+    required_children: &[],
+    single_children: &[SINGLE_DOC_CHILDREN],
+    spec: Spec::NONE,
+};
+
+const MEMBER: Schema = Schema {
+    attributes: &[
+        &[
+            Attr::required::<String>("name"),
+            Attr::required::<String>("value"),
+            Attr::required::<String>("c:identifier"),
+            Attr::optional::<String>("glib:nick"),
+            Attr::optional::<String>("glib:name"),
+        ],
+        INFO_ATTRS,
+    ],
+    children: &[DOC_CHILDREN, ATTRIBUTE_CHILDREN],
+    required_children: &[],
+    single_children: &[SINGLE_DOC_CHILDREN],
+    spec: Spec::NONE,
+};
+
+const BOXED: Schema = Schema {
+    attributes: &[
+        &[
+            Attr::required::<String>("glib:name"),
+            Attr::optional::<String>("glib:type-name"),
+            Attr::optional::<String>("glib:get-type"),
+            Attr::optional::<String>("c:symbol-prefix"),
+            Attr::optional::<bool>("foreign").extension(),
+            Attr::optional::<String>("glib:is-gtype-struct-for").extension(),
+            Attr::optional::<String>("copy-function").extension(),
+            Attr::optional::<String>("free-function").extension(),
+        ],
+        INFO_ATTRS,
+    ],
+    children: &[
+        DOC_CHILDREN,
+        ATTRIBUTE_CHILDREN,
+        &[
+            ("function", Kind::Function),
+            ("function-inline", Kind::Function),
+        ],
+    ],
+    required_children: &[],
+    single_children: &[SINGLE_DOC_CHILDREN],
+    spec: Spec::NONE,
+};
+
+const CALLBACK: Schema = Schema {
+    attributes: &[
+        &[
+            Attr::required::<String>("name"),
+            Attr::optional::<String>("c:type"),
+            Attr::optional::<bool>("throws"),
+        ],
+        INFO_ATTRS,
+    ],
+    children: &[DOC_CHILDREN, ATTRIBUTE_CHILDREN, CALLABLE_CHILDREN],
+    required_children: &[&["return-value"]],
+    single_children: &[SINGLE_DOC_CHILDREN, SINGLE_CALLABLE_CHILDREN],
+    spec: Spec::NONE,
+};
+
+const FUNCTION: Schema = Schema {
+    attributes: &[CALLABLE_ATTRS, INFO_ATTRS],
+    children: &[DOC_CHILDREN, ATTRIBUTE_CHILDREN, CALLABLE_CHILDREN],
+    required_children: &[&["return-value"]],
+    single_children: &[SINGLE_DOC_CHILDREN, SINGLE_CALLABLE_CHILDREN],
+    spec: Spec::NONE,
+};
+
+const FUNCTION_MACRO: Schema = Schema {
+    attributes: &[CALLABLE_ATTRS, INFO_ATTRS],
+    children: &[
+        DOC_CHILDREN,
+        ATTRIBUTE_CHILDREN,
+        &[("parameters", Kind::Parameters)],
+    ],
+    required_children: &[],
+    single_children: &[SINGLE_DOC_CHILDREN, &[&["parameters"]]],
+    spec: Spec::NONE,
+};
+
+const METHOD: Schema = Schema {
+    attributes: &[
+        &[
+            Attr::optional::<String>("glib:get-property"),
+            Attr::optional::<String>("glib:set-property"),
+        ],
+        CALLABLE_ATTRS,
+        INFO_ATTRS,
+    ],
+    children: &[DOC_CHILDREN, ATTRIBUTE_CHILDREN, CALLABLE_CHILDREN],
+    required_children: &[&["return-value"]],
+    single_children: &[SINGLE_DOC_CHILDREN, SINGLE_CALLABLE_CHILDREN],
+    spec: Spec::NONE,
+};
+
+const VIRTUAL_METHOD: Schema = Schema {
+    attributes: &[
+        &[Attr::optional::<String>("invoker")],
+        CALLABLE_ATTRS,
+        INFO_ATTRS,
+    ],
+    children: &[DOC_CHILDREN, ATTRIBUTE_CHILDREN, CALLABLE_CHILDREN],
+    required_children: &[&["return-value"]],
+    single_children: &[SINGLE_DOC_CHILDREN, SINGLE_CALLABLE_CHILDREN],
+    spec: Spec::NONE,
+};
+
+const SIGNAL: Schema = Schema {
+    attributes: &[
+        &[
+            Attr::required::<String>("name"),
+            Attr::optional::<bool>("detailed"),
+            Attr::optional::<SignalEmission>("when"),
+            Attr::optional::<bool>("action"),
+            Attr::optional::<bool>("no-hooks"),
+            Attr::optional::<bool>("no-recurse"),
+            Attr::optional::<String>("emitter"),
+        ],
+        INFO_ATTRS,
+    ],
+    children: &[DOC_CHILDREN, ATTRIBUTE_CHILDREN, CALLABLE_CHILDREN],
+    required_children: &[&["return-value"]],
+    single_children: &[SINGLE_DOC_CHILDREN, SINGLE_CALLABLE_CHILDREN],
+    spec: Spec::NONE,
+};
+
+const PARAMETERS: Schema = Schema {
+    attributes: &[],
+    children: &[&[
+        ("instance-parameter", Kind::InstanceParameter),
+        ("parameter", Kind::Parameter),
+    ]],
+    required_children: &[],
+    single_children: &[&[&["instance-parameter"]]],
+    spec: Spec::NONE,
+};
+
+const PARAMETER: Schema = Schema {
+    attributes: &[&[
+        Attr::required::<String>("name"),
+        Attr::optional::<TransferOwnership>("transfer-ownership"),
+        Attr::optional::<bool>("nullable"),
+        Attr::optional::<bool>("allow-none"),
+        Attr::optional::<bool>("introspectable"),
+        Attr::optional::<FunctionScope>("scope"),
+        Attr::optional::<usize>("closure"),
+        Attr::optional::<usize>("destroy"),
+        Attr::optional::<Direction>("direction"),
+        Attr::optional::<bool>("caller-allocates"),
+        Attr::optional::<bool>("optional"),
+        Attr::optional::<bool>("skip"),
+    ]],
+    children: &[
+        DOC_CHILDREN,
+        ATTRIBUTE_CHILDREN,
+        ANY_TYPE_CHILDREN,
+        &[("varargs", Kind::VarArgs)],
+    ],
+    required_children: &[],
+    single_children: &[SINGLE_DOC_CHILDREN, &[&["type", "array", "varargs"]]],
+    spec: Spec::NONE,
+};
+
+const INSTANCE_PARAMETER: Schema = Schema {
+    attributes: &[&[
+        Attr::required::<String>("name"),
+        Attr::optional::<TransferOwnership>("transfer-ownership"),
+        Attr::optional::<bool>("nullable"),
+        Attr::optional::<bool>("allow-none"),
+        Attr::optional::<Direction>("direction"),
+        Attr::optional::<bool>("caller-allocates"),
+    ]],
+    children: &[DOC_CHILDREN, &[("type", Kind::Type)]],
+    required_children: &[],
+    single_children: &[SINGLE_DOC_CHILDREN, &[&["type"]]],
+    spec: Spec {
+        extensions: &[],
+        required_children: &[&["type"]],
+        single_children: &[],
+    },
+};
+
+const RETURN_VALUE: Schema = Schema {
+    attributes: &[&[
+        Attr::optional::<bool>("introspectable"),
+        Attr::optional::<bool>("nullable"),
+        Attr::optional::<usize>("closure"),
+        Attr::optional::<FunctionScope>("scope"),
+        Attr::optional::<usize>("destroy"),
+        Attr::optional::<bool>("skip"),
+        Attr::optional::<bool>("allow-none"),
+        Attr::optional::<TransferOwnership>("transfer-ownership"),
+    ]],
+    children: &[DOC_CHILDREN, ATTRIBUTE_CHILDREN, ANY_TYPE_CHILDREN],
+    required_children: ANY_TYPE,
+    single_children: &[SINGLE_DOC_CHILDREN, ANY_TYPE],
+    spec: Spec::NONE,
+};
+
+const PROPERTY: Schema = Schema {
+    attributes: &[
+        &[
+            Attr::required::<String>("name"),
+            Attr::optional::<bool>("readable"),
+            Attr::optional::<bool>("writable"),
+            Attr::optional::<bool>("construct"),
+            Attr::optional::<bool>("construct-only"),
+            Attr::optional::<String>("setter"),
+            Attr::optional::<String>("getter"),
+            Attr::optional::<String>("default-value"),
+            Attr::optional::<TransferOwnership>("transfer-ownership"),
+        ],
+        INFO_ATTRS,
+    ],
+    children: &[DOC_CHILDREN, ATTRIBUTE_CHILDREN, ANY_TYPE_CHILDREN],
+    required_children: ANY_TYPE,
+    single_children: &[SINGLE_DOC_CHILDREN, ANY_TYPE],
+    spec: Spec::NONE,
+};
+
+const FIELD: Schema = Schema {
+    attributes: &[
+        &[
+            Attr::required::<String>("name"),
+            Attr::optional::<bool>("readable"),
+            Attr::optional::<bool>("writable"),
+            Attr::optional::<bool>("nullable"),
+            Attr::optional::<bool>("private"),
+            Attr::optional::<u8>("bits"),
+        ],
+        INFO_ATTRS,
+    ],
+    children: &[
+        DOC_CHILDREN,
+        ATTRIBUTE_CHILDREN,
+        ANY_TYPE_CHILDREN,
+        &[("callback", Kind::Callback)],
+    ],
+    required_children: &[&["type", "array", "callback"]],
+    single_children: &[SINGLE_DOC_CHILDREN, &[&["type", "array", "callback"]]],
+    spec: Spec::NONE,
+};
+
+const CLASS: Schema = Schema {
+    attributes: &[
+        &[
+            Attr::required::<String>("name"),
+            Attr::optional::<String>("c:symbol-prefix"),
+            Attr::optional::<String>("c:type"),
+            Attr::optional::<String>("parent"),
+            Attr::required::<String>("glib:type-name"),
+            Attr::required::<String>("glib:get-type"),
+            Attr::optional::<String>("glib:type-struct"),
+            Attr::optional::<bool>("glib:fundamental"),
+            Attr::optional::<String>("glib:ref-func"),
+            Attr::optional::<String>("glib:unref-func"),
+            Attr::optional::<String>("glib:set-value-func"),
+            Attr::optional::<String>("glib:get-value-func"),
+            Attr::optional::<bool>("abstract"),
+            Attr::optional::<bool>("final"),
+        ],
+        INFO_ATTRS,
+    ],
+    children: &[
+        DOC_CHILDREN,
+        ATTRIBUTE_CHILDREN,
+        &[
+            ("implements", Kind::Implements),
+            ("function-inline", Kind::Function),
+            ("method-inline", Kind::Function),
+            ("property", Kind::Property),
+            ("glib:signal", Kind::Signal),
+            ("virtual-method", Kind::VirtualMethod),
+            ("constant", Kind::Constant),
+        ],
+        COMPOUND_FIELDS,
+        CALLABLES,
+    ],
+    required_children: &[],
+    single_children: &[SINGLE_DOC_CHILDREN],
+    spec: Spec::NONE,
+};
+
+const INTERFACE: Schema = Schema {
+    attributes: &[
+        &[
+            Attr::required::<String>("name"),
+            Attr::optional::<String>("c:symbol-prefix"),
+            Attr::optional::<String>("c:type"),
+            Attr::required::<String>("glib:type-name"),
+            Attr::required::<String>("glib:get-type"),
+            Attr::optional::<String>("glib:type-struct"),
+        ],
+        INFO_ATTRS,
+    ],
+    children: &[
+        DOC_CHILDREN,
+        ATTRIBUTE_CHILDREN,
+        &[
+            ("prerequisite", Kind::Prerequisite),
+            ("implements", Kind::Implements),
+            ("function-inline", Kind::Function),
+            ("method-inline", Kind::Function),
+            ("virtual-method", Kind::VirtualMethod),
+            ("property", Kind::Property),
+            ("glib:signal", Kind::Signal),
+            ("constant", Kind::Constant),
+        ],
+        COMPOUND_FIELDS,
+        CALLABLES,
+    ],
+    required_children: &[],
+    single_children: &[SINGLE_DOC_CHILDREN],
+    spec: Spec {
+        extensions: &["union", "record"],
+        required_children: &[],
+        single_children: &[&["constructor"]],
+    },
+};
+
+const RECORD: Schema = Schema {
+    attributes: &[
+        &[
+            Attr::optional::<String>("name").spec_required(),
+            Attr::optional::<String>("c:type"),
+            Attr::optional::<bool>("disguised"),
+            Attr::optional::<bool>("pointer"),
+            Attr::optional::<bool>("opaque"),
+            Attr::optional::<bool>("foreign"),
+            Attr::optional::<String>("glib:is-gtype-struct-for"),
+            Attr::optional::<String>("glib:type-name"),
+            Attr::optional::<String>("glib:get-type"),
+            Attr::optional::<String>("c:symbol-prefix"),
+            Attr::optional::<String>("copy-function"),
+            Attr::optional::<String>("free-function"),
+        ],
+        INFO_ATTRS,
+    ],
+    children: &[
+        DOC_CHILDREN,
+        ATTRIBUTE_CHILDREN,
+        &[
+            ("function-inline", Kind::Function),
+            ("method-inline", Kind::Function),
+        ],
+        COMPOUND_FIELDS,
+        CALLABLES,
+    ],
+    required_children: &[],
+    single_children: &[SINGLE_DOC_CHILDREN],
+    spec: Spec {
+        extensions: &["callback", "record"],
+        required_children: &[],
+        single_children: &[],
+    },
+};
+
+const UNION: Schema = Schema {
+    attributes: &[
+        &[
+            Attr::optional::<String>("name"),
+            Attr::optional::<String>("c:type"),
+            Attr::optional::<String>("c:symbol-prefix"),
+            Attr::optional::<String>("glib:type-name"),
+            Attr::optional::<String>("glib:get-type"),
+            Attr::optional::<String>("copy-function"),
+            Attr::optional::<String>("free-function"),
+        ],
+        INFO_ATTRS,
+    ],
+    children: &[
+        DOC_CHILDREN,
+        ATTRIBUTE_CHILDREN,
+        &[
+            ("function-inline", Kind::Function),
+            ("method-inline", Kind::Function),
+        ],
+        COMPOUND_FIELDS,
+        CALLABLES,
+    ],
+    required_children: &[],
+    single_children: &[SINGLE_DOC_CHILDREN],
+    spec: Spec {
+        extensions: &["callback", "union"],
+        required_children: &[],
+        single_children: &[],
+    },
+};
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use xmlserde::quick_xml::{
+        events::{BytesStart, Event},
+        Reader,
+    };
+
+    use super::*;
+    use crate::{ParseOptions, Repository};
+
+    /// A valid value for an attribute.
+    fn value(attr: &Attr) -> &'static str {
+        const CANDIDATES: &[&str] = &[
+            "1",
+            "1.0",
+            "in",
+            "none",
+            "call",
+            "first",
+            "Stable",
+            "gtk-doc-markdown",
+        ];
+        attr.spec_values
+            .into_iter()
+            .flatten()
+            .chain(CANDIDATES)
+            .copied()
+            .find(|value| attr.check(value).is_ok())
+            .unwrap_or_else(|| panic!("no valid value for `{}`", attr.name))
+    }
+
+    /// A document using every attribute and child of the schema.
+    struct Fixture {
+        output: String,
+        /// Which child of the groups of which at most one can be present is
+        /// picked.
+        variant: usize,
+        /// The size of the largest of those groups.
+        widest: usize,
+        ancestors: Vec<Kind>,
+    }
+
+    /// Write an element of the given kind with all its attributes and
+    /// children. Kinds already written by an ancestor only get the children
+    /// they require, so the document stays finite.
+    fn element(fixture: &mut Fixture, tag: &str, kind: Kind) {
+        let Fixture {
+            output,
+            variant,
+            widest,
+            ancestors,
+        } = fixture;
+        let schema = kind.schema();
+        output.push_str(&format!("<{tag}"));
+        for attr in schema.attributes() {
+            output.push_str(&format!(r#" {}="{}""#, attr.name, value(attr)));
+        }
+        output.push('>');
+        if matches!(kind, Kind::Documentation | Kind::DocText) {
+            output.push_str("text");
+        }
+        let is_nested = ancestors.contains(&kind);
+        ancestors.push(kind);
+        let mut written = BTreeSet::new();
+        for group in schema.required_children() {
+            let tag = group
+                .iter()
+                .find(|tag| !ancestors.contains(&kind.child(tag).unwrap().1))
+                .unwrap_or(&group[0]);
+            written.insert(*tag);
+        }
+        if !is_nested {
+            for (tag, _) in schema.children() {
+                let single = schema.single_children().find(|tags| tags.contains(&tag));
+                let picked = single.map_or(tag, |tags| tags[*variant % tags.len()]);
+                *widest = (*widest).max(single.map_or(1, <[_]>::len));
+                if !single.is_some_and(|tags| tags.iter().any(|tag| written.contains(tag))) {
+                    written.insert(picked);
+                }
+            }
+        }
+        for (tag, child) in schema.children() {
+            if written.contains(tag) {
+                element(fixture, tag, child);
+            }
+        }
+        fixture.ancestors.pop();
+        fixture.output.push_str(&format!("</{tag}>"));
+    }
+
+    /// Describe an element independently of the order of its attributes
+    /// and children.
+    fn canonical(reader: &mut Reader<&[u8]>, tag: &str, attributes: Vec<String>) -> String {
+        let mut children = Vec::new();
+        loop {
+            match reader.read_event().unwrap() {
+                Event::Start(start) => {
+                    let tag = String::from_utf8_lossy(start.name().into_inner()).into_owned();
+                    let attributes = attributes_of(&start);
+                    children.push(canonical(reader, &tag, attributes));
+                }
+                Event::Empty(start) => {
+                    let tag = String::from_utf8_lossy(start.name().into_inner()).into_owned();
+                    let attributes = attributes_of(&start).join(" ");
+                    children.push(format!("<{tag} {attributes}></{tag}>"));
+                }
+                Event::Text(text) => {
+                    let text = String::from_utf8_lossy(&text).trim().to_owned();
+                    if !text.is_empty() {
+                        children.push(text);
+                    }
+                }
+                Event::End(_) | Event::Eof => break,
+                _ => {}
+            }
+        }
+        children.sort();
+        format!(
+            "<{tag} {}>{}</{tag}>",
+            attributes.join(" "),
+            children.concat()
+        )
+    }
+
+    fn attributes_of(start: &BytesStart) -> Vec<String> {
+        let mut attributes = start
+            .attributes()
+            .map(|attr| {
+                let attr = attr.unwrap();
+                let key = String::from_utf8_lossy(attr.key.into_inner()).into_owned();
+                format!("{key}={}", String::from_utf8_lossy(&attr.value))
+            })
+            .collect::<Vec<_>>();
+        attributes.sort();
+        attributes
+    }
+
+    fn normalize(document: &str) -> String {
+        let mut reader = Reader::from_str(document);
+        canonical(&mut reader, "", Vec::new())
+    }
+
+    /// Every attribute and child of the schema is accepted in strict mode,
+    /// read into the model and written back.
+    #[test]
+    fn round_trips_every_declaration() {
+        let mut fixture = Fixture {
+            output: String::new(),
+            variant: 0,
+            widest: 1,
+            ancestors: Vec::new(),
+        };
+        while fixture.variant < fixture.widest {
+            fixture.output.clear();
+            element(&mut fixture, "repository", Kind::Repository);
+            let document = &fixture.output;
+            let (repository, warnings) =
+                Repository::from_str_with_options(document, &ParseOptions::default())
+                    .unwrap_or_else(|e| panic!("{e}\n{document}"));
+            assert!(warnings.is_empty(), "{warnings:?}");
+            assert_eq!(
+                normalize(&repository.to_xml()),
+                normalize(document),
+                "variant {}",
+                fixture.variant
+            );
+            fixture.variant += 1;
+        }
+    }
+}
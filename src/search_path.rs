@@ -0,0 +1,142 @@
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use crate::{compression, repository::NamespaceInclude, ParserError};
+
+/// The directory GIR files are installed to by default.
+const DEFAULT_GIR_DIR: &str = "/usr/share/gir-1.0";
+/// The value `XDG_DATA_DIRS` defaults to when it is unset or empty.
+const DEFAULT_XDG_DATA_DIRS: &str = "/usr/local/share/:/usr/share/";
+
+/// An ordered list of directories to look for GIR files in, where the first
+/// directory containing a file wins.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GirSearchPath {
+    dirs: Vec<PathBuf>,
+}
+
+/// Where a GIR file was found in a [`GirSearchPath`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GirLocation {
+    path: PathBuf,
+    shadowed: Vec<PathBuf>,
+}
+
+impl GirLocation {
+    /// The file picked.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The files with the same name in directories of lower precedence, which
+    /// are ignored.
+    pub fn shadowed(&self) -> &[PathBuf] {
+        &self.shadowed
+    }
+}
+
+impl GirSearchPath {
+    /// A search path made of the given directories only.
+    pub fn new(dirs: impl IntoIterator<Item = impl Into<PathBuf>>) -> Self {
+        let mut search_path = Self::default();
+        search_path.extend(dirs);
+        search_path
+    }
+
+    /// The search path used by gobject-introspection: the given include
+    /// directories first, followed by the directories of `GI_GIR_PATH`, the
+    /// `gir-1.0` directory of `XDG_DATA_HOME` (`~/.local/share` by default),
+    /// then of each of the `XDG_DATA_DIRS` and finally `/usr/share/gir-1.0`.
+    pub fn system(include_dirs: impl IntoIterator<Item = impl Into<PathBuf>>) -> Self {
+        Self::from_env(
+            include_dirs,
+            std::env::var_os("GI_GIR_PATH"),
+            std::env::var_os("XDG_DATA_HOME"),
+            std::env::var_os("HOME"),
+            std::env::var_os("XDG_DATA_DIRS"),
+        )
+    }
+
+    pub(crate) fn from_env(
+        include_dirs: impl IntoIterator<Item = impl Into<PathBuf>>,
+        gi_gir_path: Option<OsString>,
+        xdg_data_home: Option<OsString>,
+        home: Option<OsString>,
+        xdg_data_dirs: Option<OsString>,
+    ) -> Self {
+        let mut search_path = Self::new(include_dirs);
+        if let Some(gi_gir_path) = gi_gir_path {
+            search_path.extend(std::env::split_paths(&gi_gir_path));
+        }
+        let xdg_data_home = xdg_data_home
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| home.map(|home| Path::new(&home).join(".local/share")));
+        if let Some(xdg_data_home) = xdg_data_home {
+            search_path.push(xdg_data_home.join("gir-1.0"));
+        }
+        let xdg_data_dirs = xdg_data_dirs
+            .filter(|dirs| !dirs.is_empty())
+            .unwrap_or_else(|| DEFAULT_XDG_DATA_DIRS.into());
+        search_path.extend(std::env::split_paths(&xdg_data_dirs).map(|dir| dir.join("gir-1.0")));
+        search_path.push(DEFAULT_GIR_DIR);
+        search_path
+    }
+
+    /// Add a directory with the lowest precedence. Empty paths and
+    /// directories already part of the search path are ignored.
+    pub fn push(&mut self, dir: impl Into<PathBuf>) {
+        let dir = dir.into();
+        if dir.as_os_str().is_empty() || self.dirs.iter().any(|d| d == &dir) {
+            return;
+        }
+        self.dirs.push(dir);
+    }
+
+    pub fn dirs(&self) -> &[PathBuf] {
+        &self.dirs
+    }
+
+    /// Look for a file, such as `GObject-2.0.gir`, in the search path.
+    ///
+    /// With the `gz` or `xz` features, a compressed `GObject-2.0.gir.gz` or
+    /// `GObject-2.0.gir.xz` is picked when a directory has no uncompressed
+    /// file.
+    pub fn find(&self, file_name: &str) -> Option<GirLocation> {
+        let mut candidates = self.dirs.iter().filter_map(|dir| {
+            compression::variants(dir.join(file_name)).find(|path| path.is_file())
+        });
+        let path = candidates.next()?;
+        Some(GirLocation {
+            path,
+            shadowed: candidates.collect(),
+        })
+    }
+
+    /// The path of a file, failing if it isn't part of the search path.
+    pub(crate) fn locate(&self, file_name: &str) -> Result<PathBuf, ParserError> {
+        match self.find(file_name) {
+            Some(location) => Ok(location.path),
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("{file_name} not found in the GIR search path"),
+            )
+            .into()),
+        }
+    }
+
+    /// Look for the GIR file of an included namespace.
+    pub fn resolve(&self, include: &NamespaceInclude) -> Option<GirLocation> {
+        self.find(&include.as_package_file())
+    }
+}
+
+impl<P: Into<PathBuf>> Extend<P> for GirSearchPath {
+    fn extend<T: IntoIterator<Item = P>>(&mut self, dirs: T) {
+        for dir in dirs {
+            self.push(dir);
+        }
+    }
+}
@@ -0,0 +1,38 @@
+//! `serde` support for the types with an XML representation, such as the
+//! enumerated attribute values, which are serialized as that representation.
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use xmlserde::XmlValue;
+
+macro_rules! impl_serde_value {
+    ($($rust_type:ty),* $(,)?) => {
+        $(impl Serialize for $rust_type {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&XmlValue::serialize(self))
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $rust_type {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = <String as Deserialize>::deserialize(deserializer)?;
+                <Self as XmlValue>::deserialize(&value).map_err(D::Error::custom)
+            }
+        })*
+    };
+}
+
+impl_serde_value!(
+    crate::Atom,
+    crate::Stability,
+    crate::TransferOwnership,
+    crate::FunctionScope,
+    crate::SignalEmission,
+    crate::Direction,
+    crate::DocFormat,
+);
+
+impl Serialize for crate::Version {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
@@ -1,63 +1,47 @@
-use xmlserde_derives::XmlDeserialize;
-
 use crate::{
     attribute::Attribute,
     documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition},
+    extra::Extra,
     parameter::Parameters,
     prelude::*,
     return_value::ReturnValue,
+    span::NodeSpan,
+    tree::{FromNode, Node},
     version::Version,
+    writer::{Element, ToXml},
     SignalEmission, Stability,
 };
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
-#[xmlserde(root = b"glib:signal")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Signal {
-    #[xmlserde(name = b"name", ty = "attr")]
     name: String,
-    #[xmlserde(name = b"detailed", ty = "attr")]
     detailed: Option<bool>,
-    #[xmlserde(name = b"when", ty = "attr")]
     when: Option<SignalEmission>,
-    #[xmlserde(name = b"action", ty = "attr")]
     action: Option<bool>,
-    #[xmlserde(name = b"no-hooks", ty = "attr")]
     no_hooks: Option<bool>,
-    #[xmlserde(name = b"no-recurse", ty = "attr")]
     no_recurse: Option<bool>,
-    #[xmlserde(name = b"emitter", ty = "attr")]
     emitter: Option<String>,
     // Common attributes
-    #[xmlserde(name = b"introspectable", ty = "attr")]
     introspectable: Option<bool>,
-    #[xmlserde(name = b"deprecated", ty = "attr")]
     deprecated: Option<bool>,
-    #[xmlserde(name = b"version", ty = "attr")]
     version: Option<Version>,
-    #[xmlserde(name = b"deprecated-version", ty = "attr")]
     deprecated_version: Option<Version>,
-    #[xmlserde(name = b"stability", ty = "attr")]
     stability: Option<Stability>,
     // Documentation
-    #[xmlserde(name = b"doc", ty = "child")]
     doc: Option<Documentation>,
-    #[xmlserde(name = b"doc-deprecated", ty = "child")]
     doc_deprecated: Option<DocDeprecated>,
-    #[xmlserde(name = b"doc-stability", ty = "child")]
     doc_stability: Option<DocStability>,
-    #[xmlserde(name = b"doc-version", ty = "child")]
     doc_version: Option<DocVersion>,
-    #[xmlserde(name = b"source-position", ty = "child")]
     source_position: Option<SourcePosition>,
     // Attributes: 0 or more
-    #[xmlserde(name = b"attribute", ty = "child")]
     attributes: Vec<Attribute>,
 
-    #[xmlserde(name = b"return-value", ty = "child")]
     return_value: ReturnValue,
-    #[xmlserde(name = b"parameters", ty = "child", default = "Parameters::default")]
     parameters: Parameters,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Signal {
@@ -101,3 +85,88 @@ impl Signal {
 impl_info!(Signal);
 impl_attributable!(Signal);
 impl_documentable!(Signal);
+
+impl_extensible!(Signal);
+impl_spanned!(Signal);
+impl_builder!(Signal => SignalBuilder {
+    new {
+        name: String => name_mut;
+    }
+    optional {
+        detailed: bool => detailed, detailed_mut;
+        when: SignalEmission => when, when_mut;
+        action: bool => action, action_mut;
+        no_hooks: bool => no_hooks, no_hooks_mut;
+        no_recurse: bool => no_recurse, no_recurse_mut;
+        emitter: String => emitter, emitter_mut;
+        introspectable: bool => introspectable, introspectable_mut;
+        deprecated: bool => deprecated, deprecated_mut;
+        version: Version => version, version_mut;
+        deprecated_version: Version => deprecated_version, deprecated_version_mut;
+        stability: Stability => stability, stability_mut;
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+    }
+    list {
+        attributes: Attribute => attribute, attributes_mut;
+    }
+    value {
+        return_value: ReturnValue => return_value, return_value_mut;
+        parameters: Parameters => parameters, parameters_mut;
+    }
+    hidden { span, extra }
+});
+impl_parameters_builder!(SignalBuilder);
+impl FromNode for Signal {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.attr("name"),
+            detailed: node.opt_attr("detailed"),
+            when: node.opt_attr("when"),
+            action: node.opt_attr("action"),
+            no_hooks: node.opt_attr("no-hooks"),
+            no_recurse: node.opt_attr("no-recurse"),
+            emitter: node.opt_attr("emitter"),
+            introspectable: node.opt_attr("introspectable"),
+            deprecated: node.opt_attr("deprecated"),
+            version: node.opt_attr("version"),
+            deprecated_version: node.opt_attr("deprecated-version"),
+            stability: node.opt_attr("stability"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            attributes: node.children("attribute"),
+            return_value: node.required("return-value"),
+            parameters: node.child("parameters").unwrap_or_default(),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Signal {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .attr("name", &self.name)
+            .opt_attr("detailed", &self.detailed)
+            .opt_attr("when", &self.when)
+            .opt_attr("action", &self.action)
+            .opt_attr("no-hooks", &self.no_hooks)
+            .opt_attr("no-recurse", &self.no_recurse)
+            .opt_attr("emitter", &self.emitter)
+            .info(self)
+            .documentation(self)
+            .attributes(self)
+            .child("return-value", &self.return_value)
+            .opt_child(
+                "parameters",
+                (!self.parameters.is_empty()).then_some(&self.parameters),
+            )
+            .extra(self)
+    }
+}
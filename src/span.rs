@@ -0,0 +1,145 @@
+use std::{ops::Range, path::Path, sync::Arc};
+
+/// Where a node was found in the document it was parsed from.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Span {
+    file: Option<Arc<Path>>,
+    start: usize,
+    end: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Span {
+    /// The span of an element whose end isn't known yet.
+    pub(crate) fn open(file: Option<Arc<Path>>, start: usize, line: usize, column: usize) -> Self {
+        Self {
+            file,
+            start,
+            end: start,
+            line,
+            column,
+        }
+    }
+
+    pub(crate) fn close(&mut self, end: usize) {
+        self.end = end;
+    }
+
+    /// The file the document was read from, if any.
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    /// The byte offset of the start tag of the element.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// The byte offset following the end tag of the element.
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// The line of the start tag, starting at 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The column of the start tag, in characters and starting at 1.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+/// The span of a node. It's metadata about where the node was found rather
+/// than part of its content, so it's left out when comparing and hashing
+/// nodes.
+#[derive(Clone, Default)]
+pub(crate) struct NodeSpan(Option<Span>);
+
+impl PartialEq for NodeSpan {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for NodeSpan {}
+
+impl std::hash::Hash for NodeSpan {
+    fn hash<H: std::hash::Hasher>(&self, _state: &mut H) {}
+}
+
+impl NodeSpan {
+    pub(crate) fn new(span: Option<Span>) -> Self {
+        Self(span)
+    }
+
+    pub(crate) fn get(&self) -> Option<&Span> {
+        self.0.as_ref()
+    }
+}
+
+impl std::fmt::Debug for NodeSpan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Spans are only kept by binary formats, such as the snapshots of the
+/// cache. Human-readable formats like JSON leave them out so the output only
+/// depends on the content.
+#[cfg(feature = "serde")]
+impl serde::Serialize for NodeSpan {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let span = self.0.as_ref().filter(|_| !serializer.is_human_readable());
+        // Paths can't be serialized when they aren't valid UTF-8, as often
+        // found on Unix, so they are written as raw bytes.
+        span.map(|span| {
+            let file = span.file().map(|file| file.as_os_str().as_encoded_bytes());
+            (file, span.start, span.end, span.line, span.column)
+        })
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for NodeSpan {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        type Fields = (Option<Vec<u8>>, usize, usize, usize, usize);
+        let span = Option::<Fields>::deserialize(deserializer)?;
+        Ok(Self(span.map(|(file, start, end, line, column)| Span {
+            file: file.map(|file| Arc::from(path_from_bytes(file))),
+            start,
+            end,
+            line,
+            column,
+        })))
+    }
+}
+
+/// The path written as bytes by [`NodeSpan`]'s `Serialize` implementation.
+/// Only Unix paths can be rebuilt from any bytes, the others are decoded as
+/// UTF-8, which they are unless they contain unpaired surrogates.
+#[cfg(feature = "serde")]
+fn path_from_bytes(bytes: Vec<u8>) -> std::path::PathBuf {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+
+        std::ffi::OsString::from_vec(bytes).into()
+    }
+    #[cfg(not(unix))]
+    {
+        match String::from_utf8(bytes) {
+            Ok(path) => path.into(),
+            Err(error) => String::from_utf8_lossy(error.as_bytes())
+                .into_owned()
+                .into(),
+        }
+    }
+}
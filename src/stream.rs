@@ -0,0 +1,740 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+use xmlserde::quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+
+use crate::{
+    compression,
+    documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition},
+    prelude::*,
+    repository::DocFormatChild,
+    schema::Kind,
+    tree::{FromNode, Node, Origin, Tree},
+    Alias, Attribute, BitField, Boxed, Callback, Class, Constant, DocFormat, DocSection,
+    Enumeration, Field, Function, FunctionInline, FunctionMacro, HeaderInclude, Implements,
+    Interface, Method, MethodInline, Namespace, NamespaceInclude, Package, ParseError,
+    ParseOptions, ParseWarning, ParserError, Prerequisite, Property, Record, Repository, Signal,
+    Span, Stability, Union, Version, VirtualMethod,
+};
+
+/// An element read by a [`GirStream`].
+///
+/// The types containing other types or functions, from the repository and
+/// its namespace to the classes, interfaces, records and unions it defines,
+/// are split into a start event and an end event. The start event holds a
+/// header with the attributes, documentation and [`Attribute`]s of the
+/// element, its other children following as separate events. Its
+/// [`Span`] ends with that header.
+///
+/// The other elements are read as a whole.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[allow(clippy::large_enum_variant)]
+pub enum GirEvent {
+    StartRepository(RepositoryAttributes),
+    EndRepository,
+    Include(NamespaceInclude),
+    HeaderInclude(HeaderInclude),
+    Package(Package),
+    DocFormat(DocFormat),
+    StartNamespace(NamespaceHeader),
+    EndNamespace,
+    StartClass(ClassHeader),
+    EndClass,
+    StartInterface(InterfaceHeader),
+    EndInterface,
+    StartRecord(RecordHeader),
+    EndRecord,
+    StartUnion(UnionHeader),
+    EndUnion,
+    Alias(Alias),
+    Attribute(Attribute),
+    BitField(BitField),
+    Boxed(Boxed),
+    Callback(Callback),
+    Constant(Constant),
+    Constructor(Function),
+    DocSection(DocSection),
+    Enumeration(Enumeration),
+    Field(Field),
+    Function(Function),
+    FunctionInline(FunctionInline),
+    FunctionMacro(FunctionMacro),
+    Implements(Implements),
+    Method(Method),
+    MethodInline(MethodInline),
+    Prerequisite(Prerequisite),
+    Property(Property),
+    Signal(Signal),
+    VirtualMethod(VirtualMethod),
+    /// A union nested in a record or in another union.
+    NestedUnion(Union),
+    /// A record nested in a union or in another record.
+    NestedRecord(Record),
+}
+
+/// Define the header of a container read by a [`GirStream`], giving access to
+/// the attributes of the element only.
+macro_rules! header {
+    (
+        $(#[$meta:meta])*
+        $header:ident($rust_type:ident) { $($method:ident -> $ret:ty;)* }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        pub struct $header($rust_type);
+
+        impl $header {
+            $(pub fn $method(&self) -> $ret {
+                self.0.$method()
+            })*
+        }
+
+        impl Extensible for $header {
+            fn extra_attributes(&self) -> &[(String, String)] {
+                self.0.extra_attributes()
+            }
+
+            fn extra_children(&self) -> &[String] {
+                self.0.extra_children()
+            }
+        }
+
+        impl Spanned for $header {
+            fn span(&self) -> Option<&Span> {
+                self.0.span()
+            }
+        }
+    };
+}
+
+/// Implement [`Attributable`] for headers, and [`Documentable`] and [`Info`]
+/// for those of documented elements.
+macro_rules! impl_header_info {
+    (attributes: $($header:ident),*) => {
+        $(impl Attributable for $header {
+            fn attributes(&self) -> &[Attribute] {
+                self.0.attributes()
+            }
+        })*
+    };
+    ($($header:ident),*) => {
+        impl_header_info!(attributes: $($header),*);
+
+        $(impl Documentable for $header {
+            fn doc(&self) -> Option<&Documentation> {
+                self.0.doc()
+            }
+            fn doc_deprecated(&self) -> Option<&DocDeprecated> {
+                self.0.doc_deprecated()
+            }
+            fn doc_stability(&self) -> Option<&DocStability> {
+                self.0.doc_stability()
+            }
+            fn doc_version(&self) -> Option<&DocVersion> {
+                self.0.doc_version()
+            }
+            fn source_position(&self) -> Option<&SourcePosition> {
+                self.0.source_position()
+            }
+        }
+
+        impl Info for $header {
+            fn is_introspectable(&self) -> bool {
+                self.0.is_introspectable()
+            }
+            fn is_deprecated(&self) -> bool {
+                self.0.is_deprecated()
+            }
+            fn version(&self) -> Option<&Version> {
+                Info::version(&self.0)
+            }
+            fn deprecated_version(&self) -> Option<&Version> {
+                self.0.deprecated_version()
+            }
+            fn stability(&self) -> Option<Stability> {
+                self.0.stability()
+            }
+        })*
+    };
+}
+
+header!(
+    /// The attributes of the `<repository>` element.
+    RepositoryAttributes(Repository) {
+        version -> Option<&Version>;
+        c_identifier_prefixes -> impl Iterator<Item = &str>;
+        c_symbol_prefixes -> impl Iterator<Item = &str>;
+    }
+);
+
+header!(
+    /// The attributes and [`Attribute`]s of a `<namespace>` element.
+    NamespaceHeader(Namespace) {
+        name -> &str;
+        version -> &Version;
+        c_identifier_prefixes -> impl Iterator<Item = &str>;
+        c_symbol_prefixes -> impl Iterator<Item = &str>;
+        shared_library -> Option<&str>;
+    }
+);
+
+header!(
+    /// The attributes, documentation and [`Attribute`]s of a `<class>`
+    /// element.
+    ClassHeader(Class) {
+        name -> &str;
+        is_fundamental -> bool;
+        is_final -> bool;
+        is_abstract -> bool;
+        symbol_prefix -> Option<&str>;
+        c_type -> Option<&str>;
+        parent -> Option<&str>;
+        g_type_name -> &str;
+        g_get_type -> &str;
+        g_type_struct -> Option<&str>;
+        g_ref_func -> Option<&str>;
+        g_unref_func -> Option<&str>;
+        g_set_value_func -> Option<&str>;
+        g_get_value_func -> Option<&str>;
+    }
+);
+
+header!(
+    /// The attributes, documentation and [`Attribute`]s of an `<interface>`
+    /// element.
+    InterfaceHeader(Interface) {
+        name -> &str;
+        symbol_prefix -> Option<&str>;
+        c_type -> Option<&str>;
+        g_type_name -> &str;
+        g_get_type -> &str;
+        g_type_struct -> Option<&str>;
+    }
+);
+
+header!(
+    /// The attributes, documentation and [`Attribute`]s of a `<record>`
+    /// element.
+    RecordHeader(Record) {
+        name -> Option<&str>;
+        c_type -> Option<&str>;
+        is_disguised -> bool;
+        is_opaque -> bool;
+        is_pointer -> bool;
+        is_foreign -> bool;
+        g_is_gtype_struct_for -> Option<&str>;
+        g_type_name -> Option<&str>;
+        g_get_type -> Option<&str>;
+        symbol_prefix -> Option<&str>;
+        copy_function -> Option<&str>;
+        free_function -> Option<&str>;
+    }
+);
+
+header!(
+    /// The attributes, documentation and [`Attribute`]s of a `<union>`
+    /// element.
+    UnionHeader(Union) {
+        name -> Option<&str>;
+        c_type -> Option<&str>;
+        c_symbol_prefix -> Option<&str>;
+        g_type_name -> Option<&str>;
+        g_get_type -> Option<&str>;
+        copy_function -> Option<&str>;
+        free_function -> Option<&str>;
+    }
+);
+
+impl_header_info!(attributes: NamespaceHeader);
+impl_header_info!(ClassHeader, InterfaceHeader, RecordHeader, UnionHeader);
+
+/// Record the bytes consumed from a reader, so the source of the element
+/// being read is at hand.
+struct Recorder<R> {
+    inner: R,
+    recorded: Vec<u8>,
+}
+
+impl<R: BufRead> Read for Recorder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.recorded.extend_from_slice(&buf[..read]);
+        Ok(read)
+    }
+}
+
+impl<R: BufRead> BufRead for Recorder<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amount: usize) {
+        // The data was just returned by `fill_buf`, so this doesn't read.
+        if let Ok(buf) = self.inner.fill_buf() {
+            self.recorded
+                .extend_from_slice(&buf[..amount.min(buf.len())]);
+        }
+        self.inner.consume(amount);
+    }
+}
+
+struct Container {
+    kind: Kind,
+    /// The element as it appears in error paths.
+    segment: String,
+}
+
+/// An element whose start tag was read.
+struct Child {
+    offset: usize,
+    start: BytesStart<'static>,
+    is_empty: bool,
+}
+
+/// Read a GIR document one element at a time, as an iterator of
+/// [`GirEvent`]s.
+///
+/// Only the element being read is kept in memory, making it possible to
+/// go through large sets of GIR files with bounded memory. The elements are
+/// checked like [`Repository::from_path`](crate::Repository::from_path)
+/// does, except for the unknown elements which are skipped instead of being
+/// kept as extra content, with a warning in lenient mode. The documentation
+/// of a type following its first member can't be part of its header anymore,
+/// and is skipped with a warning.
+///
+/// The iterator stops after the first error.
+pub struct GirStream<'a> {
+    reader: Reader<Recorder<BufReader<Box<dyn Read + 'a>>>>,
+    path: Option<PathBuf>,
+    options: ParseOptions,
+    buf: Vec<u8>,
+    /// The offset of the first recorded byte in the document.
+    base: usize,
+    /// The line of the first recorded byte.
+    line: usize,
+    /// The number of characters preceding the first recorded byte on its
+    /// line.
+    column: usize,
+    containers: Vec<Container>,
+    /// Whether the root element was read, as it must be the only one.
+    has_root: bool,
+    /// A child read while looking for the end of the header of its parent.
+    child: Option<Child>,
+    /// The end of an empty container.
+    queued: Option<GirEvent>,
+    warnings: Vec<ParseWarning>,
+    done: bool,
+}
+
+impl GirStream<'static> {
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, ParserError> {
+        Self::from_path_with_options(path, &ParseOptions::default())
+    }
+
+    pub fn from_path_with_options(
+        path: impl AsRef<Path>,
+        options: &ParseOptions,
+    ) -> Result<Self, ParserError> {
+        let file = BufReader::new(File::open(path.as_ref())?);
+        let mut stream = Self::from_reader_with_options(file, options)?;
+        stream.path = Some(path.as_ref().to_path_buf());
+        Ok(stream)
+    }
+}
+
+impl<'a> GirStream<'a> {
+    /// Read a GIR document from a reader, decompressing it first if it is
+    /// gzip or xz compressed and the matching feature is enabled.
+    pub fn from_reader(reader: impl BufRead + 'a) -> Result<Self, ParserError> {
+        Self::from_reader_with_options(reader, &ParseOptions::default())
+    }
+
+    pub fn from_reader_with_options(
+        reader: impl BufRead + 'a,
+        options: &ParseOptions,
+    ) -> Result<Self, ParserError> {
+        let recorder = Recorder {
+            inner: BufReader::new(compression::decode(reader)?),
+            recorded: Vec::new(),
+        };
+        Ok(Self {
+            reader: Reader::from_reader(recorder),
+            path: None,
+            options: options.clone(),
+            buf: Vec::new(),
+            base: 0,
+            line: 1,
+            column: 0,
+            containers: Vec::new(),
+            has_root: false,
+            child: None,
+            queued: None,
+            warnings: Vec::new(),
+            done: false,
+        })
+    }
+
+    /// The warnings about the parts of the elements read so far that were
+    /// skipped, either in lenient mode or as misplaced documentation.
+    pub fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+
+    fn offset(&self) -> usize {
+        self.reader.buffer_position() as usize
+    }
+
+    fn recorded(&self) -> &[u8] {
+        &self.reader.get_ref().recorded
+    }
+
+    /// Forget about the bytes before `offset`.
+    fn drain(&mut self, offset: usize) -> Result<(), ParserError> {
+        let recorded = &mut self.reader.get_mut().recorded;
+        let text = std::str::from_utf8(&recorded[..offset - self.base])
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let last_line = match text.rfind('\n') {
+            Some(i) => {
+                self.line += text.matches('\n').count();
+                self.column = 0;
+                &text[i + 1..]
+            }
+            None => text,
+        };
+        self.column += last_line.chars().count();
+        recorded.drain(..offset - self.base);
+        self.base = offset;
+        Ok(())
+    }
+
+    /// The recorded source up to `end`.
+    fn source(&self, end: usize) -> Result<String, ParserError> {
+        let recorded = &self.recorded()[..end - self.base];
+        let recorded = std::str::from_utf8(recorded)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(recorded.to_owned())
+    }
+
+    fn element_path(&self) -> String {
+        self.containers
+            .iter()
+            .skip(1)
+            .map(|container| container.segment.as_str())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    fn relocate(&self, error: ParseError) -> ParseError {
+        let path = self.element_path();
+        error.relocate(self.base, self.line, self.column, &path)
+    }
+
+    fn error(&self, offset: usize, message: String) -> ParserError {
+        self.error_in(&self.element_path(), offset, message).into()
+    }
+
+    fn error_in(&self, element_path: &str, offset: usize, message: String) -> ParseError {
+        let source = String::from_utf8_lossy(self.recorded());
+        let offset = offset.saturating_sub(self.base);
+        let error = ParseError::new(
+            &source,
+            self.path.as_deref(),
+            offset,
+            String::new(),
+            message,
+        );
+        error.relocate(self.base, self.line, self.column, element_path)
+    }
+
+    /// An error about a child of the current container.
+    fn child_error(&self, child: &Child, tag: &str, message: String) -> ParseError {
+        let element_path = match self.element_path() {
+            path if path.is_empty() => tag.to_owned(),
+            path => format!("{path}/{tag}"),
+        };
+        self.error_in(&element_path, child.offset, message)
+    }
+
+    fn read_event(&mut self) -> Result<(usize, Event<'static>), ParserError> {
+        let offset = self.offset();
+        self.buf.clear();
+        match self.reader.read_event_into(&mut self.buf) {
+            Ok(event) => Ok((offset, event.into_owned())),
+            Err(e) => {
+                let offset = self.reader.error_position() as usize;
+                Err(self.error(offset, format!("malformed XML: {e}")))
+            }
+        }
+    }
+
+    /// Read until the end of a child, returning where it ends.
+    fn read_to_end(&mut self, child: &Child) -> Result<usize, ParserError> {
+        if !child.is_empty {
+            self.buf.clear();
+            if let Err(e) = self
+                .reader
+                .read_to_end_into(child.start.name(), &mut self.buf)
+            {
+                let offset = self.reader.error_position() as usize;
+                return Err(self.error(offset, format!("malformed XML: {e}")));
+            }
+        }
+        Ok(self.offset())
+    }
+
+    fn next_event(&mut self) -> Result<Option<GirEvent>, ParserError> {
+        if let Some(event) = self.queued.take() {
+            return Ok(Some(event));
+        }
+        loop {
+            let child = match self.child.take() {
+                Some(child) => child,
+                None => match self.read_event()? {
+                    (offset, Event::Start(start)) => Child {
+                        offset,
+                        start,
+                        is_empty: false,
+                    },
+                    (offset, Event::Empty(start)) => Child {
+                        offset,
+                        start,
+                        is_empty: true,
+                    },
+                    (_, Event::End(_)) => {
+                        let container = self.containers.pop().expect("end of an unopened element");
+                        match end_event(container.kind) {
+                            Some(event) => return Ok(Some(event)),
+                            None => continue,
+                        }
+                    }
+                    (offset, Event::Eof) => {
+                        if self.containers.is_empty() {
+                            return Ok(None);
+                        }
+                        let message = "unexpected end of document".to_owned();
+                        return Err(self.error(offset, message));
+                    }
+                    _ => continue,
+                },
+            };
+            self.drain(child.offset)?;
+            if let Some(event) = self.element(child)? {
+                return Ok(Some(event));
+            }
+        }
+    }
+
+    fn element(&mut self, child: Child) -> Result<Option<GirEvent>, ParserError> {
+        let tag = String::from_utf8_lossy(child.start.name().into_inner()).into_owned();
+        let Some(parent) = self.containers.last().map(|container| container.kind) else {
+            if self.has_root {
+                let message = "unexpected element after the document root".to_owned();
+                return Err(self.error(child.offset, message));
+            }
+            if tag != Kind::ROOT.0 {
+                let message = format!("expected a `{}` element, found `{tag}`", Kind::ROOT.0);
+                return Err(self.error(child.offset, message));
+            }
+            self.has_root = true;
+            return self.container(child, Kind::ROOT.0, Kind::ROOT.1);
+        };
+        let Some((tag, kind)) = parent.child(&tag) else {
+            if !self.options.strict {
+                let message = format!("unknown element `{tag}`");
+                let error = self.child_error(&child, &tag, message);
+                self.warnings.push(ParseWarning::new(error));
+            }
+            self.read_to_end(&child)?;
+            return Ok(None);
+        };
+        match kind {
+            Kind::Namespace | Kind::Class | Kind::Interface => self.container(child, tag, kind),
+            Kind::Record | Kind::Union if parent == Kind::Namespace => {
+                self.container(child, tag, kind)
+            }
+            // Documentation following the members of a type, whose header
+            // was already read.
+            kind if kind.is_documentation() => {
+                if !self.options.skip_docs {
+                    let message = format!("`{tag}` element after the members of its parent");
+                    let error = self.child_error(&child, tag, message);
+                    self.warnings.push(ParseWarning::new(error));
+                }
+                self.read_to_end(&child)?;
+                Ok(None)
+            }
+            kind => {
+                let end = self.read_to_end(&child)?;
+                let source = self.source(end)?;
+                let nested = parent != Kind::Namespace;
+                self.parse((tag, kind), &source, None, |node| match tag {
+                    "include" => Some(GirEvent::Include(FromNode::from_node(node))),
+                    "c:include" => Some(GirEvent::HeaderInclude(FromNode::from_node(node))),
+                    "package" => Some(GirEvent::Package(FromNode::from_node(node))),
+                    "doc:format" => Some(GirEvent::DocFormat(
+                        DocFormatChild::from_node(node).format(),
+                    )),
+                    "alias" => Some(GirEvent::Alias(FromNode::from_node(node))),
+                    "attribute" => Some(GirEvent::Attribute(FromNode::from_node(node))),
+                    "bitfield" => Some(GirEvent::BitField(FromNode::from_node(node))),
+                    "glib:boxed" => Some(GirEvent::Boxed(FromNode::from_node(node))),
+                    "callback" => Some(GirEvent::Callback(FromNode::from_node(node))),
+                    "constant" => Some(GirEvent::Constant(FromNode::from_node(node))),
+                    "constructor" => Some(GirEvent::Constructor(FromNode::from_node(node))),
+                    "docsection" => Some(GirEvent::DocSection(FromNode::from_node(node))),
+                    "enumeration" => Some(GirEvent::Enumeration(FromNode::from_node(node))),
+                    "field" => Some(GirEvent::Field(FromNode::from_node(node))),
+                    "function" => Some(GirEvent::Function(FromNode::from_node(node))),
+                    "function-inline" => Some(GirEvent::FunctionInline(FromNode::from_node(node))),
+                    "function-macro" => Some(GirEvent::FunctionMacro(FromNode::from_node(node))),
+                    "implements" => Some(GirEvent::Implements(FromNode::from_node(node))),
+                    "method" => Some(GirEvent::Method(FromNode::from_node(node))),
+                    "method-inline" => Some(GirEvent::MethodInline(FromNode::from_node(node))),
+                    "prerequisite" => Some(GirEvent::Prerequisite(FromNode::from_node(node))),
+                    "property" => Some(GirEvent::Property(FromNode::from_node(node))),
+                    "glib:signal" => Some(GirEvent::Signal(FromNode::from_node(node))),
+                    "virtual-method" => Some(GirEvent::VirtualMethod(FromNode::from_node(node))),
+                    "union" if nested => Some(GirEvent::NestedUnion(FromNode::from_node(node))),
+                    "record" if nested => Some(GirEvent::NestedRecord(FromNode::from_node(node))),
+                    _ => None,
+                })
+            }
+        }
+    }
+
+    /// Read the header of a container, up to its first child which isn't
+    /// documentation or an attribute.
+    fn container(
+        &mut self,
+        child: Child,
+        tag: &'static str,
+        kind: Kind,
+    ) -> Result<Option<GirEvent>, ParserError> {
+        let segment = match child.start.try_get_attribute("name") {
+            Ok(Some(name)) => format!("{tag}[@name={}]", String::from_utf8_lossy(&name.value)),
+            _ => tag.to_owned(),
+        };
+        let (end, closed) = if child.is_empty {
+            (self.offset(), true)
+        } else {
+            loop {
+                let (offset, event) = self.read_event()?;
+                let (start, is_empty) = match event {
+                    Event::Start(start) => (start, false),
+                    Event::Empty(start) => (start, true),
+                    Event::End(_) => break (self.offset(), true),
+                    Event::Eof => {
+                        let message = "unexpected end of document".to_owned();
+                        return Err(self.error(offset, message));
+                    }
+                    _ => continue,
+                };
+                let name = String::from_utf8_lossy(start.name().into_inner()).into_owned();
+                let inner = Child {
+                    offset,
+                    start,
+                    is_empty,
+                };
+                match kind.child(&name) {
+                    Some((_, kind)) if !kind.is_documentation() && kind != Kind::Attribute => {
+                        self.child = Some(inner);
+                        break (offset, false);
+                    }
+                    // Unknown elements are left to the checker.
+                    _ => {
+                        self.read_to_end(&inner)?;
+                    }
+                }
+            }
+        };
+        let mut source = self.source(end)?;
+        let cut = source.trim_end().len();
+        if closed {
+            self.queued = end_event(kind);
+        } else {
+            source.push_str(&format!("</{tag}>"));
+        }
+        let event = self.parse((tag, kind), &source, Some(cut), |node| match kind {
+            Kind::Repository => Some(GirEvent::StartRepository(RepositoryAttributes(
+                FromNode::from_node(node),
+            ))),
+            Kind::Namespace => Some(GirEvent::StartNamespace(NamespaceHeader(
+                FromNode::from_node(node),
+            ))),
+            Kind::Class => Some(GirEvent::StartClass(ClassHeader(FromNode::from_node(node)))),
+            Kind::Interface => Some(GirEvent::StartInterface(InterfaceHeader(
+                FromNode::from_node(node),
+            ))),
+            Kind::Record => Some(GirEvent::StartRecord(RecordHeader(FromNode::from_node(
+                node,
+            )))),
+            Kind::Union => Some(GirEvent::StartUnion(UnionHeader(FromNode::from_node(node)))),
+            _ => None,
+        })?;
+        if !closed {
+            self.containers.push(Container { kind, segment });
+        }
+        Ok(event)
+    }
+
+    /// Check an element and build its event with `f`.
+    fn parse(
+        &mut self,
+        root: (&'static str, Kind),
+        source: &str,
+        cut: Option<usize>,
+        f: impl FnOnce(Node) -> Option<GirEvent>,
+    ) -> Result<Option<GirEvent>, ParserError> {
+        let origin = Origin {
+            offset: self.base,
+            line: self.line,
+            column: self.column,
+            cut,
+        };
+        let (tree, warnings) =
+            Tree::parse(source, root, self.path.as_deref(), &self.options, origin)
+                .map_err(|error| self.relocate(error))?;
+        let element_path = self.element_path();
+        self.warnings.extend(
+            warnings
+                .into_iter()
+                .map(|warning| warning.relocate(self.base, self.line, self.column, &element_path)),
+        );
+        let build = || f(tree.root());
+        Ok(match &self.options.interner {
+            Some(interner) => interner.scope(build),
+            None => build(),
+        })
+    }
+}
+
+impl Iterator for GirStream<'_> {
+    type Item = Result<GirEvent, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let event = self.next_event().transpose();
+        self.done = !matches!(event, Some(Ok(_)));
+        event
+    }
+}
+
+fn end_event(kind: Kind) -> Option<GirEvent> {
+    match kind {
+        Kind::Repository => Some(GirEvent::EndRepository),
+        Kind::Namespace => Some(GirEvent::EndNamespace),
+        Kind::Class => Some(GirEvent::EndClass),
+        Kind::Interface => Some(GirEvent::EndInterface),
+        Kind::Record => Some(GirEvent::EndRecord),
+        Kind::Union => Some(GirEvent::EndUnion),
+        _ => None,
+    }
+}
@@ -0,0 +1,467 @@
+use std::collections::HashMap;
+
+use crate::{
+    alias::Alias,
+    bitfield::BitField,
+    boxed::Boxed,
+    callable::Callable,
+    callback::Callback,
+    class::Class,
+    constant::Constant,
+    enums::Enumeration,
+    environment::GirEnvironment,
+    function::{Function, FunctionInline},
+    function_macro::FunctionMacro,
+    interface::Interface,
+    member::Member,
+    method::{Method, MethodInline},
+    namespace::Namespace,
+    record::Record,
+    repository::Repository,
+    traits::Callable as _,
+    union::Union,
+};
+
+/// An element that can be looked up in a [`SymbolIndex`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symbol<'a> {
+    Alias(&'a Alias),
+    BitField(&'a BitField),
+    Boxed(&'a Boxed),
+    Callback(&'a Callback),
+    Class(&'a Class),
+    Constant(&'a Constant),
+    Constructor(&'a Function),
+    Enumeration(&'a Enumeration),
+    Function(&'a Function),
+    FunctionInline(&'a FunctionInline),
+    FunctionMacro(&'a FunctionMacro),
+    Interface(&'a Interface),
+    Member(&'a Member),
+    Method(&'a Method),
+    MethodInline(&'a MethodInline),
+    Record(&'a Record),
+    Union(&'a Union),
+}
+
+/// The element a [`Symbol`] is defined in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolContainer<'a> {
+    Namespace(&'a Namespace),
+    BitField(&'a BitField),
+    Boxed(&'a Boxed),
+    Class(&'a Class),
+    Enumeration(&'a Enumeration),
+    Interface(&'a Interface),
+    Record(&'a Record),
+    Union(&'a Union),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SymbolEntry<'a> {
+    namespace: &'a Namespace,
+    container: SymbolContainer<'a>,
+    symbol: Symbol<'a>,
+}
+
+impl<'a> SymbolEntry<'a> {
+    pub fn namespace(&self) -> &'a Namespace {
+        self.namespace
+    }
+
+    pub fn container(&self) -> SymbolContainer<'a> {
+        self.container
+    }
+
+    pub fn symbol(&self) -> Symbol<'a> {
+        self.symbol
+    }
+}
+
+#[derive(Default)]
+struct Keys<'a> {
+    name: Option<String>,
+    c_identifier: Option<&'a str>,
+    c_type: Option<&'a str>,
+    g_type_name: Option<&'a str>,
+    g_get_type: Option<&'a str>,
+}
+
+/// Lookup tables over the elements of one or many repositories.
+///
+/// When two elements share a key, the first one indexed wins.
+#[derive(Debug, Default)]
+pub struct SymbolIndex<'a> {
+    entries: Vec<SymbolEntry<'a>>,
+    names: HashMap<String, usize>,
+    c_identifiers: HashMap<&'a str, usize>,
+    c_types: HashMap<&'a str, usize>,
+    g_type_names: HashMap<&'a str, usize>,
+    g_get_types: HashMap<&'a str, usize>,
+}
+
+impl<'a> SymbolIndex<'a> {
+    pub fn new(repositories: impl IntoIterator<Item = &'a Repository>) -> Self {
+        let mut index = Self::default();
+        for repository in repositories {
+            index.index_namespace(repository.namespace());
+        }
+        index
+    }
+
+    fn insert(&mut self, entry: SymbolEntry<'a>, keys: Keys<'a>) {
+        let position = self.entries.len();
+        self.entries.push(entry);
+        if let Some(name) = keys.name {
+            self.names.entry(name).or_insert(position);
+        }
+        let tables = [
+            (&mut self.c_identifiers, keys.c_identifier),
+            (&mut self.c_types, keys.c_type),
+            (&mut self.g_type_names, keys.g_type_name),
+            (&mut self.g_get_types, keys.g_get_type),
+        ];
+        for (table, key) in tables {
+            if let Some(key) = key.filter(|k| !k.is_empty()) {
+                table.entry(key).or_insert(position);
+            }
+        }
+    }
+
+    fn index_namespace(&mut self, ns: &'a Namespace) {
+        let root = SymbolContainer::Namespace(ns);
+        let prefix = ns.name();
+
+        for alias in ns.aliases() {
+            let keys = Keys {
+                name: Some(format!("{prefix}.{}", alias.name())),
+                c_type: Some(alias.c_type()),
+                ..Default::default()
+            };
+            self.add(ns, root, Symbol::Alias(alias), keys);
+        }
+        for callback in ns.callbacks() {
+            let keys = Keys {
+                name: Some(format!("{prefix}.{}", callback.name())),
+                c_type: callback.c_type(),
+                ..Default::default()
+            };
+            self.add(ns, root, Symbol::Callback(callback), keys);
+        }
+
+        for class in ns.classes() {
+            let keys = Keys {
+                name: Some(format!("{prefix}.{}", class.name())),
+                c_type: class.c_type(),
+                g_type_name: Some(class.g_type_name()),
+                g_get_type: Some(class.g_get_type()),
+                ..Default::default()
+            };
+            self.add(ns, root, Symbol::Class(class), keys);
+            let container = SymbolContainer::Class(class);
+            let prefix = format!("{prefix}.{}", class.name());
+            self.index_callables(ns, container, Some(&prefix), class.callables());
+            self.index_inlined(
+                ns,
+                container,
+                Some(&prefix),
+                class.inlined_functions(),
+                class.inlined_methods(),
+            );
+            for constant in class.constants() {
+                self.add(
+                    ns,
+                    container,
+                    Symbol::Constant(constant),
+                    constant_keys(&prefix, constant),
+                );
+            }
+        }
+        for interface in ns.interfaces() {
+            let keys = Keys {
+                name: Some(format!("{prefix}.{}", interface.name())),
+                c_type: interface.c_type(),
+                g_type_name: Some(interface.g_type_name()),
+                g_get_type: Some(interface.g_get_type()),
+                ..Default::default()
+            };
+            self.add(ns, root, Symbol::Interface(interface), keys);
+            let container = SymbolContainer::Interface(interface);
+            let prefix = format!("{prefix}.{}", interface.name());
+            self.index_callables(ns, container, Some(&prefix), interface.callables());
+            self.index_inlined(
+                ns,
+                container,
+                Some(&prefix),
+                interface.inlined_functions(),
+                interface.inlined_methods(),
+            );
+            for constant in interface.constants() {
+                self.add(
+                    ns,
+                    container,
+                    Symbol::Constant(constant),
+                    constant_keys(&prefix, constant),
+                );
+            }
+        }
+        for record in ns.records() {
+            let keys = Keys {
+                name: record.name().map(|name| format!("{prefix}.{name}")),
+                c_type: record.c_type(),
+                g_type_name: record.g_type_name(),
+                g_get_type: record.g_get_type(),
+                ..Default::default()
+            };
+            self.add(ns, root, Symbol::Record(record), keys);
+            // The callables of anonymous records have no qualified name, they
+            // are only indexed by their C identifier.
+            let container = SymbolContainer::Record(record);
+            let prefix = record.name().map(|name| format!("{prefix}.{name}"));
+            self.index_callables(ns, container, prefix.as_deref(), record.callables());
+            self.index_inlined(
+                ns,
+                container,
+                prefix.as_deref(),
+                record.inlined_functions(),
+                record.inlined_methods(),
+            );
+        }
+        for union in ns.unions() {
+            let keys = Keys {
+                name: union.name().map(|name| format!("{prefix}.{name}")),
+                c_type: union.c_type(),
+                g_type_name: union.g_type_name(),
+                g_get_type: union.g_get_type(),
+                ..Default::default()
+            };
+            self.add(ns, root, Symbol::Union(union), keys);
+            // The callables of anonymous unions have no qualified name, they
+            // are only indexed by their C identifier.
+            let container = SymbolContainer::Union(union);
+            let prefix = union.name().map(|name| format!("{prefix}.{name}"));
+            self.index_callables(ns, container, prefix.as_deref(), union.callables());
+            self.index_inlined(
+                ns,
+                container,
+                prefix.as_deref(),
+                union.inlined_functions(),
+                union.inlined_methods(),
+            );
+        }
+        for enumeration in ns.enums() {
+            let keys = Keys {
+                name: Some(format!("{prefix}.{}", enumeration.name())),
+                c_type: Some(enumeration.c_type()),
+                g_type_name: enumeration.g_type_name(),
+                g_get_type: enumeration.g_get_type(),
+                ..Default::default()
+            };
+            self.add(ns, root, Symbol::Enumeration(enumeration), keys);
+            let container = SymbolContainer::Enumeration(enumeration);
+            let prefix = format!("{prefix}.{}", enumeration.name());
+            self.index_members(ns, container, &prefix, enumeration.members());
+            self.index_functions(ns, container, &prefix, enumeration.functions());
+            self.index_inlined(
+                ns,
+                container,
+                Some(&prefix),
+                enumeration.inlined_functions(),
+                &[],
+            );
+        }
+        for bitfield in ns.flags() {
+            let keys = Keys {
+                name: Some(format!("{prefix}.{}", bitfield.name())),
+                c_type: Some(bitfield.c_type()),
+                g_type_name: bitfield.g_type_name(),
+                g_get_type: bitfield.g_get_type(),
+                ..Default::default()
+            };
+            self.add(ns, root, Symbol::BitField(bitfield), keys);
+            let container = SymbolContainer::BitField(bitfield);
+            let prefix = format!("{prefix}.{}", bitfield.name());
+            self.index_members(ns, container, &prefix, bitfield.members());
+            self.index_functions(ns, container, &prefix, bitfield.functions());
+            self.index_inlined(
+                ns,
+                container,
+                Some(&prefix),
+                bitfield.inlined_functions(),
+                &[],
+            );
+        }
+        for boxed in ns.boxed() {
+            let keys = Keys {
+                name: Some(format!("{prefix}.{}", boxed.g_name())),
+                g_type_name: boxed.g_type_name(),
+                g_get_type: boxed.g_get_type(),
+                ..Default::default()
+            };
+            self.add(ns, root, Symbol::Boxed(boxed), keys);
+            let container = SymbolContainer::Boxed(boxed);
+            let prefix = format!("{prefix}.{}", boxed.g_name());
+            self.index_functions(ns, container, &prefix, boxed.functions());
+            self.index_inlined(ns, container, Some(&prefix), boxed.inlined_functions(), &[]);
+        }
+        // Functions moved to a type share their C identifier with the moved
+        // copy, which should win, so the namespace level items come last.
+        for constant in ns.constants() {
+            self.add(
+                ns,
+                root,
+                Symbol::Constant(constant),
+                constant_keys(prefix, constant),
+            );
+        }
+        for function in ns.functions() {
+            let keys = callable_keys(Some(prefix), function.name(), function.c_identifier());
+            self.add(ns, root, Symbol::Function(function), keys);
+        }
+        for function in ns.inlined_functions() {
+            let keys = callable_keys(Some(prefix), function.name(), function.c_identifier());
+            self.add(ns, root, Symbol::FunctionInline(function), keys);
+        }
+        for function in ns.macros() {
+            let keys = callable_keys(Some(prefix), function.name(), function.c_identifier());
+            self.add(ns, root, Symbol::FunctionMacro(function), keys);
+        }
+    }
+
+    fn add(
+        &mut self,
+        namespace: &'a Namespace,
+        container: SymbolContainer<'a>,
+        symbol: Symbol<'a>,
+        keys: Keys<'a>,
+    ) {
+        self.insert(
+            SymbolEntry {
+                namespace,
+                container,
+                symbol,
+            },
+            keys,
+        );
+    }
+
+    fn index_callables(
+        &mut self,
+        namespace: &'a Namespace,
+        container: SymbolContainer<'a>,
+        prefix: Option<&str>,
+        callables: &'a [Callable],
+    ) {
+        for callable in callables {
+            let symbol = match callable {
+                Callable::Constructor(f) => Symbol::Constructor(f),
+                Callable::Function(f) => Symbol::Function(f),
+                Callable::Method(m) => Symbol::Method(m),
+            };
+            let keys = callable_keys(prefix, callable.name(), callable.c_identifier());
+            self.add(namespace, container, symbol, keys);
+        }
+    }
+
+    fn index_functions(
+        &mut self,
+        namespace: &'a Namespace,
+        container: SymbolContainer<'a>,
+        prefix: &str,
+        functions: &'a [Function],
+    ) {
+        for function in functions {
+            let keys = callable_keys(Some(prefix), function.name(), function.c_identifier());
+            self.add(namespace, container, Symbol::Function(function), keys);
+        }
+    }
+
+    fn index_inlined(
+        &mut self,
+        namespace: &'a Namespace,
+        container: SymbolContainer<'a>,
+        prefix: Option<&str>,
+        functions: &'a [FunctionInline],
+        methods: &'a [MethodInline],
+    ) {
+        for function in functions {
+            let keys = callable_keys(prefix, function.name(), function.c_identifier());
+            self.add(namespace, container, Symbol::FunctionInline(function), keys);
+        }
+        for method in methods {
+            let keys = callable_keys(prefix, method.name(), method.c_identifier());
+            self.add(namespace, container, Symbol::MethodInline(method), keys);
+        }
+    }
+
+    fn index_members(
+        &mut self,
+        namespace: &'a Namespace,
+        container: SymbolContainer<'a>,
+        prefix: &str,
+        members: &'a [Member],
+    ) {
+        for member in members {
+            let keys = callable_keys(Some(prefix), member.name(), Some(member.c_identifier()));
+            self.add(namespace, container, Symbol::Member(member), keys);
+        }
+    }
+
+    pub fn entries(&self) -> &[SymbolEntry<'a>] {
+        &self.entries
+    }
+
+    /// Look up an element by its qualified GIR name, such as `Gtk.Widget`,
+    /// `Gtk.init` or `Gtk.Widget.show`.
+    pub fn by_name(&self, name: &str) -> Option<&SymbolEntry<'a>> {
+        self.names.get(name).map(|i| &self.entries[*i])
+    }
+
+    #[doc(alias = "c:identifier")]
+    pub fn by_c_identifier(&self, c_identifier: &str) -> Option<&SymbolEntry<'a>> {
+        self.c_identifiers
+            .get(c_identifier)
+            .map(|i| &self.entries[*i])
+    }
+
+    #[doc(alias = "c:type")]
+    pub fn by_c_type(&self, c_type: &str) -> Option<&SymbolEntry<'a>> {
+        self.c_types.get(c_type).map(|i| &self.entries[*i])
+    }
+
+    #[doc(alias = "glib:type-name")]
+    pub fn by_g_type_name(&self, g_type_name: &str) -> Option<&SymbolEntry<'a>> {
+        self.g_type_names
+            .get(g_type_name)
+            .map(|i| &self.entries[*i])
+    }
+
+    #[doc(alias = "glib:get-type")]
+    pub fn by_g_get_type(&self, g_get_type: &str) -> Option<&SymbolEntry<'a>> {
+        self.g_get_types.get(g_get_type).map(|i| &self.entries[*i])
+    }
+}
+
+fn callable_keys<'a>(prefix: Option<&str>, name: &str, c_identifier: Option<&'a str>) -> Keys<'a> {
+    Keys {
+        name: prefix.map(|prefix| format!("{prefix}.{name}")),
+        c_identifier,
+        ..Default::default()
+    }
+}
+
+fn constant_keys<'a>(prefix: &str, constant: &'a Constant) -> Keys<'a> {
+    Keys {
+        name: Some(format!("{prefix}.{}", constant.name())),
+        c_identifier: constant.c_identifier(),
+        c_type: constant.c_type(),
+        ..Default::default()
+    }
+}
+
+impl GirEnvironment {
+    /// Index the symbols of every repository of the environment.
+    pub fn symbol_index(&self) -> SymbolIndex<'_> {
+        SymbolIndex::new(self.repositories())
+    }
+}
@@ -2,57 +2,109 @@ use crate::{
     attribute::Attribute,
     documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition},
     version::Version,
-    Parameters, ReturnValue, Stability,
+    Parameters, ReturnValue, Span, Stability,
 };
 
-pub trait Documentable {
-    fn doc(&self) -> Option<&Documentation>;
-    fn doc_deprecated(&self) -> Option<&DocDeprecated>;
-    fn doc_stability(&self) -> Option<&DocStability>;
-    fn doc_version(&self) -> Option<&DocVersion>;
-    fn source_position(&self) -> Option<&SourcePosition>;
+/// The types of the nodes returned by the traits of the prelude, which are
+/// implemented by both the [`Owned`] model and the
+/// [`Borrowed`](crate::borrowed::Borrowed) one. The nodes of the borrowed
+/// model are given the lifetime of the node returning them.
+pub trait Model {
+    type Documentation<'a>: 'a;
+    type DocDeprecated<'a>: 'a;
+    type DocStability<'a>: 'a;
+    type DocVersion<'a>: 'a;
+    type SourcePosition<'a>: 'a;
+    type Attribute<'a>: 'a;
+    type ReturnValue<'a>: 'a;
+    type Parameters<'a>: 'a;
+
+    /// The name and the value of an attribute.
+    fn name_value<'a>(attribute: &'a Self::Attribute<'_>) -> (&'a str, &'a str);
 }
 
-pub trait Attributable {
-    fn attributes(&self) -> &[Attribute];
+/// The model at the root of the crate, whose nodes own their strings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Owned;
 
-    fn element_type(&self) -> Option<&str> {
+impl Model for Owned {
+    type Documentation<'a> = Documentation;
+    type DocDeprecated<'a> = DocDeprecated;
+    type DocStability<'a> = DocStability;
+    type DocVersion<'a> = DocVersion;
+    type SourcePosition<'a> = SourcePosition;
+    type Attribute<'a> = Attribute;
+    type ReturnValue<'a> = ReturnValue;
+    type Parameters<'a> = Parameters;
+
+    fn name_value(attribute: &Attribute) -> (&str, &str) {
+        (attribute.name(), attribute.value())
+    }
+}
+
+pub trait Documentable<M: Model = Owned> {
+    fn doc(&self) -> Option<&M::Documentation<'_>>;
+    fn doc_deprecated(&self) -> Option<&M::DocDeprecated<'_>>;
+    fn doc_stability(&self) -> Option<&M::DocStability<'_>>;
+    fn doc_version(&self) -> Option<&M::DocVersion<'_>>;
+    fn source_position(&self) -> Option<&M::SourcePosition<'_>>;
+}
+
+pub trait Attributable<M: Model = Owned> {
+    fn attributes(&self) -> &[M::Attribute<'_>];
+
+    /// The value of the attribute named `name`, if any.
+    fn attribute(&self, name: &str) -> Option<&str> {
         self.attributes()
             .iter()
-            .find(|a| a.name() == "element-type")
-            .map(|a| a.value())
+            .map(M::name_value)
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    }
+
+    fn element_type(&self) -> Option<&str> {
+        self.attribute("element-type")
     }
 
     fn gtk_property_get(&self) -> Option<&str> {
-        self.attributes()
-            .iter()
-            .find(|a| a.name() == "org.gtk.Property.get")
-            .map(|a| a.value())
+        self.attribute("org.gtk.Property.get")
     }
 
     fn gtk_method_get_property(&self) -> Option<&str> {
-        self.attributes()
-            .iter()
-            .find(|a| a.name() == "org.gtk.Method.get_property")
-            .map(|a| a.value())
+        self.attribute("org.gtk.Method.get_property")
     }
 
     fn gtk_property_set(&self) -> Option<&str> {
-        self.attributes()
-            .iter()
-            .find(|a| a.name() == "org.gtk.Property.set")
-            .map(|a| a.value())
+        self.attribute("org.gtk.Property.set")
     }
 
     fn gtk_method_set_property(&self) -> Option<&str> {
-        self.attributes()
-            .iter()
-            .find(|a| a.name() == "org.gtk.Method.set_property")
-            .map(|a| a.value())
+        self.attribute("org.gtk.Method.set_property")
     }
 }
 
-pub trait Info: Documentable + Attributable {
+/// The content of a node that isn't modelled by this crate, such as vendor
+/// extensions, kept so it can be written back.
+pub trait Extensible {
+    /// The unknown attributes, as name/value pairs. Those are only kept when
+    /// parsing in lenient mode, as they fail the parsing otherwise.
+    fn extra_attributes(&self) -> &[(String, String)];
+    /// The unknown child elements, as raw XML fragments including their
+    /// content, even when it looks like elements this crate knows about.
+    fn extra_children(&self) -> &[String];
+}
+
+/// A node which knows where it was found in the document it was parsed from.
+pub trait Spanned {
+    /// The span of the node. Every parsed node has one by default, it is
+    /// `None` for the nodes parsed with [`ParseOptions::spans`] disabled and
+    /// the ones built in code.
+    ///
+    /// [`ParseOptions::spans`]: crate::ParseOptions::spans
+    fn span(&self) -> Option<&Span>;
+}
+
+pub trait Info<M: Model = Owned>: Documentable<M> + Attributable<M> {
     fn is_introspectable(&self) -> bool;
     fn is_deprecated(&self) -> bool;
     fn version(&self) -> Option<&Version>;
@@ -60,7 +112,7 @@ pub trait Info: Documentable + Attributable {
     fn stability(&self) -> Option<Stability>;
 }
 
-pub trait Callable: Info {
+pub trait Callable<M: Model = Owned>: Info<M> {
     fn name(&self) -> &str;
     fn c_identifier(&self) -> Option<&str>;
     fn shadows(&self) -> Option<&str>;
@@ -103,6 +155,30 @@ macro_rules! impl_attributable {
     };
 }
 
+macro_rules! impl_extensible {
+    ($rust_type:ident) => {
+        impl Extensible for $rust_type {
+            fn extra_attributes(&self) -> &[(String, String)] {
+                self.extra.as_ref().map_or(&[], |extra| extra.attributes())
+            }
+
+            fn extra_children(&self) -> &[String] {
+                self.extra.as_ref().map_or(&[], |extra| extra.children())
+            }
+        }
+    };
+}
+
+macro_rules! impl_spanned {
+    ($rust_type:ident) => {
+        impl Spanned for $rust_type {
+            fn span(&self) -> Option<&crate::Span> {
+                self.span.get()
+            }
+        }
+    };
+}
+
 macro_rules! impl_info {
     ($rust_type:ident) => {
         impl Info for $rust_type {
@@ -126,6 +202,17 @@ macro_rules! impl_info {
                 self.stability
             }
         }
+
+        impl crate::writer::InfoAttrs for $rust_type {
+            fn write_info_attrs(&self, element: crate::writer::Element) -> crate::writer::Element {
+                element
+                    .opt_attr("introspectable", &self.introspectable)
+                    .opt_attr("deprecated", &self.deprecated)
+                    .opt_attr("version", &self.version)
+                    .opt_attr("deprecated-version", &self.deprecated_version)
+                    .opt_attr("stability", &self.stability)
+            }
+        }
     };
 }
 
@@ -164,13 +251,31 @@ macro_rules! impl_callable {
                 self.sync_func.as_deref()
             }
         }
+
+        impl crate::writer::CallableAttrs for $rust_type {
+            fn write_callable_attrs(
+                &self,
+                element: crate::writer::Element,
+            ) -> crate::writer::Element {
+                element
+                    .attr("name", &self.name)
+                    .opt_attr("c:identifier", &self.c_identifier)
+                    .opt_attr("shadows", &self.shadows)
+                    .opt_attr("shadowed-by", &self.shadowed_by)
+                    .opt_attr("throws", &self.throws)
+                    .opt_attr("moved-to", &self.moved_to)
+                    .opt_attr("glib:async-func", &self.async_func)
+                    .opt_attr("glib:finish-func", &self.finish_func)
+                    .opt_attr("glib:sync-func", &self.sync_func)
+            }
+        }
     };
 }
 
-pub trait FunctionLike: Attributable + Info + Documentable {
+pub trait FunctionLike<M: Model = Owned>: Attributable<M> + Info<M> + Documentable<M> {
     fn throws(&self) -> bool;
-    fn return_value(&self) -> &ReturnValue;
-    fn parameters(&self) -> &Parameters;
+    fn return_value(&self) -> &M::ReturnValue<'_>;
+    fn parameters(&self) -> &M::Parameters<'_>;
 }
 
 macro_rules! impl_function_like {
@@ -0,0 +1,397 @@
+//! The elements of a GIR document, recorded by the [`checker`] as it goes
+//! through the document, which the models are then built from.
+//!
+//! The tree only holds what the models read: the known elements with their
+//! valid attributes, the character data of the documentation and the
+//! content that isn't part of the schema, unknown attributes only being
+//! kept in lenient mode. Strings are borrowed from the document unless the
+//! XML escapes them.
+//!
+//! [`checker`]: crate::checker
+
+use std::{borrow::Cow, ops::Range, path::Path, sync::Arc};
+
+use xmlserde::{
+    quick_xml::{escape::unescape, events::Event},
+    XmlValue,
+};
+
+use crate::{
+    checker, error::ParseWarning, extra::Extra, schema::Kind, span::NodeSpan, ParseError,
+    ParseOptions, Span,
+};
+
+/// Where the document being checked starts in the document it was taken
+/// from, used to give its elements a [`Span`].
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Origin {
+    pub(crate) offset: usize,
+    pub(crate) line: usize,
+    /// The number of characters preceding the document on its first line,
+    /// added to the columns of that line.
+    pub(crate) column: usize,
+    /// The end of the text taken from the document, when the elements left
+    /// open at that point were closed to check it.
+    pub(crate) cut: Option<usize>,
+}
+
+impl Origin {
+    pub(crate) const START: Self = Self {
+        offset: 0,
+        line: 1,
+        column: 0,
+        cut: None,
+    };
+}
+
+const REQUIRED: &str = "required attributes and children are checked against the schema";
+
+struct NodeData<'a> {
+    /// `None` for elements that aren't part of the schema, which are kept
+    /// as they are, without recording their content.
+    kind: Option<Kind>,
+    tag: &'a str,
+    range: Range<usize>,
+    attributes: Range<usize>,
+    /// The character data of the documentation elements.
+    text: Cow<'a, str>,
+    /// The index following the last descendant of the node.
+    end: usize,
+}
+
+/// The elements of a document, in document order.
+pub(crate) struct Tree<'a> {
+    source: &'a str,
+    nodes: Vec<NodeData<'a>>,
+    attributes: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+    /// The spans of the nodes, by index, when they are asked for.
+    spans: Vec<Option<Span>>,
+    /// The elements being read.
+    stack: Vec<usize>,
+}
+
+/// Recover the lifetime of the source for a string read from it, copying the
+/// strings the reader had to unescape.
+fn borrow<'a>(source: &'a str, value: Cow<'_, str>) -> Cow<'a, str> {
+    if let Cow::Borrowed(value) = value {
+        let start = (value.as_ptr() as usize).wrapping_sub(source.as_ptr() as usize);
+        if let Some(value) = source.get(start..start + value.len()) {
+            return Cow::Borrowed(value);
+        }
+    }
+    Cow::Owned(value.into_owned())
+}
+
+fn utf8(bytes: Cow<'_, [u8]>) -> Cow<'_, str> {
+    match bytes {
+        Cow::Borrowed(bytes) => String::from_utf8_lossy(bytes),
+        Cow::Owned(bytes) => Cow::Owned(String::from_utf8_lossy(&bytes).into_owned()),
+    }
+}
+
+fn append<'a>(text: &mut Cow<'a, str>, chunk: Cow<'a, str>) {
+    if text.is_empty() {
+        *text = chunk;
+    } else {
+        text.to_mut().push_str(&chunk);
+    }
+}
+
+impl<'a> Tree<'a> {
+    /// Check a document made of a single element with the given tag and
+    /// kind, such as a `<repository>` or a `<function>`, recording its
+    /// elements. They are given a span when the options ask for it.
+    pub(crate) fn parse(
+        source: &'a str,
+        root: (&'static str, Kind),
+        path: Option<&Path>,
+        options: &ParseOptions,
+        origin: Origin,
+    ) -> Result<(Self, Vec<ParseWarning>), ParseError> {
+        let mut tree = Self {
+            source,
+            nodes: Vec::new(),
+            attributes: Vec::new(),
+            spans: Vec::new(),
+            stack: Vec::new(),
+        };
+        let warnings = checker::check(&mut tree, root, path, options, origin.cut)?;
+        if options.spans {
+            tree.locate(origin, path.map(Arc::from));
+        }
+        Ok((tree, warnings))
+    }
+
+    pub(crate) fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// The root element.
+    pub(crate) fn root(&self) -> Node<'_, 'a> {
+        Node {
+            tree: self,
+            index: 0,
+        }
+    }
+
+    pub(crate) fn start(&mut self, tag: &'a str, kind: Option<Kind>, offset: usize) {
+        self.stack.push(self.nodes.len());
+        let attributes = self.attributes.len();
+        self.nodes.push(NodeData {
+            kind,
+            tag,
+            range: offset..offset,
+            attributes: attributes..attributes,
+            text: Cow::Borrowed(""),
+            end: 0,
+        });
+    }
+
+    /// Add an attribute to the element which just started.
+    pub(crate) fn attribute(&mut self, key: &str, value: Cow<'_, str>) {
+        let key = borrow(self.source, Cow::Borrowed(key));
+        let value = borrow(self.source, value);
+        self.attributes.push((key, value));
+        let node = self.nodes.last_mut().expect("element being checked");
+        node.attributes.end = self.attributes.len();
+    }
+
+    pub(crate) fn end(&mut self, end: usize) {
+        let end_index = self.nodes.len();
+        if let Some(index) = self.stack.pop() {
+            let node = &mut self.nodes[index];
+            node.range.end = end;
+            node.end = end_index;
+        }
+    }
+
+    /// Add character data to the documentation element being read, if any.
+    pub(crate) fn text(&mut self, event: Event<'a>) {
+        let Some(index) = self
+            .stack
+            .iter()
+            .rev()
+            .find(|index| self.nodes[**index].kind.is_some())
+        else {
+            return;
+        };
+        let node = &mut self.nodes[*index];
+        if !matches!(node.kind, Some(Kind::Documentation | Kind::DocText)) {
+            return;
+        }
+        let text = match event {
+            Event::Text(text) => match utf8(text.into_inner()) {
+                Cow::Borrowed(text) => unescape(text).unwrap_or(Cow::Borrowed(text)),
+                text => text,
+            },
+            Event::GeneralRef(entity) => {
+                let entity = format!("&{};", utf8(entity.into_inner()));
+                match unescape(&entity) {
+                    Ok(text) => Cow::Owned(text.into_owned()),
+                    Err(_) => return,
+                }
+            }
+            Event::CData(data) => utf8(data.into_inner()),
+            _ => return,
+        };
+        append(&mut node.text, text);
+    }
+
+    /// Compute the spans of the nodes, going through the document once as
+    /// the nodes are in document order.
+    fn locate(&mut self, origin: Origin, file: Option<Arc<Path>>) {
+        let source = self.source;
+        let (mut offset, mut line, mut line_start) = (0, 1, 0);
+        self.spans = self
+            .nodes
+            .iter()
+            .map(|node| {
+                node.kind.filter(|kind| kind.is_spanned())?;
+                let start = node.range.start;
+                let skipped = &source[offset..start];
+                if let Some(i) = skipped.rfind('\n') {
+                    line += skipped.matches('\n').count();
+                    line_start = offset + i + 1;
+                }
+                offset = start;
+                let mut column = source[line_start..start].chars().count() + 1;
+                if line == 1 {
+                    column += origin.column;
+                }
+                let end = origin
+                    .cut
+                    .map_or(node.range.end, |cut| cut.min(node.range.end));
+                let mut span = Span::open(
+                    file.clone(),
+                    origin.offset + start,
+                    origin.line + line - 1,
+                    column,
+                );
+                span.close(origin.offset + end);
+                Some(span)
+            })
+            .collect();
+    }
+}
+
+/// Build a node of the model from its element.
+pub(crate) trait FromNode {
+    fn from_node(node: Node) -> Self;
+}
+
+/// The enums of elements with different tags, such as the fields of a
+/// record, whose variant is picked from the tag of the element.
+pub(crate) trait FromTag: Sized {
+    fn from_tag(node: Node) -> Option<Self>;
+}
+
+/// An element of the document, read to build the node of its kind.
+#[derive(Clone, Copy)]
+pub(crate) struct Node<'t, 'a> {
+    tree: &'t Tree<'a>,
+    index: usize,
+}
+
+impl<'t, 'a> Node<'t, 'a> {
+    fn data(self) -> &'t NodeData<'a> {
+        &self.tree.nodes[self.index]
+    }
+
+    fn attributes(self) -> &'t [(Cow<'a, str>, Cow<'a, str>)] {
+        &self.tree.attributes[self.data().attributes.clone()]
+    }
+
+    pub(crate) fn tag(self) -> &'a str {
+        self.data().tag
+    }
+
+    /// The value of an attribute, as found in the document.
+    pub(crate) fn string(self, name: &str) -> Option<Cow<'a, str>> {
+        self.attributes()
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+    }
+
+    /// An optional attribute. Invalid values were already skipped.
+    pub(crate) fn opt_attr<T: XmlValue>(self, name: &str) -> Option<T> {
+        self.attributes()
+            .iter()
+            .find(|(key, _)| key == name)
+            .and_then(|(_, value)| T::deserialize(value).ok())
+    }
+
+    /// A required attribute.
+    pub(crate) fn attr<T: XmlValue>(self, name: &str) -> T {
+        self.opt_attr(name).expect(REQUIRED)
+    }
+
+    /// The character data of a documentation element.
+    pub(crate) fn text(self) -> Cow<'a, str> {
+        self.data().text.clone()
+    }
+
+    /// The known child elements.
+    pub(crate) fn elements(self) -> impl Iterator<Item = Self> {
+        let nodes = &self.tree.nodes;
+        let mut next = self.index + 1;
+        let end = self.data().end;
+        std::iter::from_fn(move || {
+            while next < end {
+                let index = next;
+                if nodes[index].kind.is_some() {
+                    next = nodes[index].end;
+                    return Some(Self {
+                        tree: self.tree,
+                        index,
+                    });
+                }
+                next += 1;
+            }
+            None
+        })
+    }
+
+    pub(crate) fn elements_with_tag(self, tag: &'static str) -> impl Iterator<Item = Self> {
+        self.elements().filter(move |child| child.tag() == tag)
+    }
+
+    pub(crate) fn element(self, tag: &'static str) -> Option<Self> {
+        self.elements_with_tag(tag).next()
+    }
+
+    pub(crate) fn child<T: FromNode>(self, tag: &'static str) -> Option<T> {
+        self.element(tag).map(T::from_node)
+    }
+
+    /// A child the schema requires.
+    pub(crate) fn required<T: FromNode>(self, tag: &'static str) -> T {
+        self.child(tag).expect(REQUIRED)
+    }
+
+    pub(crate) fn children<T: FromNode>(self, tag: &'static str) -> Vec<T> {
+        self.elements_with_tag(tag).map(T::from_node).collect()
+    }
+
+    /// The child that is one of the variants of `T`, which the schema
+    /// requires.
+    pub(crate) fn one_of<T: FromTag>(self) -> T {
+        self.opt_one_of().expect(REQUIRED)
+    }
+
+    pub(crate) fn opt_one_of<T: FromTag>(self) -> Option<T> {
+        self.elements().find_map(T::from_tag)
+    }
+
+    /// The children that are variants of `T`, in document order.
+    pub(crate) fn all_of<T: FromTag>(self) -> Vec<T> {
+        self.elements().filter_map(T::from_tag).collect()
+    }
+
+    pub(crate) fn span(self) -> NodeSpan {
+        NodeSpan::new(self.tree.spans.get(self.index).cloned().flatten())
+    }
+
+    /// The attributes that aren't part of the schema, in lenient mode.
+    pub(crate) fn extra_attributes(self) -> Vec<(Cow<'a, str>, Cow<'a, str>)> {
+        let Some(kind) = self.data().kind.filter(|kind| kind.keeps_extra()) else {
+            return Vec::new();
+        };
+        self.attributes()
+            .iter()
+            .filter(|(key, _)| kind.attribute(key).is_none())
+            .cloned()
+            .collect()
+    }
+
+    /// The unknown child elements, along with their content.
+    pub(crate) fn extra_children(self) -> Vec<&'a str> {
+        let nodes = &self.tree.nodes;
+        let source = self.tree.source;
+        let mut children = Vec::new();
+        if !self.data().kind.is_some_and(Kind::keeps_extra) {
+            return children;
+        }
+        let mut next = self.index + 1;
+        while next < self.data().end {
+            let node = &nodes[next];
+            if node.kind.is_none() {
+                children.push(&source[node.range.clone()]);
+            }
+            next = node.end;
+        }
+        children
+    }
+
+    /// The content that isn't part of the schema.
+    pub(crate) fn extra(self) -> Option<Extra> {
+        let mut extra = Extra::default();
+        for (key, value) in self.extra_attributes() {
+            extra.push_attribute(&key, &value);
+        }
+        for child in self.extra_children() {
+            extra.push_child(child);
+        }
+        (!extra.is_empty()).then_some(extra)
+    }
+}
@@ -1,36 +1,32 @@
-use xmlserde_derives::XmlDeserialize;
-
 use crate::{
     array::Array,
     documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition},
+    extra::Extra,
+    interner::Atom,
     prelude::*,
+    span::NodeSpan,
+    tree::{FromNode, FromTag, Node},
+    writer::{Element, ToXml},
 };
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, XmlDeserialize)]
-#[xmlserde(root = b"type")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Type {
-    #[xmlserde(name = b"name", ty = "attr")]
-    name: Option<String>,
-    #[xmlserde(name = b"c:type", ty = "attr")]
-    c_type: Option<String>,
-    #[xmlserde(name = b"introspectable", ty = "attr")]
+    name: Option<Atom>,
+    c_type: Option<Atom>,
     introspectable: Option<bool>,
     // Documentation
-    #[xmlserde(name = b"doc", ty = "child")]
     doc: Option<Documentation>,
-    #[xmlserde(name = b"doc-deprecated", ty = "child")]
     doc_deprecated: Option<DocDeprecated>,
-    #[xmlserde(name = b"doc-stability", ty = "child")]
     doc_stability: Option<DocStability>,
-    #[xmlserde(name = b"doc-version", ty = "child")]
     doc_version: Option<DocVersion>,
-    #[xmlserde(name = b"source-position", ty = "child")]
     source_position: Option<SourcePosition>,
-    #[xmlserde(name = b"type", ty = "child")]
-    types: Vec<Type>,
-    #[xmlserde(name = b"array", ty = "child")]
-    arrays: Vec<Array>,
+    /// The type parameters, in the order of the document as it tells the
+    /// key from the value of a hash table.
+    parameters: Vec<AnyType>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Type {
@@ -42,26 +38,57 @@ impl Type {
         self.c_type.as_deref()
     }
 
+    /// The name as an [`Atom`], cheap to clone and compare.
+    pub fn name_atom(&self) -> Option<&Atom> {
+        self.name.as_ref()
+    }
+
+    pub fn c_type_atom(&self) -> Option<&Atom> {
+        self.c_type.as_ref()
+    }
+
     pub fn is_introspectable(&self) -> bool {
         self.introspectable.unwrap_or(true)
     }
 
-    pub fn types(&self) -> &[Type] {
-        &self.types
+    /// The type parameters, such as the key and value types of a hash table,
+    /// in order.
+    pub fn parameters(&self) -> &[AnyType] {
+        &self.parameters
+    }
+
+    /// The type parameters which aren't arrays, in order.
+    pub fn types(&self) -> impl Iterator<Item = &Type> {
+        self.parameters
+            .iter()
+            .filter_map(|parameter| match parameter {
+                AnyType::Type(ty) => Some(ty),
+                AnyType::Array(_) => None,
+            })
     }
 
-    pub fn arrays(&self) -> &[Array] {
-        &self.arrays
+    /// The type parameters which are arrays, in order.
+    pub fn arrays(&self) -> impl Iterator<Item = &Array> {
+        self.parameters
+            .iter()
+            .filter_map(|parameter| match parameter {
+                AnyType::Array(array) => Some(array),
+                AnyType::Type(_) => None,
+            })
     }
 }
 
 impl_documentable!(Type);
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+#[allow(clippy::large_enum_variant)]
 pub enum AnyType {
-    #[xmlserde(name = b"type")]
     Type(Type),
-    #[xmlserde(name = b"array")]
     Array(Array),
 }
 
@@ -94,3 +121,83 @@ impl AnyType {
         }
     }
 }
+
+impl_extensible!(Type);
+impl_spanned!(Type);
+impl_builder!(Type => TypeBuilder {
+    new {}
+    optional {
+        name: Atom => name, name_mut;
+        c_type: Atom => c_type, c_type_mut;
+        introspectable: bool => introspectable, introspectable_mut;
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+    }
+    list {
+        parameters: AnyType => parameter, parameters_mut;
+    }
+    value {}
+    hidden { span, extra }
+});
+
+impl TypeBuilder {
+    pub fn r#type(self, ty: Type) -> Self {
+        self.parameter(AnyType::Type(ty))
+    }
+
+    pub fn array(self, array: Array) -> Self {
+        self.parameter(AnyType::Array(array))
+    }
+}
+
+impl FromNode for Type {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.opt_attr("name"),
+            c_type: node.opt_attr("c:type"),
+            introspectable: node.opt_attr("introspectable"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            parameters: node.all_of(),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Type {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .opt_attr("name", &self.name)
+            .opt_attr("c:type", &self.c_type)
+            .opt_attr("introspectable", &self.introspectable)
+            .documentation(self)
+            .untagged(&self.parameters)
+            .extra(self)
+    }
+}
+
+impl FromTag for AnyType {
+    fn from_tag(node: Node) -> Option<Self> {
+        match node.tag() {
+            "type" => Some(Self::Type(Type::from_node(node))),
+            "array" => Some(Self::Array(Array::from_node(node))),
+            _ => None,
+        }
+    }
+}
+
+impl ToXml for AnyType {
+    fn to_xml(&self, _tag: &str) -> Element {
+        match self {
+            Self::Type(ty) => ty.to_xml("type"),
+            Self::Array(array) => array.to_xml("array"),
+        }
+    }
+}
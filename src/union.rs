@@ -1,87 +1,72 @@
-use xmlserde_derives::XmlDeserialize;
-
 use crate::{
     attribute::Attribute,
     callable::Callable,
     documentation::{DocDeprecated, DocStability, DocVersion, Documentation, SourcePosition},
+    extra::Extra,
     field::Field,
     function::{Function, FunctionInline},
     method::{Method, MethodInline},
     prelude::*,
     record::Record,
+    span::NodeSpan,
+    tree::{FromNode, FromTag, Node},
     version::Version,
+    writer::{Element, ToXml},
     Callback, Stability,
 };
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 // FIXME: The `Type` / `AnyType` fields are quite huge and some boxing would
-// probably be useful here but `xmlserde` does not seem to support that.
+// probably be useful here.
 #[allow(clippy::large_enum_variant)]
 pub enum UnionField {
-    #[xmlserde(name = b"field")]
     Field(Field),
-    #[xmlserde(name = b"union")]
     Union(Union),
-    #[xmlserde(name = b"record")]
     Record(Record),
-    #[xmlserde(name = b"callback")]
     Callback(Callback),
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, XmlDeserialize)]
-#[xmlserde(root = b"union")]
-#[xmlserde(deny_unknown_fields)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Union {
-    #[xmlserde(name = b"name", ty = "attr")]
     name: Option<String>,
-    #[xmlserde(name = b"c:type", ty = "attr")]
     c_type: Option<String>,
-    #[xmlserde(name = b"c:symbol-prefix", ty = "attr")]
     c_symbol_prefix: Option<String>,
-    #[xmlserde(name = b"glib:type-name", ty = "attr")]
     g_type_name: Option<String>,
-    #[xmlserde(name = b"glib:get-type", ty = "attr")]
     g_get_type: Option<String>,
-    #[xmlserde(name = b"copy-function", ty = "attr")]
     copy_function: Option<String>,
-    #[xmlserde(name = b"free-function", ty = "attr")]
     free_function: Option<String>,
     // Common attributes
-    #[xmlserde(name = b"introspectable", ty = "attr")]
     introspectable: Option<bool>,
-    #[xmlserde(name = b"deprecated", ty = "attr")]
     deprecated: Option<bool>,
-    #[xmlserde(name = b"version", ty = "attr")]
     version: Option<Version>,
-    #[xmlserde(name = b"deprecated-version", ty = "attr")]
     deprecated_version: Option<Version>,
-    #[xmlserde(name = b"stability", ty = "attr")]
     stability: Option<Stability>,
     // Documentation
-    #[xmlserde(name = b"doc", ty = "child")]
     doc: Option<Documentation>,
-    #[xmlserde(name = b"doc-deprecated", ty = "child")]
     doc_deprecated: Option<DocDeprecated>,
-    #[xmlserde(name = b"doc-stability", ty = "child")]
     doc_stability: Option<DocStability>,
-    #[xmlserde(name = b"doc-version", ty = "child")]
     doc_version: Option<DocVersion>,
-    #[xmlserde(name = b"source-position", ty = "child")]
     source_position: Option<SourcePosition>,
     // Attributes: 0 or more
-    #[xmlserde(name = b"attribute", ty = "child")]
     attributes: Vec<Attribute>,
 
-    #[xmlserde(name = b"function-inline", ty = "child")]
+    #[cfg_attr(feature = "serde", serde(rename = "inlined_functions"))]
     inline_functions: Vec<FunctionInline>,
 
-    #[xmlserde(name = b"method-inline", ty = "child")]
+    #[cfg_attr(feature = "serde", serde(rename = "inlined_methods"))]
     inline_methods: Vec<MethodInline>,
 
-    #[xmlserde(ty = "untag")]
     fields: Vec<UnionField>,
-    #[xmlserde(ty = "untag")]
     callables: Vec<Callable>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    span: NodeSpan,
+    extra: Option<Extra>,
 }
 
 impl Union {
@@ -154,3 +139,112 @@ impl Union {
 impl_info!(Union);
 impl_attributable!(Union);
 impl_documentable!(Union);
+
+impl FromTag for UnionField {
+    fn from_tag(node: Node) -> Option<Self> {
+        match node.tag() {
+            "field" => Some(Self::Field(Field::from_node(node))),
+            "union" => Some(Self::Union(Union::from_node(node))),
+            "record" => Some(Self::Record(Record::from_node(node))),
+            "callback" => Some(Self::Callback(Callback::from_node(node))),
+            _ => None,
+        }
+    }
+}
+
+impl ToXml for UnionField {
+    fn to_xml(&self, _tag: &str) -> Element {
+        match self {
+            Self::Field(f) => f.to_xml("field"),
+            Self::Union(u) => u.to_xml("union"),
+            Self::Record(r) => r.to_xml("record"),
+            Self::Callback(c) => c.to_xml("callback"),
+        }
+    }
+}
+
+impl_extensible!(Union);
+impl_spanned!(Union);
+impl_builder!(Union => UnionBuilder {
+    new {}
+    optional {
+        name: String => name, name_mut;
+        c_type: String => c_type, c_type_mut;
+        c_symbol_prefix: String => c_symbol_prefix, c_symbol_prefix_mut;
+        g_type_name: String => g_type_name, g_type_name_mut;
+        g_get_type: String => g_get_type, g_get_type_mut;
+        copy_function: String => copy_function, copy_function_mut;
+        free_function: String => free_function, free_function_mut;
+        introspectable: bool => introspectable, introspectable_mut;
+        deprecated: bool => deprecated, deprecated_mut;
+        version: Version => version, version_mut;
+        deprecated_version: Version => deprecated_version, deprecated_version_mut;
+        stability: Stability => stability, stability_mut;
+        doc: Documentation => doc, doc_mut;
+        doc_deprecated: DocDeprecated => doc_deprecated, doc_deprecated_mut;
+        doc_stability: DocStability => doc_stability, doc_stability_mut;
+        doc_version: DocVersion => doc_version, doc_version_mut;
+        source_position: SourcePosition => source_position, source_position_mut;
+    }
+    list {
+        attributes: Attribute => attribute, attributes_mut;
+        inline_functions: FunctionInline => inlined_function, inlined_functions_mut;
+        inline_methods: MethodInline => inlined_method, inlined_methods_mut;
+        fields: UnionField => field, fields_mut;
+        callables: Callable => callable, callables_mut;
+    }
+    value {}
+    hidden { span, extra }
+});
+
+impl FromNode for Union {
+    fn from_node(node: Node) -> Self {
+        Self {
+            name: node.opt_attr("name"),
+            c_type: node.opt_attr("c:type"),
+            c_symbol_prefix: node.opt_attr("c:symbol-prefix"),
+            g_type_name: node.opt_attr("glib:type-name"),
+            g_get_type: node.opt_attr("glib:get-type"),
+            copy_function: node.opt_attr("copy-function"),
+            free_function: node.opt_attr("free-function"),
+            introspectable: node.opt_attr("introspectable"),
+            deprecated: node.opt_attr("deprecated"),
+            version: node.opt_attr("version"),
+            deprecated_version: node.opt_attr("deprecated-version"),
+            stability: node.opt_attr("stability"),
+            doc: node.child("doc"),
+            doc_deprecated: node.child("doc-deprecated"),
+            doc_stability: node.child("doc-stability"),
+            doc_version: node.child("doc-version"),
+            source_position: node.child("source-position"),
+            attributes: node.children("attribute"),
+            inline_functions: node.children("function-inline"),
+            inline_methods: node.children("method-inline"),
+            fields: node.all_of(),
+            callables: node.all_of(),
+            span: node.span(),
+            extra: node.extra(),
+        }
+    }
+}
+
+impl ToXml for Union {
+    fn to_xml(&self, tag: &str) -> Element {
+        Element::new(tag)
+            .opt_attr("name", &self.name)
+            .opt_attr("c:type", &self.c_type)
+            .opt_attr("c:symbol-prefix", &self.c_symbol_prefix)
+            .opt_attr("glib:type-name", &self.g_type_name)
+            .opt_attr("glib:get-type", &self.g_get_type)
+            .opt_attr("copy-function", &self.copy_function)
+            .opt_attr("free-function", &self.free_function)
+            .info(self)
+            .documentation(self)
+            .attributes(self)
+            .untagged(&self.fields)
+            .untagged(&self.callables)
+            .children("function-inline", &self.inline_functions)
+            .children("method-inline", &self.inline_methods)
+            .extra(self)
+    }
+}
@@ -0,0 +1,328 @@
+//! Validation of a repository against the rules of `gir-1.2.rnc`, which are
+//! stricter than what deserializing a document checks.
+//!
+//! The nodes of the repository are validated along with the attributes and
+//! elements a lenient load kept without modelling them. The rules are the
+//! schema deserializing checks, along with where `gir-1.2.rnc` is stricter.
+
+use xmlserde::quick_xml::{
+    events::{BytesStart, Event},
+    Reader, XmlVersion,
+};
+
+use crate::{
+    schema::{Kind, Schema},
+    writer::{ToXml, Visit},
+    Repository, Span,
+};
+
+/// A rule of `gir-1.2.rnc` a repository breaks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    element_path: String,
+    message: String,
+    span: Option<Span>,
+}
+
+impl Violation {
+    /// The path to the offending element, in the format of
+    /// [`ParseError::element_path`](crate::ParseError::element_path).
+    pub fn element_path(&self) -> &str {
+        &self.element_path
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Where the offending element was parsed from, or its closest ancestor
+    /// for the elements that aren't modelled. Unknown for repositories parsed
+    /// without [`ParseOptions::spans`](crate::ParseOptions::spans).
+    pub fn span(&self) -> Option<&Span> {
+        self.span.as_ref()
+    }
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(span) = &self.span {
+            if let Some(file) = span.file() {
+                write!(f, "{}:", file.display())?;
+            }
+            write!(f, "{}:{}: ", span.line(), span.column())?;
+        }
+        write!(f, "{}: {}", self.element_path, self.message)
+    }
+}
+
+impl Repository {
+    /// Check the repository against `gir-1.2.rnc`, such as for missing
+    /// required attributes, elements out of place or extensions of the
+    /// format. Repositories loaded leniently can be validated as well.
+    ///
+    /// Attribute values are checked against their types, and against the
+    /// values `gir-1.2.rnc` allows where it's narrower, such as for
+    /// `xml:space`.
+    ///
+    /// The violations follow the order of the document, or the order of
+    /// [`Repository::to_xml`], which groups the nodes by kind, for
+    /// repositories parsed without
+    /// [`ParseOptions::spans`](crate::ParseOptions::spans).
+    pub fn validate(&self) -> Vec<Violation> {
+        let mut validator = Validator::default();
+        ToXml::to_xml(self, "repository").visit(&mut validator);
+        let mut violations = validator.violations;
+        violations.sort_by_key(|violation| violation.span.as_ref().map(Span::start));
+        violations
+    }
+}
+
+fn quoted(tags: &[&str], separator: &str) -> String {
+    tags.iter()
+        .map(|tag| format!("`{tag}`"))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+struct Frame {
+    tag: String,
+    segment: String,
+    /// The span of the element, or of its closest ancestor with one.
+    span: Option<Span>,
+    /// `None` for the elements outside of the schema, whose content isn't
+    /// checked.
+    kind: Option<Kind>,
+    children: Vec<(String, usize)>,
+}
+
+impl Frame {
+    fn count(&self, tags: &[&str]) -> usize {
+        self.children
+            .iter()
+            .filter(|(tag, _)| tags.contains(&tag.as_str()))
+            .map(|(_, count)| count)
+            .sum()
+    }
+}
+
+/// The kind of an element in any of the places it's allowed in, to check the
+/// elements out of place.
+fn element_kind(tag: &str) -> Option<Kind> {
+    let mut visited = Vec::new();
+    let mut queue = vec![Kind::ROOT];
+    while let Some((name, kind)) = queue.pop() {
+        if name == tag {
+            return Some(kind);
+        }
+        if !visited.contains(&kind) {
+            visited.push(kind);
+            queue.extend(kind.schema().children());
+        }
+    }
+    None
+}
+
+/// The groups of elements of which at most one can be present, where those
+/// of `gir-1.2.rnc` replace the ones of deserializing they cover.
+fn single_children(schema: &Schema) -> impl Iterator<Item = &'static [&'static str]> + '_ {
+    let spec = schema.spec().single_children;
+    spec.iter()
+        .copied()
+        .chain(schema.single_children().filter(move |tags| {
+            !spec
+                .iter()
+                .any(|group| tags.iter().all(|tag| group.contains(tag)))
+        }))
+}
+
+#[derive(Default)]
+struct Validator {
+    stack: Vec<Frame>,
+    violations: Vec<Violation>,
+}
+
+impl Validator {
+    fn start_tag(&mut self, start: &BytesStart) {
+        let tag = String::from_utf8_lossy(start.name().into_inner()).into_owned();
+        let mut attributes = Vec::new();
+        for attr in start.attributes().flatten() {
+            let key = String::from_utf8_lossy(attr.key.into_inner()).into_owned();
+            let value = attr
+                .normalized_value(XmlVersion::Implicit1_0)
+                .map(|value| value.into_owned())
+                .unwrap_or_default();
+            attributes.push((key, value));
+        }
+        self.start(&tag, &attributes, None);
+    }
+
+    fn element_path(&self) -> String {
+        // The repository is left out, as in parse errors.
+        self.stack
+            .iter()
+            .skip(1)
+            .map(|frame| frame.segment.as_str())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    fn report(&mut self, message: String) {
+        self.violations.push(Violation {
+            element_path: self.element_path(),
+            message,
+            span: self.stack.last().and_then(|frame| frame.span.clone()),
+        });
+    }
+}
+
+impl Visit for Validator {
+    fn start(&mut self, tag: &str, attributes: &[(String, String)], span: Option<&Span>) {
+        let tag = tag.to_owned();
+        let mut kind = None;
+        let mut position = 1;
+        let mut message = None;
+        match self.stack.last_mut() {
+            // Only the outermost unknown element is reported, not its content.
+            Some(parent) => {
+                if let Some(parent_kind) = parent.kind {
+                    position = match parent.children.iter_mut().find(|(t, _)| *t == tag) {
+                        Some((_, count)) => {
+                            *count += 1;
+                            *count
+                        }
+                        None => {
+                            parent.children.push((tag.clone(), 1));
+                            1
+                        }
+                    };
+                    let schema = parent_kind.schema();
+                    match parent_kind.child(&tag) {
+                        Some((_, child)) if !schema.spec().extensions.contains(&tag.as_str()) => {
+                            kind = Some(child);
+                        }
+                        child => {
+                            kind = child.map(|(_, kind)| kind).or_else(|| element_kind(&tag));
+                            message = Some(match kind {
+                                Some(_) => format!(
+                                    "`{tag}` elements aren't allowed in `{}` elements",
+                                    parent.tag
+                                ),
+                                None => format!("unknown element `{tag}`"),
+                            });
+                        }
+                    }
+                }
+            }
+            None if tag == Kind::ROOT.0 => kind = Some(Kind::ROOT.1),
+            None => {
+                message = Some(format!("expected a `repository` element, found `{tag}`"));
+            }
+        }
+        let span = span
+            .or_else(|| self.stack.last().and_then(|frame| frame.span.as_ref()))
+            .cloned();
+        let segment = match attributes.iter().find(|(key, _)| key == "name") {
+            _ if tag == "parameter" => format!("{tag}[{position}]"),
+            Some((_, name)) => format!("{tag}[@name={name}]"),
+            None => tag.clone(),
+        };
+        self.stack.push(Frame {
+            tag,
+            segment,
+            span,
+            kind,
+            children: Vec::new(),
+        });
+        if let Some(message) = message {
+            self.report(message);
+        }
+        let Some(kind) = kind else {
+            return;
+        };
+
+        let schema = kind.schema();
+        for (key, value) in attributes {
+            // Namespace declarations aren't attributes.
+            if key == "xmlns" || key.starts_with("xmlns:") {
+                continue;
+            }
+            match kind.attribute(key) {
+                Some(attr) if !attr.extension => {
+                    if let Err(e) = attr.check(value) {
+                        let mut message = format!("invalid value `{value}` for attribute `{key}`");
+                        if !e.is_empty() {
+                            message.push_str(&format!(": {e}"));
+                        }
+                        self.report(message);
+                    } else if let Some(values) = attr
+                        .spec_values
+                        .filter(|values| !values.contains(&value.as_str()))
+                    {
+                        self.report(format!(
+                            "invalid value `{value}` for attribute `{key}`: expected one of {}",
+                            quoted(values, ", ")
+                        ));
+                    }
+                }
+                _ => self.report(format!("unknown attribute `{key}`")),
+            }
+        }
+        for attr in schema.attributes() {
+            if (attr.required || attr.spec_required)
+                && !attributes.iter().any(|(key, _)| key == attr.name)
+            {
+                self.report(format!("missing required attribute `{}`", attr.name));
+            }
+        }
+    }
+
+    fn end(&mut self) {
+        let Some(kind) = self.stack.last().and_then(|frame| frame.kind) else {
+            self.stack.pop();
+            return;
+        };
+        let schema = kind.schema();
+        for tags in single_children(schema) {
+            let frame = self.stack.last().expect("element being validated");
+            if frame.count(tags) > 1 {
+                self.report(format!(
+                    "expected a single {} element",
+                    quoted(tags, " or ")
+                ));
+            }
+        }
+        let required_children = schema.required_children().iter();
+        for tags in required_children.chain(schema.spec().required_children) {
+            let frame = self.stack.last().expect("element being validated");
+            if frame.count(tags) == 0 {
+                self.report(format!("missing {} element", quoted(tags, " or ")));
+            }
+        }
+        self.stack.pop();
+    }
+
+    fn raw(&mut self, xml: &str) {
+        let mut reader = Reader::from_str(xml);
+        let depth = self.stack.len();
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(start)) => self.start_tag(&start),
+                Ok(Event::Empty(start)) => {
+                    self.start_tag(&start);
+                    self.end();
+                }
+                Ok(Event::End(_)) => self.end(),
+                Ok(Event::Eof) => break,
+                Ok(_) => {}
+                Err(e) => {
+                    self.report(format!("malformed element: {e}"));
+                    break;
+                }
+            }
+        }
+        // Close the elements of a truncated fragment.
+        while self.stack.len() > depth {
+            self.end();
+        }
+    }
+}
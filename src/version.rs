@@ -22,6 +22,16 @@ impl Version {
     pub fn patch(self) -> Option<u16> {
         self.2
     }
+
+    /// The version with a minor number and without a null patch number, so
+    /// `4`, `4.0` and `4.0.0` compare equal.
+    pub(crate) fn normalize(self) -> Self {
+        Self(
+            self.0,
+            Some(self.1.unwrap_or(0)),
+            self.2.filter(|patch| *patch != 0),
+        )
+    }
 }
 
 impl<'de> Deserialize<'de> for Version {
@@ -0,0 +1,426 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    callable::Callable,
+    constant::Constant,
+    function::FunctionInline,
+    method::MethodInline,
+    namespace::Namespace,
+    property::Property,
+    signal::Signal,
+    symbol_index::{Symbol, SymbolContainer},
+    traits::{Callable as _, Info},
+    version::Version,
+    virtual_method::VirtualMethod,
+};
+
+/// An element of a namespace with a version, which includes the properties,
+/// signals and virtual methods that have no C symbol of their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnySymbol<'a> {
+    Symbol(Symbol<'a>),
+    Property(&'a Property),
+    Signal(&'a Signal),
+    VirtualMethod(&'a VirtualMethod),
+}
+
+impl<'a> From<Symbol<'a>> for AnySymbol<'a> {
+    fn from(symbol: Symbol<'a>) -> Self {
+        Self::Symbol(symbol)
+    }
+}
+
+/// A symbol of a namespace along with the version it's available since.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionedSymbol<'a> {
+    name: String,
+    container: SymbolContainer<'a>,
+    symbol: AnySymbol<'a>,
+    version: Version,
+    declared_version: Option<&'a Version>,
+}
+
+impl<'a> VersionedSymbol<'a> {
+    /// The qualified name of the symbol, such as `Gtk.Widget.show`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn container(&self) -> SymbolContainer<'a> {
+        self.container
+    }
+
+    pub fn symbol(&self) -> AnySymbol<'a> {
+        self.symbol
+    }
+
+    /// The effective minimum version: the `version` attribute of the symbol,
+    /// or of its container if the symbol has none or an older one, and the
+    /// version of the namespace by default.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// The `version` attribute of the symbol itself.
+    pub fn declared_version(&self) -> Option<&'a Version> {
+        self.declared_version
+    }
+}
+
+/// A symbol declared as available before the type it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionConflict {
+    name: String,
+    version: Version,
+    container_name: String,
+    container_version: Version,
+}
+
+impl VersionConflict {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The `version` attribute of the symbol.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    pub fn container_name(&self) -> &str {
+        &self.container_name
+    }
+
+    /// The effective version of the container, which the symbol inherits.
+    pub fn container_version(&self) -> Version {
+        self.container_version
+    }
+}
+
+impl std::fmt::Display for VersionConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "`{}` is available since {}, before `{}` which is available since {}",
+            self.name, self.version, self.container_name, self.container_version
+        )
+    }
+}
+
+/// The symbols of a namespace grouped by the version they're available
+/// since, for example to gate them behind a feature per version.
+///
+/// Versions are normalized so `4`, `4.0` and `4.0.0` are the same.
+#[derive(Debug, Default)]
+pub struct VersionIndex<'a> {
+    baseline: Version,
+    entries: Vec<VersionedSymbol<'a>>,
+    versions: BTreeMap<Version, Vec<usize>>,
+    conflicts: Vec<VersionConflict>,
+}
+
+/// The members of a class or interface, of which records and unions only
+/// have the callables and inline functions and methods.
+#[derive(Default)]
+struct Members<'a> {
+    callables: &'a [Callable],
+    inlined_functions: &'a [FunctionInline],
+    inlined_methods: &'a [MethodInline],
+    virtual_methods: &'a [VirtualMethod],
+    properties: &'a [Property],
+    signals: &'a [Signal],
+    constants: &'a [Constant],
+}
+
+/// The container of the symbols being indexed, with its qualified name and
+/// effective version.
+#[derive(Clone, Copy)]
+struct Parent<'a, 'b> {
+    container: SymbolContainer<'a>,
+    name: &'b str,
+    version: Version,
+}
+
+impl<'a> VersionIndex<'a> {
+    pub fn new(namespace: &'a Namespace) -> Self {
+        let mut index = Self {
+            baseline: namespace.version().normalize(),
+            ..Default::default()
+        };
+        index.index_namespace(namespace);
+        index
+    }
+
+    /// The version of the namespace, that symbols without a version are
+    /// available since.
+    pub fn baseline(&self) -> Version {
+        self.baseline
+    }
+
+    pub fn symbols(&self) -> &[VersionedSymbol<'a>] {
+        &self.entries
+    }
+
+    /// The effective versions of the symbols, in increasing order.
+    pub fn versions(&self) -> impl Iterator<Item = Version> + '_ {
+        self.versions.keys().copied()
+    }
+
+    /// The symbols first available in `version`, those a feature for that
+    /// version would gate.
+    pub fn since(&self, version: Version) -> impl Iterator<Item = &VersionedSymbol<'a>> {
+        self.versions
+            .get(&version.normalize())
+            .into_iter()
+            .flatten()
+            .map(|i| &self.entries[*i])
+    }
+
+    /// The symbols declared as available before their container.
+    pub fn conflicts(&self) -> &[VersionConflict] {
+        &self.conflicts
+    }
+
+    /// Index a symbol and return its effective version.
+    fn add(
+        &mut self,
+        parent: Parent<'a, '_>,
+        name: &str,
+        symbol: impl Into<AnySymbol<'a>>,
+        info: &'a impl Info,
+    ) -> Version {
+        let declared_version = info.version();
+        let mut version = parent.version;
+        if let Some(declared) = declared_version.map(|v| v.normalize()) {
+            let is_type = !matches!(parent.container, SymbolContainer::Namespace(_));
+            if declared < parent.version && is_type {
+                self.conflicts.push(VersionConflict {
+                    name: format!("{}.{name}", parent.name),
+                    version: declared,
+                    container_name: parent.name.to_owned(),
+                    container_version: parent.version,
+                });
+            }
+            version = version.max(declared);
+        }
+        let position = self.entries.len();
+        self.entries.push(VersionedSymbol {
+            name: format!("{}.{name}", parent.name),
+            container: parent.container,
+            symbol: symbol.into(),
+            version,
+            declared_version,
+        });
+        self.versions.entry(version).or_default().push(position);
+        version
+    }
+
+    fn index_namespace(&mut self, ns: &'a Namespace) {
+        let root = Parent {
+            container: SymbolContainer::Namespace(ns),
+            name: ns.name(),
+            version: self.baseline,
+        };
+
+        for alias in ns.aliases() {
+            self.add(root, alias.name(), Symbol::Alias(alias), alias);
+        }
+        for callback in ns.callbacks() {
+            self.add(root, callback.name(), Symbol::Callback(callback), callback);
+        }
+        for class in ns.classes() {
+            let version = self.add(root, class.name(), Symbol::Class(class), class);
+            let name = format!("{}.{}", root.name, class.name());
+            let parent = Parent {
+                container: SymbolContainer::Class(class),
+                name: &name,
+                version,
+            };
+            let members = Members {
+                callables: class.callables(),
+                inlined_functions: class.inlined_functions(),
+                inlined_methods: class.inlined_methods(),
+                virtual_methods: class.virtual_methods(),
+                properties: class.properties(),
+                signals: class.signals(),
+                constants: class.constants(),
+            };
+            self.index_members(parent, members);
+        }
+        for interface in ns.interfaces() {
+            let symbol = Symbol::Interface(interface);
+            let version = self.add(root, interface.name(), symbol, interface);
+            let name = format!("{}.{}", root.name, interface.name());
+            let parent = Parent {
+                container: SymbolContainer::Interface(interface),
+                name: &name,
+                version,
+            };
+            let members = Members {
+                callables: interface.callables(),
+                inlined_functions: interface.inlined_functions(),
+                inlined_methods: interface.inlined_methods(),
+                virtual_methods: interface.virtual_methods(),
+                properties: interface.properties(),
+                signals: interface.signals(),
+                constants: interface.constants(),
+            };
+            self.index_members(parent, members);
+        }
+        // Anonymous records and unions are only reachable through the fields
+        // of another type.
+        for record in ns.records() {
+            let Some(record_name) = record.name() else {
+                continue;
+            };
+            let version = self.add(root, record_name, Symbol::Record(record), record);
+            let name = format!("{}.{record_name}", root.name);
+            let parent = Parent {
+                container: SymbolContainer::Record(record),
+                name: &name,
+                version,
+            };
+            let members = Members {
+                callables: record.callables(),
+                inlined_functions: record.inlined_functions(),
+                inlined_methods: record.inlined_methods(),
+                ..Default::default()
+            };
+            self.index_members(parent, members);
+        }
+        for union in ns.unions() {
+            let Some(union_name) = union.name() else {
+                continue;
+            };
+            let version = self.add(root, union_name, Symbol::Union(union), union);
+            let name = format!("{}.{union_name}", root.name);
+            let parent = Parent {
+                container: SymbolContainer::Union(union),
+                name: &name,
+                version,
+            };
+            let members = Members {
+                callables: union.callables(),
+                inlined_functions: union.inlined_functions(),
+                inlined_methods: union.inlined_methods(),
+                ..Default::default()
+            };
+            self.index_members(parent, members);
+        }
+        for enumeration in ns.enums() {
+            let symbol = Symbol::Enumeration(enumeration);
+            let version = self.add(root, enumeration.name(), symbol, enumeration);
+            let name = format!("{}.{}", root.name, enumeration.name());
+            let parent = Parent {
+                container: SymbolContainer::Enumeration(enumeration),
+                name: &name,
+                version,
+            };
+            for member in enumeration.members() {
+                self.add(parent, member.name(), Symbol::Member(member), member);
+            }
+            for function in enumeration.functions() {
+                self.add(
+                    parent,
+                    function.name(),
+                    Symbol::Function(function),
+                    function,
+                );
+            }
+        }
+        for bitfield in ns.flags() {
+            let version = self.add(root, bitfield.name(), Symbol::BitField(bitfield), bitfield);
+            let name = format!("{}.{}", root.name, bitfield.name());
+            let parent = Parent {
+                container: SymbolContainer::BitField(bitfield),
+                name: &name,
+                version,
+            };
+            for member in bitfield.members() {
+                self.add(parent, member.name(), Symbol::Member(member), member);
+            }
+            for function in bitfield.functions() {
+                self.add(
+                    parent,
+                    function.name(),
+                    Symbol::Function(function),
+                    function,
+                );
+            }
+        }
+        for boxed in ns.boxed() {
+            let version = self.add(root, boxed.g_name(), Symbol::Boxed(boxed), boxed);
+            let name = format!("{}.{}", root.name, boxed.g_name());
+            let parent = Parent {
+                container: SymbolContainer::Boxed(boxed),
+                name: &name,
+                version,
+            };
+            for function in boxed.functions() {
+                self.add(
+                    parent,
+                    function.name(),
+                    Symbol::Function(function),
+                    function,
+                );
+            }
+        }
+        for constant in ns.constants() {
+            self.add(root, constant.name(), Symbol::Constant(constant), constant);
+        }
+        for function in ns.functions() {
+            self.add(root, function.name(), Symbol::Function(function), function);
+        }
+        for function in ns.inlined_functions() {
+            self.add(
+                root,
+                function.name(),
+                Symbol::FunctionInline(function),
+                function,
+            );
+        }
+        for function in ns.macros() {
+            self.add(
+                root,
+                function.name(),
+                Symbol::FunctionMacro(function),
+                function,
+            );
+        }
+    }
+
+    fn index_members(&mut self, parent: Parent<'a, '_>, members: Members<'a>) {
+        self.index_callables(parent, members.callables);
+        for function in members.inlined_functions {
+            let symbol = Symbol::FunctionInline(function);
+            self.add(parent, function.name(), symbol, function);
+        }
+        for method in members.inlined_methods {
+            self.add(parent, method.name(), Symbol::MethodInline(method), method);
+        }
+        for method in members.virtual_methods {
+            let symbol = AnySymbol::VirtualMethod(method);
+            self.add(parent, method.name(), symbol, method);
+        }
+        for property in members.properties {
+            let symbol = AnySymbol::Property(property);
+            self.add(parent, property.name(), symbol, property);
+        }
+        for signal in members.signals {
+            self.add(parent, signal.name(), AnySymbol::Signal(signal), signal);
+        }
+        for constant in members.constants {
+            let symbol = Symbol::Constant(constant);
+            self.add(parent, constant.name(), symbol, constant);
+        }
+    }
+
+    fn index_callables(&mut self, parent: Parent<'a, '_>, callables: &'a [Callable]) {
+        for callable in callables {
+            match callable {
+                Callable::Constructor(f) => self.add(parent, f.name(), Symbol::Constructor(f), f),
+                Callable::Function(f) => self.add(parent, f.name(), Symbol::Function(f), f),
+                Callable::Method(m) => self.add(parent, m.name(), Symbol::Method(m), m),
+            };
+        }
+    }
+}
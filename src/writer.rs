@@ -0,0 +1,207 @@
+use std::io::Write;
+
+use xmlserde::{
+    quick_xml::{
+        events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event},
+        Writer,
+    },
+    XmlValue,
+};
+
+use crate::{prelude::*, Span};
+
+/// An in-memory XML element, built by [`ToXml`] implementations and written
+/// out in one go so empty elements can be self-closed.
+#[derive(Debug)]
+pub(crate) struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Node>,
+    span: Option<Span>,
+}
+
+#[derive(Debug)]
+enum Node {
+    Element(Element),
+    Text(String),
+    /// An XML fragment written as is.
+    Raw(String),
+}
+
+impl Element {
+    pub(crate) fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            attributes: Vec::new(),
+            children: Vec::new(),
+            span: None,
+        }
+    }
+
+    pub(crate) fn attr(mut self, name: &str, value: &impl XmlValue) -> Self {
+        self.attributes.push((name.to_owned(), value.serialize()));
+        self
+    }
+
+    pub(crate) fn opt_attr(self, name: &str, value: &Option<impl XmlValue>) -> Self {
+        match value {
+            Some(value) => self.attr(name, value),
+            None => self,
+        }
+    }
+
+    pub(crate) fn text(mut self, text: &str) -> Self {
+        if !text.is_empty() {
+            self.children.push(Node::Text(text.to_owned()));
+        }
+        self
+    }
+
+    pub(crate) fn child(mut self, tag: &str, child: &impl ToXml) -> Self {
+        self.children.push(Node::Element(child.to_xml(tag)));
+        self
+    }
+
+    pub(crate) fn opt_child(self, tag: &str, child: Option<&impl ToXml>) -> Self {
+        match child {
+            Some(child) => self.child(tag, child),
+            None => self,
+        }
+    }
+
+    pub(crate) fn children<'a, T: ToXml + 'a>(
+        self,
+        tag: &str,
+        children: impl IntoIterator<Item = &'a T>,
+    ) -> Self {
+        children
+            .into_iter()
+            .fold(self, |element, child| element.child(tag, child))
+    }
+
+    /// Children whose tag is decided by the value itself, the equivalent of
+    /// the [`FromTag`](crate::tree::FromTag) enums on the parsing side.
+    pub(crate) fn untagged<'a, T: ToXml + 'a>(
+        self,
+        children: impl IntoIterator<Item = &'a T>,
+    ) -> Self {
+        self.children("", children)
+    }
+
+    pub(crate) fn info(self, info: &impl InfoAttrs) -> Self {
+        info.write_info_attrs(self)
+    }
+
+    pub(crate) fn callable(self, callable: &impl CallableAttrs) -> Self {
+        callable.write_callable_attrs(self)
+    }
+
+    pub(crate) fn documentation(self, documentable: &impl Documentable) -> Self {
+        self.opt_child("doc", documentable.doc())
+            .opt_child("doc-deprecated", documentable.doc_deprecated())
+            .opt_child("doc-stability", documentable.doc_stability())
+            .opt_child("doc-version", documentable.doc_version())
+            .opt_child("source-position", documentable.source_position())
+    }
+
+    pub(crate) fn attributes(self, attributable: &impl Attributable) -> Self {
+        self.children("attribute", attributable.attributes())
+    }
+
+    /// The attributes and children that aren't modelled, written after the
+    /// known ones, along with the span of the node.
+    pub(crate) fn extra(mut self, extensible: &(impl Extensible + Spanned)) -> Self {
+        self.attributes
+            .extend(extensible.extra_attributes().iter().cloned());
+        self.children.extend(
+            extensible
+                .extra_children()
+                .iter()
+                .map(|child| Node::Raw(child.clone())),
+        );
+        self.span(extensible)
+    }
+
+    /// Where the node was parsed from, which isn't written but reported by
+    /// [`Repository::validate`](crate::Repository::validate).
+    pub(crate) fn span(mut self, spanned: &impl Spanned) -> Self {
+        self.span = spanned.span().cloned();
+        self
+    }
+
+    /// Walk the element and its descendants in document order.
+    pub(crate) fn visit(&self, visitor: &mut impl Visit) {
+        visitor.start(&self.name, &self.attributes, self.span.as_ref());
+        for child in &self.children {
+            match child {
+                Node::Element(element) => element.visit(visitor),
+                Node::Text(_) => {}
+                Node::Raw(xml) => visitor.raw(xml),
+            }
+        }
+        visitor.end();
+    }
+
+    fn write(&self, writer: &mut Writer<Vec<u8>>) {
+        let start = BytesStart::new(self.name.as_str()).with_attributes(
+            self.attributes
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str())),
+        );
+        if self.children.is_empty() {
+            let _ = writer.write_event(Event::Empty(start));
+            return;
+        }
+        let _ = writer.write_event(Event::Start(start));
+        for child in &self.children {
+            match child {
+                Node::Element(element) => element.write(writer),
+                Node::Text(text) => {
+                    let _ = writer.write_event(Event::Text(BytesText::new(text)));
+                }
+                // Written on its own line like the elements around it, unlike
+                // text which would stop the indentation of the parent.
+                Node::Raw(xml) => {
+                    let _ = writer.write_indent();
+                    let _ = writer.get_mut().write_all(xml.as_bytes());
+                }
+            }
+        }
+        let _ = writer.write_event(Event::End(BytesEnd::new(self.name.as_str())));
+    }
+
+    /// Render the element as a standalone XML document.
+    pub(crate) fn to_document(&self) -> String {
+        let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+        let _ = writer.write_event(Event::Decl(BytesDecl::new("1.0", None, None)));
+        self.write(&mut writer);
+        let mut output = String::from_utf8(writer.into_inner()).expect("XML output is UTF-8");
+        output.push('\n');
+        output
+    }
+}
+
+/// A visitor of the elements built by [`ToXml`] implementations.
+pub(crate) trait Visit {
+    fn start(&mut self, tag: &str, attributes: &[(String, String)], span: Option<&Span>);
+    /// An XML fragment written as is, such as an element that isn't modelled.
+    fn raw(&mut self, xml: &str);
+    fn end(&mut self);
+}
+
+pub(crate) trait ToXml {
+    /// Build the element for `self`, using `tag` as its name. Enums ignore the
+    /// tag and use the one of the variant instead.
+    fn to_xml(&self, tag: &str) -> Element;
+}
+
+/// Raw access to the common attributes of [`Info`] types, which the public
+/// trait only exposes with their default values applied.
+pub(crate) trait InfoAttrs {
+    fn write_info_attrs(&self, element: Element) -> Element;
+}
+
+/// Raw access to the attributes shared by [`Callable`] types.
+pub(crate) trait CallableAttrs {
+    fn write_callable_attrs(&self, element: Element) -> Element;
+}